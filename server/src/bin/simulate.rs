@@ -0,0 +1,407 @@
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![deny(clippy::cargo)]
+
+// Project will not published on crates.io, so no need for fields "keywords" and "categories"
+#![allow(clippy::cargo_common_metadata)]
+
+use fastrand::Rng;
+use serde::Serialize;
+use std::{ collections::BTreeMap, env::args, thread::{ available_parallelism, scope } };
+use settlers_server::{
+  game::{
+    EXTENSION_PLAYERS, MAX_PLAYERS, MIN_PLAYERS, Difficulty, Event, Game, Phase, Setup,
+    board::Board,
+    dice::Dice,
+    generator::{ Constraints, GenerateError, Generator, Report, Template },
+    resources::{ Resource, Resources },
+    rules::Rules,
+    scenario::Scenario
+  },
+  helpers::exit_with_error
+};
+
+const USAGE: &str = "Usage: simulate [--games N] [--players N] [--difficulty easy|medium|hard] \
+[--dice standard|balanced|karma] [--victory-points N] [--seed N] [--format json|csv]";
+
+// Actions of single game, limit stops bots, which keep game in loop
+const ACTIONS_LIMIT: u32 = 100_000;
+
+// Generator constraints, which effect is compared, every set plays games with same seeds
+const CONSTRAINTS: [(&str, Constraints); 5] = [
+  ("none", Constraints {
+    separate_red_tokens: false, separate_terrains: false, max_vertex_pips: None
+  }),
+  ("separate_red_tokens", Constraints {
+    separate_red_tokens: true, separate_terrains: false, max_vertex_pips: None
+  }),
+  ("separate_terrains", Constraints {
+    separate_red_tokens: false, separate_terrains: true, max_vertex_pips: None
+  }),
+  ("max_vertex_pips_11", Constraints {
+    separate_red_tokens: false, separate_terrains: false, max_vertex_pips: Some(11)
+  }),
+  ("all", Constraints {
+    separate_red_tokens: true, separate_terrains: true, max_vertex_pips: Some(11)
+  })
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+  Json,
+  Csv
+}
+
+struct Options {
+  games: u32,
+  players: usize,
+  difficulty: Difficulty,
+  rules: Rules,
+  seed: u64,
+  format: Format
+}
+
+// Cards moved by every kind of flow, summed over games
+#[derive(Clone, Copy, Default)]
+struct Flow {
+  produced: Resources,
+  discarded: Resources,
+  stolen: Resources,
+  traded_to_bank: Resources,
+  traded_from_bank: Resources,
+  taken_by_cards: Resources
+}
+
+// Sums over games of single constraints set
+#[derive(Default)]
+struct Totals {
+  games: u32,
+  finished: u32,
+  // Games stopped by actions limit or without any accepted bot action
+  stalled: u32,
+  generation_failures: u32,
+  turns: u64,
+  terrain_attempts: u64,
+  token_attempts: u64,
+  wins: Vec<u32>,
+  flow: Flow
+}
+
+// Averages of single constraints set, flow is average cards count per game
+#[derive(Serialize)]
+struct Summary {
+  constraints: &'static str,
+  games: u32,
+  finished: u32,
+  stalled: u32,
+  generation_failures: u32,
+  average_turns: f64,
+  average_terrain_attempts: f64,
+  average_token_attempts: f64,
+  // Wins of every seat divided by finished games
+  win_rates: Vec<f64>,
+  flow: BTreeMap<&'static str, BTreeMap<&'static str, f64>>
+}
+
+fn main() {
+  let options = parse_options().unwrap_or_else(|err| exit_with_error(&format!("{err}\n{USAGE}")));
+
+  let summaries = CONSTRAINTS.iter()
+    .map(|(name, constraints)| summarize(name, &simulate(&options, *constraints)))
+    .collect::<Vec<_>>();
+
+  match options.format {
+    Format::Json => {
+      let json = serde_json::to_string_pretty(&summaries)
+        .unwrap_or_else(|err| exit_with_error(&format!("Serialize statistics error: {err}")));
+      println!("{json}");
+    },
+    Format::Csv => print_csv(&summaries, options.players)
+  }
+}
+
+fn parse_options() -> Result<Options, String> {
+  let mut options = Options {
+    games: 1000,
+    players: 4,
+    difficulty: Difficulty::Hard,
+    rules: Rules::new(Scenario::Base),
+    seed: Rng::new().u64(..),
+    format: Format::Json
+  };
+
+  let mut args = args().skip(1);
+  while let Some(name) = args.next() {
+    let value = args.next().ok_or_else(|| format!("Missing value of {name}"))?;
+    let invalid = || format!("Invalid value of {name}: {value}");
+
+    match name.as_str() {
+      "--games" => options.games = value.parse().map_err(|_| invalid())?,
+      "--players" => {
+        options.players = value.parse().ok()
+          .filter(|players| (MIN_PLAYERS..=MAX_PLAYERS).contains(players))
+          .ok_or_else(invalid)?;
+      },
+      "--difficulty" => {
+        options.difficulty = match value.as_str() {
+          "easy" => Difficulty::Easy,
+          "medium" => Difficulty::Medium,
+          "hard" => Difficulty::Hard,
+          _ => return Err(invalid())
+        };
+      },
+      "--dice" => {
+        options.rules.dice = match value.as_str() {
+          "standard" => Dice::Standard,
+          "balanced" => Dice::Balanced,
+          "karma" => Dice::Karma,
+          _ => return Err(invalid())
+        };
+      },
+      "--victory-points" => {
+        options.rules.victory_points = value.parse().map_err(|_| invalid())?;
+        if !options.rules.is_valid(Scenario::Base) {
+          return Err(invalid())
+        }
+      },
+      "--seed" => options.seed = value.parse().map_err(|_| invalid())?,
+      "--format" => {
+        options.format = match value.as_str() {
+          "json" => Format::Json,
+          "csv" => Format::Csv,
+          _ => return Err(invalid())
+        };
+      },
+      _ => return Err(format!("Unknown option {name}"))
+    }
+  }
+
+  Ok(options)
+}
+
+// Games are split between threads, game of same number has same seeds with every constraints
+fn simulate(options: &Options, constraints: Constraints) -> Totals {
+  let threads = available_parallelism().map_or(1, usize::from);
+  let threads = u32::try_from(threads).unwrap_or(1).min(options.games.max(1));
+
+  let parts = scope(|scope| {
+    // Every thread is started before first one is joined
+    let mut handles = Vec::new();
+    for thread in 0..threads {
+      handles.push(scope.spawn(move || {
+        let mut totals = Totals { wins: vec![0; options.players], ..Totals::default() };
+        for number in (thread..options.games).step_by(threads as usize) {
+          play(options, constraints, number, &mut totals);
+        }
+        totals
+      }));
+    }
+
+    handles.into_iter()
+      .map(|handle| handle.join().unwrap_or_else(|_| exit_with_error("Simulation thread panic")))
+      .collect::<Vec<_>>()
+  });
+
+  let mut totals = Totals { wins: vec![0; options.players], ..Totals::default() };
+  for part in parts {
+    totals.merge(&part);
+  }
+  totals
+}
+
+fn play(options: &Options, constraints: Constraints, number: u32, totals: &mut Totals) {
+  let rng = Rng::with_seed(options.seed.wrapping_add(u64::from(number)));
+  let (board_seed, game_seed, bots_seed) = (rng.u64(..), rng.u64(..), rng.u64(..));
+
+  totals.games += 1;
+
+  let Ok((board, report)) = generate(options.players, constraints, board_seed) else {
+    totals.generation_failures += 1;
+    return
+  };
+  totals.terrain_attempts += u64::from(report.terrain_attempts);
+  totals.token_attempts += u64::from(report.token_attempts);
+
+  let mut game = Game::new(Setup {
+    scenario: Scenario::Base,
+    rules: options.rules,
+    board,
+    players: options.players,
+    seed: game_seed,
+    proof: None
+  });
+  for event in game.start_events() {
+    totals.flow.record(&event);
+  }
+
+  // Bots act as in lobby: first accepted action of first bot with decision is made
+  let bots_rng = Rng::with_seed(bots_seed);
+  for _ in 0..ACTIONS_LIMIT {
+    if game.is_finished() {
+      break
+    }
+
+    let events = (0..options.players).find_map(|seat| {
+      game.bot_actions(seat, options.difficulty, &bots_rng).into_iter()
+        .find_map(|action| game.apply(seat, action).ok())
+    });
+    let Some(events) = events else { break };
+
+    for event in &events {
+      totals.flow.record(event);
+    }
+  }
+
+  totals.turns += u64::from(game.turn());
+  match game.phase() {
+    Phase::Finished { winner } => {
+      totals.finished += 1;
+      totals.wins[winner] += 1;
+    },
+    _ => totals.stalled += 1
+  }
+}
+
+// Same templates as lobby uses for generated boards
+fn generate(
+  players: usize, constraints: Constraints, seed: u64
+) -> Result<(Board, Report), GenerateError> {
+  let extension = players >= EXTENSION_PLAYERS;
+  let template = if extension { Template::extension() } else { Template::standard() };
+  Generator::new(template, constraints).generate(seed)
+}
+
+impl Flow {
+  fn record(&mut self, event: &Event) {
+    match event {
+      Event::ResourcesProduced { resources, .. } => self.produced += *resources,
+      Event::Discarded { resources, .. } => self.discarded += *resources,
+      Event::CardStolen { resource: Some(resource), .. } => self.stolen.add(*resource, 1),
+      Event::MaritimeTraded { give, receive, .. } => {
+        self.traded_to_bank += *give;
+        self.traded_from_bank += *receive;
+      },
+      Event::YearOfPlentyTaken { resources, .. } => self.taken_by_cards += *resources,
+      Event::MonopolyTaken { resource, count, .. } => self.taken_by_cards.add(*resource, *count),
+      _ => {}
+    }
+  }
+
+  fn merge(&mut self, other: &Self) {
+    self.produced += other.produced;
+    self.discarded += other.discarded;
+    self.stolen += other.stolen;
+    self.traded_to_bank += other.traded_to_bank;
+    self.traded_from_bank += other.traded_from_bank;
+    self.taken_by_cards += other.taken_by_cards;
+  }
+
+  const fn kinds(&self) -> [(&'static str, Resources); 6] {
+    [
+      ("produced", self.produced),
+      ("discarded", self.discarded),
+      ("stolen", self.stolen),
+      ("traded_to_bank", self.traded_to_bank),
+      ("traded_from_bank", self.traded_from_bank),
+      ("taken_by_cards", self.taken_by_cards)
+    ]
+  }
+}
+
+impl Totals {
+  fn merge(&mut self, other: &Self) {
+    self.games += other.games;
+    self.finished += other.finished;
+    self.stalled += other.stalled;
+    self.generation_failures += other.generation_failures;
+    self.turns += other.turns;
+    self.terrain_attempts += other.terrain_attempts;
+    self.token_attempts += other.token_attempts;
+    for (wins, other) in self.wins.iter_mut().zip(&other.wins) {
+      *wins += other;
+    }
+    self.flow.merge(&other.flow);
+  }
+}
+
+fn summarize(constraints: &'static str, totals: &Totals) -> Summary {
+  // Games without board are not played, so they are not counted in averages
+  let played = totals.games - totals.generation_failures;
+
+  Summary {
+    constraints,
+    games: totals.games,
+    finished: totals.finished,
+    stalled: totals.stalled,
+    generation_failures: totals.generation_failures,
+    average_turns: average(totals.turns, played),
+    average_terrain_attempts: average(totals.terrain_attempts, played),
+    average_token_attempts: average(totals.token_attempts, played),
+    win_rates: totals.wins.iter()
+      .map(|wins| average(u64::from(*wins), totals.finished))
+      .collect(),
+    flow: totals.flow.kinds().into_iter()
+      .map(|(kind, resources)| {
+        let counts = Resource::ALL.into_iter()
+          .map(|resource| {
+            (resource_name(resource), average(u64::from(resources.get(resource)), played))
+          })
+          .collect();
+        (kind, counts)
+      })
+      .collect()
+  }
+}
+
+// Sums never exceed f64 precision in simulations of any practical size
+#[allow(clippy::cast_precision_loss)]
+fn average(sum: u64, count: u32) -> f64 {
+  if count == 0 { 0.0 } else { sum as f64 / f64::from(count) }
+}
+
+const fn resource_name(resource: Resource) -> &'static str {
+  match resource {
+    Resource::Brick => "brick",
+    Resource::Lumber => "lumber",
+    Resource::Ore => "ore",
+    Resource::Grain => "grain",
+    Resource::Wool => "wool",
+    Resource::Paper => "paper",
+    Resource::Cloth => "cloth",
+    Resource::Coin => "coin"
+  }
+}
+
+// Single row of every constraints set, flow columns are named as kind and resource
+fn print_csv(summaries: &[Summary], players: usize) {
+  let mut header = vec![
+    "constraints", "games", "finished", "stalled", "generation_failures", "average_turns",
+    "average_terrain_attempts", "average_token_attempts"
+  ].into_iter().map(String::from).collect::<Vec<_>>();
+  header.extend((0..players).map(|seat| format!("win_rate_seat_{seat}")));
+  if let Some(summary) = summaries.first() {
+    for (kind, counts) in &summary.flow {
+      header.extend(counts.keys().map(|resource| format!("{kind}_{resource}")));
+    }
+  }
+  println!("{}", header.join(","));
+
+  for summary in summaries {
+    let mut row = vec![
+      summary.constraints.to_string(),
+      summary.games.to_string(),
+      summary.finished.to_string(),
+      summary.stalled.to_string(),
+      summary.generation_failures.to_string(),
+      format!("{:.2}", summary.average_turns),
+      format!("{:.2}", summary.average_terrain_attempts),
+      format!("{:.2}", summary.average_token_attempts)
+    ];
+    row.extend(summary.win_rates.iter().map(|rate| format!("{rate:.4}")));
+    for counts in summary.flow.values() {
+      row.extend(counts.values().map(|count| format!("{count:.2}")));
+    }
+    println!("{}", row.join(","));
+  }
+}
\ No newline at end of file
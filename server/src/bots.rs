@@ -0,0 +1,69 @@
+use sea_orm::{ ActiveModelTrait, ActiveValue::Set, DatabaseConnection, DbErr, TransactionTrait };
+use sha2::{ Digest, Sha256 };
+use std::fmt::{ Display, Formatter, Result as FmtResult, Write };
+use crate::db::entities::{ auth_method::{ self, Method as AuthMethod }, user };
+
+// Random bytes of bot token, token is given to bot owner as hex string
+const TOKEN_SIZE: usize = 32;
+// Length limit of users names column
+const MAX_NAME_LENGTH: usize = 32;
+
+#[derive(Debug)]
+pub enum CreateError {
+  InvalidName,
+  Random(getrandom::Error),
+  Db(DbErr)
+}
+
+impl Display for CreateError {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    match self {
+      Self::InvalidName => write!(f, "name must have from 1 to {MAX_NAME_LENGTH} characters"),
+      Self::Random(err) => write!(f, "generate token error: {err}"),
+      Self::Db(err) => write!(f, "database error: {err}")
+    }
+  }
+}
+
+// Only hash of token is stored, so leaked database does not give bots tokens
+pub fn token_hash(token: &str) -> String {
+  hex(&Sha256::digest(token.as_bytes()))
+}
+
+// Bot account user with new token, token is returned once and can not be restored
+pub async fn create(db: &DatabaseConnection, name: &str) -> Result<String, CreateError> {
+  if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+    return Err(CreateError::InvalidName)
+  }
+
+  let mut secret = [0; TOKEN_SIZE];
+  getrandom::getrandom(&mut secret).map_err(CreateError::Random)?;
+  let token = hex(&secret);
+
+  let transaction = db.begin().await.map_err(CreateError::Db)?;
+
+  let user = user::ActiveModel {
+    name: Set(name.to_string()),
+    picture: Set(String::new()),
+    tag: Set(0),
+    ..Default::default()
+  }.insert(&transaction).await.map_err(CreateError::Db)?;
+
+  auth_method::ActiveModel {
+    user_id: Set(user.id),
+    method: Set(AuthMethod::Bot),
+    data: Set(token_hash(&token))
+  }.insert(&transaction).await.map_err(CreateError::Db)?;
+
+  transaction.commit().await.map_err(CreateError::Db)?;
+
+  Ok(token)
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+    // Writing to string never fails
+    let _ = write!(hex, "{byte:02x}");
+    hex
+  })
+}
\ No newline at end of file
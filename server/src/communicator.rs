@@ -3,13 +3,27 @@ use log::debug;
 use std::{ collections::HashMap, sync::Arc };
 use tokio::sync::{ mpsc::{ UnboundedReceiver, UnboundedSender, unbounded_channel }, Mutex };
 
-pub type Data = (u32, String);
+// Connected client, bot accounts are authenticated by token on connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Client {
+  Player,
+  // User id of bot account
+  Bot(u32)
+}
+
+// Message of peer with its client kind, or closed connection of peer
+#[derive(Debug)]
+pub enum Data {
+  Message(u32, Client, Vec<u8>),
+  Closed(u32)
+}
+
 pub type Sender = UnboundedSender<Data>;
 pub type Receiver = UnboundedReceiver<Data>;
 
 pub struct Communicator {
   rng: Rng,
-  peers: HashMap<u32, UnboundedSender<String>>,
+  peers: HashMap<u32, UnboundedSender<Vec<u8>>>,
   sender: Sender
 }
 
@@ -26,7 +40,7 @@ impl Communicator {
     (Arc::new(Mutex::new(communicator)), receiver)
   }
 
-  pub fn add(&mut self) -> (u32, Sender, UnboundedReceiver<String>) {
+  pub fn add(&mut self) -> (u32, Sender, UnboundedReceiver<Vec<u8>>) {
     let (peer_sender, peer_receiver) = unbounded_channel();
 
     let id = self.generate_id();
@@ -36,11 +50,17 @@ impl Communicator {
     (id, self.sender.clone(), peer_receiver)
   }
 
+  // Closed connection is passed after all its messages and before its id can be given again,
+  // so next peer with same id never gets seat of previous one
   pub fn remove(&mut self, id: u32) {
     self.peers.remove(&id);
+
+    if let Err(err) = self.sender.send(Data::Closed(id)) {
+      debug!("Send closed peer {id} error: {err}");
+    }
   }
 
-  pub fn send(&self, id: u32, data: String) -> bool {
+  pub fn send(&self, id: u32, data: Vec<u8>) -> bool {
     let Some(sender) = self.peers.get(&id) else { return false };
 
     if let Err(err) = sender.send(data) {
@@ -51,6 +71,11 @@ impl Communicator {
     }
   }
 
+  // Send same data to every peer, return ids of peers, which did not receive it
+  pub fn broadcast(&self, ids: &[u32], data: &[u8]) -> Vec<u32> {
+    ids.iter().copied().filter(|id| !self.send(*id, data.to_vec())).collect()
+  }
+
   fn generate_id(&self) -> u32 {
     loop {
       let random = self.rng.u32(..);
@@ -10,7 +10,9 @@ use sea_orm::{
 pub enum Method {
   Password = 0,
   Email = 1,
-  Telegram = 2
+  Telegram = 2,
+  // Bot account, data is SHA-256 hash of its token as hex string
+  Bot = 3
 }
 
 #[derive(Clone, Debug, DeriveEntityModel)]
@@ -0,0 +1,28 @@
+use sea_orm::{
+  ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter,
+  PrimaryKeyTrait
+};
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "games")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub id: u32,
+  // Room id, which players know game by, it is unique only among unfinished games
+  #[sea_orm(indexed)]
+  pub room: u32,
+  // Encoded GameSetup message
+  pub setup: Vec<u8>,
+  // Encoded GameSeats message, owners of seats take them again after restart
+  pub seats: Vec<u8>,
+  // Unix time in seconds, signed, because SQLite has no unsigned 64-bit integers
+  pub started: i64,
+  // Only unfinished games are restored on server start
+  #[sea_orm(indexed)]
+  pub finished: bool
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use sea_orm::{
+  ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter,
+  PrimaryKeyTrait
+};
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "games_actions")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub game_id: u32,
+  // Position of action in game log, starting from 0
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub number: u32,
+  pub seat: u8,
+  // Encoded ClientMessage message
+  pub action: Vec<u8>,
+  // Undone actions stay in log for audit, but they are skipped on replay
+  pub undone: bool
+}
+
+#[derive(Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "super::game::Entity",
+    from = "Column::GameId",
+    to = "super::game::Column::Id"
+  )]
+  Game
+}
+
+impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
@@ -1,3 +1,5 @@
 pub mod auth_method;
 pub mod auth_session;
+pub mod game;
+pub mod game_action;
 pub mod user;
\ No newline at end of file
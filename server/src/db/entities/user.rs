@@ -16,19 +16,6 @@ pub struct Model {
 }
 
 #[derive(Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {
-  #[sea_orm(
-    belongs_to = "super::auth_method::Entity",
-    from = "Column::Id",
-    to = "super::auth_method::Column::UserId"
-  )]
-  AuthMethod,
-  #[sea_orm(
-    belongs_to = "super::auth_session::Entity",
-    from = "Column::Id",
-    to = "super::auth_session::Column::UserId"
-  )]
-  AuthSession
-}
+pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
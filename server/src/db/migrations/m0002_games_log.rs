@@ -0,0 +1,18 @@
+use sea_orm::DeriveMigrationName;
+use sea_orm_migration::{ async_trait::async_trait, manager::SchemaManager, MigrationTrait };
+use super::{ MigrationResult, structure_from_entity };
+use crate::db::entities::{ game::Entity as Game, game_action::Entity as GameAction };
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> MigrationResult {
+    // Actions reference games, so games table is created first
+    structure_from_entity(manager, Game).await?;
+    structure_from_entity(manager, GameAction).await?;
+
+    Ok(())
+  }
+}
\ No newline at end of file
@@ -0,0 +1,22 @@
+use sea_orm::{ DeriveMigrationName, EntityTrait };
+use sea_orm_migration::{ async_trait::async_trait, manager::SchemaManager, MigrationTrait };
+use sea_query::Table;
+use super::{ MigrationResult, structure_from_entity };
+use crate::db::entities::user::Entity as User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> MigrationResult {
+    // Users table had foreign keys to not unique user id columns of auth tables, so database
+    // rejects every insert to it, table is always empty and is created again without them
+    if User::find().one(manager.get_connection()).await?.is_none() {
+      manager.drop_table(Table::drop().table(User).to_owned()).await?;
+      structure_from_entity(manager, User).await?;
+    }
+
+    Ok(())
+  }
+}
\ No newline at end of file
@@ -1,4 +1,6 @@
 mod m0001_initial_structure;
+mod m0002_games_log;
+mod m0003_users_without_auth_keys;
 
 use sea_orm::{ schema::Schema, EntityTrait };
 use sea_orm_migration::{
@@ -13,7 +15,11 @@ pub struct Migrator;
 #[async_trait]
 impl MigratorTrait for Migrator {
   fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-    vec![Box::new(m0001_initial_structure::Migration)]
+    vec![
+      Box::new(m0001_initial_structure::Migration),
+      Box::new(m0002_games_log::Migration),
+      Box::new(m0003_users_without_auth_keys::Migration)
+    ]
   }
 }
 
@@ -0,0 +1,150 @@
+use super::{
+  board::{ Edge, Hex, Vertex },
+  cards::{ ProgressCard, Track },
+  engine::MAX_PLAYERS,
+  resources::{ Resource, Resources }
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+  RollDice,
+  BuildRoad(Edge),
+  BuildSettlement(Vertex),
+  BuildCity(Vertex),
+  EndTurn,
+  Discard(Resources),
+  // Victim must be chosen, if any opponent with cards has building next to hex
+  MoveRobber { hex: Hex, victim: Option<usize> },
+  BuyDevelopmentCard,
+  PlayKnight,
+  PlayRoadBuilding,
+  PlayYearOfPlenty([Resource; 2]),
+  PlayMonopoly(Resource),
+  // Trade terms are always from active player point of view
+  OfferTrade { give: Resources, receive: Resources },
+  AcceptTrade,
+  RejectTrade,
+  CounterTrade { give: Resources, receive: Resources },
+  ConfirmTrade { partner: usize },
+  CancelTrade,
+  // Trade with bank at best player ratio for one card of other resource
+  MaritimeTrade { give: Resource, receive: Resource },
+  BuildShip(Edge),
+  MoveShip { from: Edge, to: Edge },
+  ChooseGold(Resources),
+  BuildCityWall(Vertex),
+  ImproveCity(Track),
+  BuildKnight(Vertex),
+  PromoteKnight(Vertex),
+  ActivateKnight(Vertex),
+  // Active knight moves along own roads, it may displace weaker opponent knight
+  MoveKnight { from: Vertex, to: Vertex },
+  // Active knight next to robber hex lets player move robber
+  ChaseRobber(Vertex),
+  PlayProgressCard { card: ProgressCard, target: Target },
+  // Cards of own choice given to active player after wedding or commercial harbor
+  GiveCards(Resources),
+  // Knight of own choice removed by victim of deserter
+  RemoveKnight(Vertex),
+  // Cards of own choice taken from shown hand of master merchant victim
+  TakeCards(Resources)
+}
+
+// Choice made with progress card, its kind depends on card
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+  None,
+  Dice([u8; 2]),
+  Hex(Hex),
+  Hexes(Hex, Hex),
+  Vertex(Vertex),
+  Resource(Resource),
+  Seat(usize),
+  // Smith promotes one or two knights
+  Vertices(Vertex, Option<Vertex>),
+  // Diplomat removes road at first edge, own removed road may be placed at second edge
+  Edges(Edge, Option<Edge>),
+  // Deserter victim, own knight of removed knight level is placed at vertex
+  SeatVertex(usize, Option<Vertex>),
+  // Commercial harbor resource offered to every seat, indexed by seat
+  Offers([Option<Resource>; MAX_PLAYERS])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+  NotYourTurn,
+  WrongPhase,
+  // Location is not on board
+  InvalidLocation,
+  Occupied,
+  // Settlement distance rule violated
+  TooClose,
+  NotConnected,
+  NotOwned,
+  NotEnoughResources,
+  NoPiecesLeft,
+  NothingToDiscard,
+  WrongDiscardCount,
+  InvalidVictim,
+  DeckEmpty,
+  NoCard,
+  // Only one development card may be played per turn
+  CardAlreadyPlayed,
+  NoLegalRoads,
+  NotEnoughInBank,
+  InvalidTrade,
+  NoOffer,
+  InvalidPartner,
+  // Partner neither accepted nor countered offer
+  NotAccepted,
+  PirateBlocks,
+  ShipAlreadyMoved,
+  // Ship is built in current turn, next to pirate or not last in shipping line
+  ShipNotMovable,
+  NothingToChoose,
+  WrongGoldCount,
+  NothingToGive,
+  WrongGiftCount,
+  // Commodity is given, where only resource is allowed
+  CommodityNotAllowed,
+  // Resource is given for commercial harbor, where only commodity is allowed
+  CommodityRequired,
+  // City is required for improvement or metropolis
+  NoCity,
+  // Improvement or knight can not be raised higher
+  MaxLevel,
+  KnightInactive,
+  KnightActive,
+  // Knight was already activated or promoted in current turn
+  KnightBusy,
+  KnightTooWeak,
+  // Robber stays in desert until first barbarian attack
+  RobberInactive,
+  InvalidTarget,
+  // Road has buildings, knights or other routes of its owner at both ends
+  RoadNotOpen,
+  // Last action is not own or it revealed hidden or random information
+  NotUndoable,
+  UndoPending,
+  NoUndoRequest,
+  AlreadyVoted
+}
+
+impl Action {
+  pub const fn cities_and_knights(self) -> bool {
+    matches!(
+      self,
+      Self::BuildCityWall(_)
+      | Self::ImproveCity(_)
+      | Self::BuildKnight(_)
+      | Self::PromoteKnight(_)
+      | Self::ActivateKnight(_)
+      | Self::MoveKnight { .. }
+      | Self::ChaseRobber(_)
+      | Self::PlayProgressCard { .. }
+      | Self::GiveCards(_)
+      | Self::RemoveKnight(_)
+      | Self::TakeCards(_)
+    )
+  }
+}
\ No newline at end of file
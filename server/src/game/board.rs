@@ -0,0 +1,570 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use super::resources::Resource;
+
+// Hexes use axial coordinates of "pointy top" grid, where `q` grows to east and `r` grows
+// to south-east, so third cube coordinate is `-q - r`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex {
+  pub q: i8,
+  pub r: i8
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+  NorthEast,
+  East,
+  SouthEast,
+  SouthWest,
+  West,
+  NorthWest
+}
+
+// Every vertex is shared by three hexes, but exactly one of them has it as north or south
+// corner, so vertex is stored in that canonical form
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Corner {
+  North,
+  South
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vertex {
+  pub hex: Hex,
+  pub corner: Corner
+}
+
+// Every edge is shared by two hexes, canonical form use only east half of hex sides
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Side {
+  NorthEast,
+  East,
+  SouthEast
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Edge {
+  pub hex: Hex,
+  pub side: Side
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Terrain {
+  Hills,
+  Forest,
+  Mountains,
+  Fields,
+  Pasture,
+  Desert,
+  // Seafarers terrains: sea carries ships and pirate, gold yields resource of choice
+  Sea,
+  Gold
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+  pub terrain: Terrain,
+  pub token: Option<u8>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HarborKind {
+  Generic,
+  Specific(Resource)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Harbor {
+  pub edge: Edge,
+  pub kind: HarborKind
+}
+
+#[derive(Clone, Debug)]
+pub struct Board {
+  // BTreeMap instead of HashMap to iterate tiles in same order on every run,
+  // it required for reproducible boards generation and game replays
+  tiles: BTreeMap<Hex, Tile>,
+  harbors: Vec<Harbor>,
+  robber: Hex,
+  // Pirate is placed on board only when it is moved first time
+  pirate: Option<Hex>
+}
+
+// Beginner setup from base game rules, rows from north to south
+const STANDARD_TILES: [(i8, i8, Terrain, Option<u8>); 19] = [
+  (0, -2, Terrain::Mountains, Some(10)),
+  (1, -2, Terrain::Pasture, Some(2)),
+  (2, -2, Terrain::Forest, Some(9)),
+  (-1, -1, Terrain::Fields, Some(12)),
+  (0, -1, Terrain::Hills, Some(6)),
+  (1, -1, Terrain::Pasture, Some(4)),
+  (2, -1, Terrain::Hills, Some(10)),
+  (-2, 0, Terrain::Fields, Some(9)),
+  (-1, 0, Terrain::Forest, Some(11)),
+  (0, 0, Terrain::Desert, None),
+  (1, 0, Terrain::Forest, Some(3)),
+  (2, 0, Terrain::Mountains, Some(8)),
+  (-2, 1, Terrain::Forest, Some(8)),
+  (-1, 1, Terrain::Mountains, Some(3)),
+  (0, 1, Terrain::Fields, Some(4)),
+  (1, 1, Terrain::Pasture, Some(5)),
+  (-2, 2, Terrain::Hills, Some(5)),
+  (-1, 2, Terrain::Fields, Some(6)),
+  (0, 2, Terrain::Pasture, Some(11))
+];
+
+// Harbors of base game frame clockwise from north-west, as hex and its side facing the sea
+const STANDARD_HARBORS: [(i8, i8, Direction, HarborKind); 9] = [
+  (0, -2, Direction::NorthWest, HarborKind::Generic),
+  (1, -2, Direction::NorthEast, HarborKind::Specific(Resource::Wool)),
+  (2, -1, Direction::East, HarborKind::Generic),
+  (2, 0, Direction::SouthEast, HarborKind::Generic),
+  (1, 1, Direction::SouthEast, HarborKind::Specific(Resource::Brick)),
+  (-1, 2, Direction::SouthEast, HarborKind::Specific(Resource::Lumber)),
+  (-2, 2, Direction::West, HarborKind::Generic),
+  (-2, 1, Direction::West, HarborKind::Specific(Resource::Grain)),
+  (-1, -1, Direction::NorthWest, HarborKind::Specific(Resource::Ore))
+];
+
+// Harbors of 5-6 player extension frame clockwise from north-west
+const EXTENSION_HARBORS: [(i8, i8, Direction); 11] = [
+  (0, -3, Direction::NorthWest),
+  (1, -3, Direction::NorthEast),
+  (2, -2, Direction::East),
+  (2, 0, Direction::East),
+  (1, 1, Direction::SouthEast),
+  (0, 2, Direction::SouthEast),
+  (-2, 3, Direction::SouthEast),
+  (-3, 3, Direction::West),
+  (-3, 1, Direction::West),
+  (-3, 0, Direction::NorthWest),
+  (-1, -2, Direction::NorthWest)
+];
+
+impl Direction {
+  pub const ALL: [Self; 6] = [
+    Self::NorthEast, Self::East, Self::SouthEast, Self::SouthWest, Self::West, Self::NorthWest
+  ];
+
+  const fn offset(self) -> (i8, i8) {
+    match self {
+      Self::NorthEast => (1, -1),
+      Self::East => (1, 0),
+      Self::SouthEast => (0, 1),
+      Self::SouthWest => (-1, 1),
+      Self::West => (-1, 0),
+      Self::NorthWest => (0, -1)
+    }
+  }
+}
+
+impl Hex {
+  pub const fn new(q: i8, r: i8) -> Self {
+    Self { q, r }
+  }
+
+  const fn offset(self, q: i8, r: i8) -> Self {
+    Self { q: self.q + q, r: self.r + r }
+  }
+
+  pub const fn neighbour(self, direction: Direction) -> Self {
+    let (q, r) = direction.offset();
+    self.offset(q, r)
+  }
+
+  pub fn neighbours(self) -> [Self; 6] {
+    Direction::ALL.map(|direction| self.neighbour(direction))
+  }
+
+  pub fn distance(self, other: Self) -> u8 {
+    let q = i16::from(self.q) - i16::from(other.q);
+    let r = i16::from(self.r) - i16::from(other.r);
+    let distance = (q.abs() + r.abs() + (q + r).abs()) / 2;
+    // Coordinates are i8, so distance is never more than u8::MAX
+    u8::try_from(distance).unwrap_or(u8::MAX)
+  }
+
+  // Corners clockwise from north
+  pub const fn vertices(self) -> [Vertex; 6] {
+    [
+      Vertex::new(self, Corner::North),
+      Vertex::new(self.offset(1, -1), Corner::South),
+      Vertex::new(self.offset(0, 1), Corner::North),
+      Vertex::new(self, Corner::South),
+      Vertex::new(self.offset(-1, 1), Corner::North),
+      Vertex::new(self.offset(0, -1), Corner::South)
+    ]
+  }
+
+  // Sides in `Direction::ALL` order
+  pub fn edges(self) -> [Edge; 6] {
+    Direction::ALL.map(|direction| Edge::new(self, direction))
+  }
+}
+
+impl Vertex {
+  pub const fn new(hex: Hex, corner: Corner) -> Self {
+    Self { hex, corner }
+  }
+
+  pub const fn hexes(self) -> [Hex; 3] {
+    let hex = self.hex;
+    match self.corner {
+      Corner::North => [hex, hex.offset(0, -1), hex.offset(1, -1)],
+      Corner::South => [hex, hex.offset(-1, 1), hex.offset(0, 1)]
+    }
+  }
+
+  pub const fn edges(self) -> [Edge; 3] {
+    let hex = self.hex;
+    match self.corner {
+      Corner::North => [
+        Edge { hex, side: Side::NorthEast },
+        Edge { hex: hex.offset(0, -1), side: Side::SouthEast },
+        Edge { hex: hex.offset(0, -1), side: Side::East }
+      ],
+      Corner::South => [
+        Edge { hex, side: Side::SouthEast },
+        Edge { hex: hex.offset(-1, 1), side: Side::NorthEast },
+        Edge { hex: hex.offset(-1, 1), side: Side::East }
+      ]
+    }
+  }
+
+  pub fn neighbours(self) -> [Self; 3] {
+    self.edges().map(|edge| edge.other_vertex(self))
+  }
+}
+
+impl Edge {
+  pub const fn new(hex: Hex, direction: Direction) -> Self {
+    match direction {
+      Direction::NorthEast => Self { hex, side: Side::NorthEast },
+      Direction::East => Self { hex, side: Side::East },
+      Direction::SouthEast => Self { hex, side: Side::SouthEast },
+      Direction::SouthWest => Self { hex: hex.offset(-1, 1), side: Side::NorthEast },
+      Direction::West => Self { hex: hex.offset(-1, 0), side: Side::East },
+      Direction::NorthWest => Self { hex: hex.offset(0, -1), side: Side::SouthEast }
+    }
+  }
+
+  pub const fn vertices(self) -> [Vertex; 2] {
+    let hex = self.hex;
+    match self.side {
+      Side::NorthEast => [
+        Vertex::new(hex, Corner::North), Vertex::new(hex.offset(1, -1), Corner::South)
+      ],
+      Side::East => [
+        Vertex::new(hex.offset(1, -1), Corner::South), Vertex::new(hex.offset(0, 1), Corner::North)
+      ],
+      Side::SouthEast => [
+        Vertex::new(hex.offset(0, 1), Corner::North), Vertex::new(hex, Corner::South)
+      ]
+    }
+  }
+
+  pub const fn hexes(self) -> [Hex; 2] {
+    let hex = self.hex;
+    match self.side {
+      Side::NorthEast => [hex, hex.offset(1, -1)],
+      Side::East => [hex, hex.offset(1, 0)],
+      Side::SouthEast => [hex, hex.offset(0, 1)]
+    }
+  }
+
+  // Passed vertex must be one of edge vertices, otherwise first vertex returned
+  pub fn other_vertex(self, vertex: Vertex) -> Vertex {
+    let [first, second] = self.vertices();
+    if first == vertex { second } else { first }
+  }
+
+  // Edges which share vertex with current edge
+  pub fn neighbours(self) -> Vec<Self> {
+    self.vertices().iter()
+      .flat_map(|vertex| vertex.edges())
+      .filter(|edge| *edge != self)
+      .collect()
+  }
+}
+
+impl Terrain {
+  pub const fn resource(self) -> Option<Resource> {
+    match self {
+      Self::Hills => Some(Resource::Brick),
+      Self::Forest => Some(Resource::Lumber),
+      Self::Mountains => Some(Resource::Ore),
+      Self::Fields => Some(Resource::Grain),
+      Self::Pasture => Some(Resource::Wool),
+      Self::Desert | Self::Sea | Self::Gold => None
+    }
+  }
+
+  // Producing terrains get number token, gold produces resource of choice
+  pub const fn has_token(self) -> bool {
+    !matches!(self, Self::Desert | Self::Sea)
+  }
+}
+
+// Count of two dice combinations giving number, which also printed on token as dots
+pub const fn pips(token: u8) -> u8 {
+  match token {
+    2..=6 => token - 1,
+    8..=12 => 13 - token,
+    _ => 0
+  }
+}
+
+impl Board {
+  pub const fn new(tiles: BTreeMap<Hex, Tile>, harbors: Vec<Harbor>, robber: Hex) -> Self {
+    Self { tiles, harbors, robber, pirate: None }
+  }
+
+  pub fn standard() -> Self {
+    let tiles = STANDARD_TILES.iter()
+      .map(|(q, r, terrain, token)| (Hex::new(*q, *r), Tile { terrain: *terrain, token: *token }))
+      .collect::<BTreeMap<Hex, Tile>>();
+
+    let robber = tiles.iter()
+      .find(|(_, tile)| tile.terrain == Terrain::Desert)
+      .map_or(Hex::new(0, 0), |(hex, _)| *hex);
+
+    Self::new(tiles, standard_harbor_edges().into_iter().zip(STANDARD_HARBORS.iter())
+      .map(|(edge, (_, _, _, kind))| Harbor { edge, kind: *kind })
+      .collect(), robber)
+  }
+
+  pub fn tile(&self, hex: Hex) -> Option<&Tile> {
+    self.tiles.get(&hex)
+  }
+
+  pub fn tiles(&self) -> impl Iterator<Item = (&Hex, &Tile)> {
+    self.tiles.iter()
+  }
+
+  pub fn harbors(&self) -> &[Harbor] {
+    &self.harbors
+  }
+
+  pub const fn robber(&self) -> Hex {
+    self.robber
+  }
+
+  pub const fn set_robber(&mut self, hex: Hex) {
+    self.robber = hex;
+  }
+
+  // Caller must check that both hexes are on board
+  pub fn swap_tokens(&mut self, first: Hex, second: Hex) {
+    let first_token = self.tiles.get(&first).and_then(|tile| tile.token);
+    let second_token = self.tiles.get(&second).and_then(|tile| tile.token);
+
+    if let Some(tile) = self.tiles.get_mut(&first) {
+      tile.token = second_token;
+    }
+    if let Some(tile) = self.tiles.get_mut(&second) {
+      tile.token = first_token;
+    }
+  }
+
+  pub const fn pirate(&self) -> Option<Hex> {
+    self.pirate
+  }
+
+  pub const fn set_pirate(&mut self, hex: Hex) {
+    self.pirate = Some(hex);
+  }
+
+  pub fn is_land(&self, hex: Hex) -> bool {
+    self.tiles.get(&hex).is_some_and(|tile| tile.terrain != Terrain::Sea)
+  }
+
+  pub fn is_sea(&self, hex: Hex) -> bool {
+    self.tiles.get(&hex).is_some_and(|tile| tile.terrain == Terrain::Sea)
+  }
+
+  // Vertex exists on board if it touches at least one land tile
+  pub fn has_vertex(&self, vertex: Vertex) -> bool {
+    vertex.hexes().iter().any(|hex| self.is_land(*hex))
+  }
+
+  // Edge exists on board if it borders at least one land tile
+  pub fn has_edge(&self, edge: Edge) -> bool {
+    edge.hexes().iter().any(|hex| self.is_land(*hex))
+  }
+
+  // Ship may be placed on edge, if it borders at least one sea tile
+  pub fn has_sea_edge(&self, edge: Edge) -> bool {
+    edge.hexes().iter().any(|hex| self.is_sea(*hex))
+  }
+
+  // Island is identified by its least land hex, None for sea and hexes out of board
+  pub fn island(&self, hex: Hex) -> Option<Hex> {
+    if !self.is_land(hex) {
+      return None
+    }
+
+    let mut island = BTreeSet::from([hex]);
+    let mut frontier = vec![hex];
+    while let Some(current) = frontier.pop() {
+      for neighbour in current.neighbours() {
+        if self.is_land(neighbour) && island.insert(neighbour) {
+          frontier.push(neighbour);
+        }
+      }
+    }
+
+    island.first().copied()
+  }
+
+  // Land hexes around vertex are always neighbours, so they belong to same island
+  pub fn vertex_island(&self, vertex: Vertex) -> Option<Hex> {
+    vertex.hexes().into_iter().find_map(|hex| self.island(hex))
+  }
+
+  pub fn vertices(&self) -> BTreeSet<Vertex> {
+    self.tiles.keys().flat_map(|hex| hex.vertices()).collect()
+  }
+
+  pub fn edges(&self) -> BTreeSet<Edge> {
+    self.tiles.keys().flat_map(|hex| hex.edges()).collect()
+  }
+
+  // Land tiles around vertex
+  pub fn vertex_tiles(&self, vertex: Vertex) -> impl Iterator<Item = (Hex, &Tile)> {
+    vertex.hexes().into_iter().filter_map(|hex| self.tiles.get(&hex).map(|tile| (hex, tile)))
+  }
+
+  pub fn vertex_harbor(&self, vertex: Vertex) -> Option<HarborKind> {
+    self.harbors.iter()
+      .find(|harbor| harbor.edge.vertices().contains(&vertex))
+      .map(|harbor| harbor.kind)
+  }
+}
+
+pub fn standard_harbor_edges() -> Vec<Edge> {
+  STANDARD_HARBORS.iter()
+    .map(|(q, r, direction, _)| Edge::new(Hex::new(*q, *r), *direction))
+    .collect()
+}
+
+pub fn extension_harbor_edges() -> Vec<Edge> {
+  EXTENSION_HARBORS.iter()
+    .map(|(q, r, direction)| Edge::new(Hex::new(*q, *r), *direction))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::generator::Template;
+
+  const HEX: Hex = Hex::new(1, -1);
+
+  #[test]
+  fn hex_vertices_are_distinct_corners_of_hex() {
+    let vertices = HEX.vertices();
+
+    assert_eq!(vertices.iter().collect::<BTreeSet<_>>().len(), 6);
+    for vertex in vertices {
+      assert!(vertex.hexes().contains(&HEX));
+    }
+  }
+
+  #[test]
+  fn vertex_belongs_to_every_hex_around_it() {
+    for vertex in HEX.vertices() {
+      let hexes = vertex.hexes();
+      assert_eq!(hexes.iter().collect::<BTreeSet<_>>().len(), 3);
+      for hex in hexes {
+        assert!(hex.vertices().contains(&vertex));
+      }
+    }
+  }
+
+  #[test]
+  fn vertex_edges_end_at_vertex() {
+    for vertex in HEX.vertices() {
+      let edges = vertex.edges();
+      assert_eq!(edges.iter().collect::<BTreeSet<_>>().len(), 3);
+      for edge in edges {
+        assert!(edge.vertices().contains(&vertex));
+      }
+    }
+  }
+
+  #[test]
+  fn vertex_neighbours_are_adjacent_both_ways() {
+    for vertex in HEX.vertices() {
+      for (edge, neighbour) in vertex.edges().into_iter().zip(vertex.neighbours()) {
+        assert_ne!(neighbour, vertex);
+        assert_eq!(edge.other_vertex(vertex), neighbour);
+        assert!(neighbour.neighbours().contains(&vertex));
+      }
+    }
+  }
+
+  #[test]
+  fn edge_vertices_are_consecutive_hex_corners() {
+    let vertices = HEX.vertices();
+
+    for (index, edge) in HEX.edges().into_iter().enumerate() {
+      let [first, second] = edge.vertices();
+      assert_ne!(first, second);
+      assert!(first.edges().contains(&edge) && second.edges().contains(&edge));
+
+      // Side of every direction lies between corners clockwise from north
+      let corners = [vertices[index], vertices[(index + 1) % 6]];
+      assert!(corners.contains(&first) && corners.contains(&second));
+    }
+  }
+
+  #[test]
+  fn neighbouring_hexes_share_side_and_its_corners() {
+    for (direction, edge) in Direction::ALL.into_iter().zip(HEX.edges()) {
+      let neighbour = HEX.neighbour(direction);
+      assert_eq!(Edge::new(neighbour, opposite(direction)), edge);
+      assert!(edge.hexes().contains(&HEX) && edge.hexes().contains(&neighbour));
+
+      let own = HEX.vertices();
+      let shared = neighbour.vertices().into_iter()
+        .filter(|vertex| own.contains(vertex))
+        .collect::<BTreeSet<_>>();
+      assert_eq!(shared, edge.vertices().into_iter().collect());
+    }
+  }
+
+  #[test]
+  fn standard_board_has_all_vertices_and_edges() {
+    let board = Board::standard();
+    assert_eq!(board.vertices().len(), 54);
+    assert_eq!(board.edges().len(), 72);
+  }
+
+  #[test]
+  fn harbors_face_sea() {
+    let faces_sea = |hexes: &[Hex], edge: Edge| {
+      edge.hexes().iter().filter(|hex| hexes.contains(hex)).count() == 1
+    };
+
+    let board = Board::standard();
+    let land = board.tiles().map(|(hex, _)| *hex).collect::<Vec<_>>();
+    for harbor in board.harbors() {
+      assert!(faces_sea(&land, harbor.edge));
+    }
+
+    let extension = Template::extension();
+    for edge in extension.harbor_edges {
+      assert!(faces_sea(&extension.hexes, edge));
+    }
+  }
+
+  const fn opposite(direction: Direction) -> Direction {
+    match direction {
+      Direction::NorthEast => Direction::SouthWest,
+      Direction::East => Direction::West,
+      Direction::SouthEast => Direction::NorthWest,
+      Direction::SouthWest => Direction::NorthEast,
+      Direction::West => Direction::East,
+      Direction::NorthWest => Direction::SouthEast
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,167 @@
+use super::resources::Resource;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevelopmentCard {
+  Knight,
+  RoadBuilding,
+  YearOfPlenty,
+  Monopoly,
+  VictoryPoint
+}
+
+// Base game deck composition
+const DECK: [(DevelopmentCard, usize); 5] = [
+  (DevelopmentCard::Knight, 14),
+  (DevelopmentCard::RoadBuilding, 2),
+  (DevelopmentCard::YearOfPlenty, 2),
+  (DevelopmentCard::Monopoly, 2),
+  (DevelopmentCard::VictoryPoint, 5)
+];
+
+// Cards added by 5-6 player extension
+const EXTENSION_DECK: [(DevelopmentCard, usize); 4] = [
+  (DevelopmentCard::Knight, 6),
+  (DevelopmentCard::RoadBuilding, 1),
+  (DevelopmentCard::YearOfPlenty, 1),
+  (DevelopmentCard::Monopoly, 1)
+];
+
+// Unshuffled deck, top card is last
+pub fn deck(extension: bool) -> Vec<DevelopmentCard> {
+  let extension_cards = if extension { &EXTENSION_DECK[..] } else { &[] };
+  DECK.iter()
+    .chain(extension_cards)
+    .flat_map(|(card, count)| [*card].repeat(*count))
+    .collect()
+}
+
+// City improvement tracks of Cities & Knights, every track has own commodity and progress deck
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Track {
+  Trade,
+  Politics,
+  Science
+}
+
+impl Track {
+  pub const ALL: [Self; 3] = [Self::Trade, Self::Politics, Self::Science];
+
+  pub const fn commodity(self) -> Resource {
+    match self {
+      Self::Trade => Resource::Cloth,
+      Self::Politics => Resource::Coin,
+      Self::Science => Resource::Paper
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressCard {
+  Alchemist,
+  Crane,
+  Engineer,
+  Inventor,
+  Irrigation,
+  Medicine,
+  Mining,
+  Printer,
+  RoadBuilding,
+  Smith,
+  CommercialHarbor,
+  Merchant,
+  MerchantFleet,
+  MasterMerchant,
+  ResourceMonopoly,
+  TradeMonopoly,
+  Bishop,
+  Constitution,
+  Intrigue,
+  Deserter,
+  Diplomat,
+  Saboteur,
+  Spy,
+  Warlord,
+  Wedding
+}
+
+impl ProgressCard {
+  pub const fn track(self) -> Track {
+    match self {
+      Self::Alchemist
+      | Self::Crane
+      | Self::Engineer
+      | Self::Inventor
+      | Self::Irrigation
+      | Self::Medicine
+      | Self::Mining
+      | Self::Printer
+      | Self::RoadBuilding
+      | Self::Smith => Track::Science,
+      Self::CommercialHarbor
+      | Self::Merchant
+      | Self::MerchantFleet
+      | Self::MasterMerchant
+      | Self::ResourceMonopoly
+      | Self::TradeMonopoly => Track::Trade,
+      Self::Bishop
+      | Self::Constitution
+      | Self::Deserter
+      | Self::Diplomat
+      | Self::Intrigue
+      | Self::Saboteur
+      | Self::Spy
+      | Self::Warlord
+      | Self::Wedding => Track::Politics
+    }
+  }
+
+  // Victory point cards are revealed, when drawn, and never held in hand
+  pub const fn is_victory_point(self) -> bool {
+    matches!(self, Self::Printer | Self::Constitution)
+  }
+}
+
+// Progress decks composition, every deck has 18 cards
+const SCIENCE_DECK: [(ProgressCard, usize); 10] = [
+  (ProgressCard::Alchemist, 2),
+  (ProgressCard::Crane, 2),
+  (ProgressCard::Engineer, 1),
+  (ProgressCard::Inventor, 2),
+  (ProgressCard::Irrigation, 2),
+  (ProgressCard::Medicine, 2),
+  (ProgressCard::Mining, 2),
+  (ProgressCard::Printer, 1),
+  (ProgressCard::RoadBuilding, 2),
+  (ProgressCard::Smith, 2)
+];
+
+const TRADE_DECK: [(ProgressCard, usize); 6] = [
+  (ProgressCard::CommercialHarbor, 2),
+  (ProgressCard::Merchant, 6),
+  (ProgressCard::MerchantFleet, 2),
+  (ProgressCard::MasterMerchant, 2),
+  (ProgressCard::ResourceMonopoly, 4),
+  (ProgressCard::TradeMonopoly, 2)
+];
+
+const POLITICS_DECK: [(ProgressCard, usize); 9] = [
+  (ProgressCard::Bishop, 2),
+  (ProgressCard::Constitution, 1),
+  (ProgressCard::Deserter, 2),
+  (ProgressCard::Diplomat, 2),
+  (ProgressCard::Intrigue, 2),
+  (ProgressCard::Saboteur, 2),
+  (ProgressCard::Spy, 3),
+  (ProgressCard::Warlord, 2),
+  (ProgressCard::Wedding, 2)
+];
+
+// Unshuffled progress deck of track, top card is last
+pub fn progress_deck(track: Track) -> Vec<ProgressCard> {
+  let cards = match track {
+    Track::Trade => &TRADE_DECK[..],
+    Track::Politics => &POLITICS_DECK[..],
+    Track::Science => &SCIENCE_DECK[..]
+  };
+  cards.iter().flat_map(|(card, count)| [*card].repeat(*count)).collect()
+}
\ No newline at end of file
@@ -0,0 +1,144 @@
+use fastrand::Rng;
+use std::fmt::Debug;
+
+// Balanced deck is reshuffled, when this count of cards is left, so last rolls stay unknown
+const RESHUFFLE_AT: usize = 5;
+// Rolls remembered by karma dice and weight of outcome, which sum was not rolled recently,
+// weight is halved for every recent roll of same sum
+const KARMA_MEMORY: usize = 6;
+const KARMA_WEIGHT: u32 = 8;
+
+// Dice house rule, chosen by host
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dice {
+  #[default]
+  Standard,
+  // Outcomes are drawn from deck of all 36 outcomes, as event cards
+  Balanced,
+  // Balanced deck, which draws recently rolled sums less likely, so streaks are dampened
+  Karma
+}
+
+impl Dice {
+  pub fn source(self) -> Box<dyn DiceSource> {
+    match self {
+      Self::Standard => Box::new(StandardDice),
+      Self::Balanced => Box::new(BalancedDice::new(false)),
+      Self::Karma => Box::new(BalancedDice::new(true))
+    }
+  }
+}
+
+// Rolls of two dice, game random generator is given, so rolls are rebuilt from game seed
+pub trait DiceSource: Debug + Send {
+  fn roll(&mut self, rng: &Rng) -> [u8; 2];
+
+  // Same source in its initial state, game is rebuilt with it on undo
+  fn restart(&self) -> Box<dyn DiceSource>;
+}
+
+#[derive(Debug)]
+pub struct StandardDice;
+
+impl DiceSource for StandardDice {
+  fn roll(&mut self, rng: &Rng) -> [u8; 2] {
+    [rng.u8(1..=6), rng.u8(1..=6)]
+  }
+
+  fn restart(&self) -> Box<dyn DiceSource> {
+    Box::new(Self)
+  }
+}
+
+#[derive(Debug)]
+pub struct BalancedDice {
+  karma: bool,
+  deck: Vec<[u8; 2]>,
+  // Last rolls, newest last
+  recent: Vec<[u8; 2]>
+}
+
+impl BalancedDice {
+  pub const fn new(karma: bool) -> Self {
+    // Deck is filled on first roll
+    Self { karma, deck: Vec::new(), recent: Vec::new() }
+  }
+
+  // Weight of every card in deck, recently rolled sums have less weight, but never zero
+  fn weights(&self) -> Vec<u32> {
+    self.deck.iter()
+      .map(|[first, second]| {
+        let sum = first + second;
+        let repeats = self.recent.iter().filter(|[first, second]| first + second == sum).count();
+        (KARMA_WEIGHT >> repeats).max(1)
+      })
+      .collect()
+  }
+}
+
+impl DiceSource for BalancedDice {
+  fn roll(&mut self, rng: &Rng) -> [u8; 2] {
+    if self.deck.len() <= RESHUFFLE_AT {
+      self.deck = (1..=6).flat_map(|first| (1..=6).map(move |second| [first, second])).collect();
+      rng.shuffle(&mut self.deck);
+    }
+
+    let index = if self.karma {
+      let weights = self.weights();
+      let mut target = rng.u32(..weights.iter().sum::<u32>());
+      weights.iter()
+        .position(|weight| {
+          if target < *weight { return true }
+          target -= weight;
+          false
+        })
+        .unwrap_or(0)
+    } else {
+      self.deck.len() - 1
+    };
+
+    let dice = self.deck.swap_remove(index);
+
+    if self.karma {
+      if self.recent.len() == KARMA_MEMORY {
+        self.recent.remove(0);
+      }
+      self.recent.push(dice);
+    }
+
+    dice
+  }
+
+  fn restart(&self) -> Box<dyn DiceSource> {
+    Box::new(Self::new(self.karma))
+  }
+}
+
+// Given rolls in loop, so engine tests go same way with any seed
+#[cfg(test)]
+#[derive(Debug)]
+pub struct ScriptedDice {
+  rolls: Vec<[u8; 2]>,
+  next: usize
+}
+
+#[cfg(test)]
+impl ScriptedDice {
+  // Rolls must not be empty, every die must be from 1 to 6
+  pub const fn new(rolls: Vec<[u8; 2]>) -> Self {
+    Self { rolls, next: 0 }
+  }
+}
+
+#[cfg(test)]
+impl DiceSource for ScriptedDice {
+  fn roll(&mut self, _rng: &Rng) -> [u8; 2] {
+    let dice = self.rolls[self.next % self.rolls.len()];
+    self.next += 1;
+    dice
+  }
+
+  fn restart(&self) -> Box<dyn DiceSource> {
+    Box::new(Self::new(self.rolls.clone()))
+  }
+}
\ No newline at end of file
@@ -0,0 +1,454 @@
+use fastrand::Rng;
+use std::cmp::Reverse;
+use crate::game::{
+  action::Action,
+  board::{ Edge, Hex, Vertex, pips },
+  cards::DevelopmentCard,
+  resources::{
+    Resource, Resources, CITY_COST, DEVELOPMENT_CARD_COST, ROAD_COST, SETTLEMENT_COST
+  }
+};
+use super::{ BuildingKind, Game, Phase, progress::GIFT_CARDS, timeout::random_cards };
+
+// Value of resource, which player does not produce yet, in tenths of pip
+const DIVERSITY_VALUE: u32 = 15;
+const HARBOR_VALUE: u32 = 10;
+// Hard bots do not help players this close to victory
+const LEADER_MARGIN: u32 = 2;
+
+// Strength of server bot, all of them play by same rules and see only own hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+  // Random locations and robber, no trades and no development cards played
+  Easy,
+  // Placement by pips and resource diversity, builds by priority, targets robber and
+  // chases it with knights
+  Medium,
+  // Also weighs resource scarcity and harbors, trades with bank and plays cards
+  Hard
+}
+
+impl Game {
+  // Candidate actions of bot seat in preference order, first accepted one is made,
+  // empty when game does not wait for this seat
+  pub fn bot_actions(&self, seat: usize, difficulty: Difficulty, rng: &Rng) -> Vec<Action> {
+    if let Some((give, receive)) = self.unanswered_offer(seat) {
+      let accept = self.bot_accepts(seat, difficulty, give, receive, rng);
+      return vec![if accept { Action::AcceptTrade } else { Action::RejectTrade }]
+    }
+
+    match self.phase {
+      Phase::Discard { .. } if self.discards[seat] > 0 => {
+        let hand = self.players[seat].resources;
+        vec![Action::Discard(self.bot_discard(seat, hand, self.discards[seat], difficulty, rng))]
+      },
+      Phase::Gold if self.gold[seat] > 0 => {
+        let mut bank = Resources::default();
+        for resource in Resource::ALL {
+          bank.add(resource, self.bank.get(resource));
+        }
+        vec![Action::ChooseGold(self.bot_choice(seat, bank, self.gold[seat], difficulty, rng))]
+      },
+      Phase::Gift if self.gifts[seat] > 0 => {
+        let hand = self.giftable(seat);
+        vec![Action::GiveCards(self.bot_discard(seat, hand, self.gifts[seat], difficulty, rng))]
+      },
+      // Weakest knights are removed first
+      Phase::Desertion { victim, .. } if seat == victim => {
+        let mut knights = self.own_knights(seat);
+        knights.sort_by_key(|vertex| {
+          self.knights.get(vertex).map(|knight| (knight.level, knight.active))
+        });
+        knights.into_iter().map(Action::RemoveKnight).collect()
+      },
+      _ if seat != self.active => Vec::new(),
+      Phase::Setup { settlement: None, .. } => {
+        self.ranked_vertices(seat, difficulty, false, rng).into_iter()
+          .flat_map(|vertex| [Action::BuildSettlement(vertex), Action::BuildCity(vertex)])
+          .collect()
+      },
+      Phase::Setup { settlement: Some(settlement), .. } => {
+        let mut edges = settlement.edges().to_vec();
+        if difficulty == Difficulty::Easy {
+          rng.shuffle(&mut edges);
+        } else {
+          edges.sort_by_cached_key(|edge| Reverse(self.edge_value(seat, *edge, difficulty)));
+        }
+        edges.into_iter()
+          .flat_map(|edge| [Action::BuildRoad(edge), Action::BuildShip(edge)])
+          .collect()
+      },
+      Phase::Roll => {
+        let mut actions = Vec::with_capacity(2);
+        if difficulty != Difficulty::Easy && self.robber_blocks(seat) {
+          actions.push(Action::PlayKnight);
+        }
+        actions.push(Action::RollDice);
+        actions
+      },
+      // Only building is allowed in special building phase, other actions are rejected
+      Phase::Main | Phase::SpecialBuilding { .. } => {
+        let mut actions = self.bot_builds(seat, difficulty, rng);
+        actions.push(Action::EndTurn);
+        actions
+      },
+      Phase::Robber => self.bot_robber(seat, difficulty, rng),
+      Phase::RoadBuilding { .. } => {
+        self.ranked_edges(seat, difficulty, rng).into_iter().map(Action::BuildRoad).collect()
+      },
+      Phase::Taking { victim } => {
+        let hand = self.players[victim].resources;
+        let count = hand.total().min(GIFT_CARDS);
+        vec![Action::TakeCards(self.bot_choice(seat, hand, count, difficulty, rng))]
+      },
+      Phase::Discard { .. }
+      | Phase::Gold
+      | Phase::Gift
+      | Phase::Desertion { .. }
+      | Phase::Finished { .. } => Vec::new()
+    }
+  }
+
+  // Builds and trades in priority order, bot saves for goal, when nothing is affordable
+  fn bot_builds(&self, seat: usize, difficulty: Difficulty, rng: &Rng) -> Vec<Action> {
+    let mut actions = Vec::new();
+
+    if difficulty != Difficulty::Easy && self.robber_blocks(seat) {
+      actions.push(Action::PlayKnight);
+    }
+
+    let mut cities = self.own_settlements(seat);
+    if difficulty == Difficulty::Easy {
+      rng.shuffle(&mut cities);
+    } else {
+      cities.sort_by_cached_key(|vertex| Reverse(self.vertex_value(seat, *vertex, difficulty)));
+    }
+    actions.extend(cities.into_iter().map(Action::BuildCity));
+
+    let settlements = self.ranked_vertices(seat, difficulty, true, rng);
+    // Roads are built only to reach new settlement locations
+    let expanding = settlements.is_empty() && self.players[seat].settlements > 0;
+    actions.extend(settlements.into_iter().map(Action::BuildSettlement));
+
+    if !self.scenario.cities_and_knights() {
+      actions.push(Action::BuyDevelopmentCard);
+    }
+
+    if expanding {
+      if difficulty == Difficulty::Hard {
+        actions.push(Action::PlayRoadBuilding);
+      }
+      let roads = self.ranked_edges(seat, difficulty, rng);
+      actions.extend(roads.into_iter().take(1).map(Action::BuildRoad));
+    }
+
+    if difficulty == Difficulty::Hard {
+      let missing = missing(self.players[seat].resources, self.bot_goal(seat));
+      let mut wanted = missing.iter()
+        .flat_map(|(resource, count)| (0..count).map(move |_| resource));
+      if let (Some(first), second) = (wanted.next(), wanted.next()) {
+        actions.push(Action::PlayYearOfPlenty([first, second.unwrap_or(first)]));
+      }
+      actions.extend(self.bot_maritime_trade(seat));
+    }
+
+    actions
+  }
+
+  // Surplus resource is traded for missing one of goal, hand only shrinks, so trades end
+  fn bot_maritime_trade(&self, seat: usize) -> Option<Action> {
+    let hand = self.players[seat].resources;
+    let goal = self.bot_goal(seat);
+    let receive = missing(hand, goal).iter().next().map(|(resource, _)| resource)?;
+    let ratios = self.trade_ratios(seat);
+
+    let give = Resource::ALL.into_iter()
+      .filter(|resource| {
+        hand.get(*resource) >= goal.get(*resource) + ratios.get(*resource)
+      })
+      .min_by_key(|resource| ratios.get(*resource))?;
+
+    Some(Action::MaritimeTrade { give, receive })
+  }
+
+  // Cost of next piece, which bot saves for
+  fn bot_goal(&self, seat: usize) -> Resources {
+    let player = &self.players[seat];
+
+    if player.cities > 0 && !self.own_settlements(seat).is_empty() {
+      CITY_COST
+    } else if player.settlements > 0 {
+      SETTLEMENT_COST
+    } else if self.scenario.cities_and_knights() {
+      ROAD_COST
+    } else {
+      DEVELOPMENT_CARD_COST
+    }
+  }
+
+  // Trade helps, when bot gets more of its goal, than gives, and partner is not about to win
+  fn bot_accepts(
+    &self, seat: usize, difficulty: Difficulty, give: Resources, receive: Resources, rng: &Rng
+  ) -> bool {
+    let hand = self.players[seat].resources;
+    if !hand.contains(&give) {
+      return false
+    }
+
+    if difficulty == Difficulty::Easy {
+      return rng.bool()
+    }
+
+    if difficulty == Difficulty::Hard {
+      let partner = self.score(self.active).visible();
+      if partner + LEADER_MARGIN >= self.rules.victory_points {
+        return false
+      }
+    }
+
+    let goal = self.bot_goal(seat);
+    let mut after = hand;
+    after -= give;
+    after += receive;
+
+    missing(after, goal).total() < missing(hand, goal).total()
+  }
+
+  // Cards of hand beyond goal are discarded or given first
+  fn bot_discard(
+    &self, seat: usize, mut hand: Resources, count: u32, difficulty: Difficulty, rng: &Rng
+  ) -> Resources {
+    if difficulty == Difficulty::Easy {
+      return random_cards(hand, count, rng)
+    }
+
+    let goal = self.bot_goal(seat);
+    let mut discarded = Resources::default();
+    for _ in 0..count {
+      let resource = hand.iter()
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(resource, count)| {
+          i64::from(*count) - i64::from(goal.get(*resource))
+        })
+        .map(|(resource, _)| resource);
+      let Some(resource) = resource else { break };
+
+      hand.remove(resource, 1);
+      discarded.add(resource, 1);
+    }
+
+    discarded
+  }
+
+  // Missing resources of goal are chosen from pool first, then random ones
+  fn bot_choice(
+    &self, seat: usize, mut pool: Resources, count: u32, difficulty: Difficulty, rng: &Rng
+  ) -> Resources {
+    let mut chosen = Resources::default();
+    if difficulty != Difficulty::Easy {
+      let wanted = missing(self.players[seat].resources, self.bot_goal(seat));
+      for (resource, wanted) in wanted.iter() {
+        let take = wanted.min(pool.get(resource)).min(count - chosen.total());
+        pool.remove(resource, take);
+        chosen.add(resource, take);
+      }
+    }
+
+    chosen += random_cards(pool, count - chosen.total(), rng);
+    chosen
+  }
+
+  // Robber goes to hex, where it blocks most production of opponents, leader is robbed
+  fn bot_robber(&self, seat: usize, difficulty: Difficulty, rng: &Rng) -> Vec<Action> {
+    let mut hexes = self.board.tiles()
+      .map(|(hex, _)| *hex)
+      .filter(|hex| self.board.is_land(*hex))
+      .collect::<Vec<_>>();
+    rng.shuffle(&mut hexes);
+
+    if difficulty != Difficulty::Easy {
+      hexes.retain(|hex| !self.touches(seat, *hex));
+      hexes.sort_by_cached_key(|hex| Reverse(self.blocked_value(*hex, difficulty)));
+    }
+
+    hexes.into_iter()
+      .map(|hex| {
+        let mut victims = self.robber_victims(hex);
+        let victim = match difficulty {
+          Difficulty::Easy => {
+            rng.shuffle(&mut victims);
+            victims.first().copied()
+          },
+          Difficulty::Medium => {
+            victims.into_iter().max_by_key(|victim| self.players[*victim].resources.total())
+          },
+          Difficulty::Hard => {
+            victims.into_iter().max_by_key(|victim| {
+              (self.score(*victim).visible(), self.players[*victim].resources.total())
+            })
+          }
+        };
+        Action::MoveRobber { hex, victim }
+      })
+      .collect()
+  }
+
+  // Opponents production blocked by robber on hex, hard bots weigh it by scores
+  fn blocked_value(&self, hex: Hex, difficulty: Difficulty) -> u32 {
+    let pips = self.board.tile(hex)
+      .and_then(|tile| tile.token)
+      .map_or(0, |token| u32::from(pips(token)));
+
+    hex.vertices().iter()
+      .filter_map(|vertex| self.buildings.get(vertex))
+      .map(|building| {
+        let count = if building.kind == BuildingKind::City { 2 } else { 1 };
+        let weight = if difficulty == Difficulty::Hard {
+          self.score(building.owner).visible()
+        } else {
+          1
+        };
+        pips * count * weight
+      })
+      .sum()
+  }
+
+  // Robber stands on hex, which produces for bot, and bot may chase it with knight
+  fn robber_blocks(&self, seat: usize) -> bool {
+    self.touches(seat, self.board.robber())
+      && self.players[seat].cards.contains(&DevelopmentCard::Knight)
+  }
+
+  fn touches(&self, seat: usize, hex: Hex) -> bool {
+    hex.vertices().iter()
+      .any(|vertex| self.buildings.get(vertex).is_some_and(|building| building.owner == seat))
+  }
+
+  fn own_settlements(&self, seat: usize) -> Vec<Vertex> {
+    self.buildings.iter()
+      .filter(|(_, building)| building.owner == seat && building.kind == BuildingKind::Settlement)
+      .map(|(vertex, _)| *vertex)
+      .collect()
+  }
+
+  // Legal settlement locations, best first
+  fn ranked_vertices(
+    &self, seat: usize, difficulty: Difficulty, need_road: bool, rng: &Rng
+  ) -> Vec<Vertex> {
+    let mut vertices = self.board.vertices().into_iter()
+      .filter(|vertex| self.check_settlement_location(seat, *vertex, need_road).is_ok())
+      .collect::<Vec<_>>();
+
+    if difficulty == Difficulty::Easy {
+      rng.shuffle(&mut vertices);
+    } else {
+      vertices.sort_by_cached_key(|vertex| Reverse(self.vertex_value(seat, *vertex, difficulty)));
+    }
+
+    vertices
+  }
+
+  // Legal road locations, leading to best settlement locations first
+  fn ranked_edges(&self, seat: usize, difficulty: Difficulty, rng: &Rng) -> Vec<Edge> {
+    let mut edges = self.board.edges().into_iter()
+      .filter(|edge| self.check_road_location(seat, *edge).is_ok())
+      .collect::<Vec<_>>();
+
+    if difficulty == Difficulty::Easy {
+      rng.shuffle(&mut edges);
+    } else {
+      edges.sort_by_cached_key(|edge| Reverse(self.edge_value(seat, *edge, difficulty)));
+    }
+
+    edges
+  }
+
+  // Best settlement location at edge end or one step further, nearer one is worth more
+  fn edge_value(&self, seat: usize, edge: Edge, difficulty: Difficulty) -> u32 {
+    let free = |vertex: &Vertex| self.check_settlement_location(seat, *vertex, false).is_ok();
+
+    edge.vertices().iter()
+      .map(|vertex| {
+        if free(vertex) {
+          return self.vertex_value(seat, *vertex, difficulty) * 2
+        }
+        vertex.neighbours().iter()
+          .filter(|neighbour| free(neighbour))
+          .map(|neighbour| self.vertex_value(seat, *neighbour, difficulty))
+          .max()
+          .unwrap_or(0)
+      })
+      .max()
+      .unwrap_or(0)
+  }
+
+  // Production of location in tenths of pip, new resources for player are worth more
+  fn vertex_value(&self, seat: usize, vertex: Vertex, difficulty: Difficulty) -> u32 {
+    let produced = self.produced(seat);
+    let scarcity = self.scarcity();
+
+    let mut value = 0;
+    for (_, tile) in self.board.vertex_tiles(vertex) {
+      let (Some(resource), Some(token)) = (tile.terrain.resource(), tile.token) else { continue };
+
+      let pips = u32::from(pips(token)) * 10;
+      value += if difficulty == Difficulty::Hard {
+        pips * scarcity.get(resource) / 10
+      } else {
+        pips
+      };
+      if produced.get(resource) == 0 {
+        value += DIVERSITY_VALUE;
+      }
+    }
+
+    if difficulty == Difficulty::Hard && self.board.vertex_harbor(vertex).is_some() {
+      value += HARBOR_VALUE;
+    }
+
+    value
+  }
+
+  // Pips of every resource from own buildings
+  fn produced(&self, seat: usize) -> Resources {
+    let mut produced = Resources::default();
+
+    let own = self.buildings.iter().filter(|(_, building)| building.owner == seat);
+    for (vertex, _) in own {
+      for (_, tile) in self.board.vertex_tiles(*vertex) {
+        if let (Some(resource), Some(token)) = (tile.terrain.resource(), tile.token) {
+          produced.add(resource, u32::from(pips(token)));
+        }
+      }
+    }
+
+    produced
+  }
+
+  // Weight of every resource in tenths, rare resources on board weigh more
+  fn scarcity(&self) -> Resources {
+    let mut pips_total = Resources::default();
+    for (_, tile) in self.board.tiles() {
+      if let (Some(resource), Some(token)) = (tile.terrain.resource(), tile.token) {
+        pips_total.add(resource, u32::from(pips(token)));
+      }
+    }
+
+    let average = pips_total.total() / 5;
+    let mut scarcity = Resources::default();
+    for resource in Resource::ALL {
+      scarcity.add(resource, average * 10 / pips_total.get(resource).max(1));
+    }
+
+    scarcity
+  }
+}
+
+// Resources of goal, which hand lacks
+fn missing(hand: Resources, goal: Resources) -> Resources {
+  let mut missing = Resources::default();
+  for (resource, count) in goal.iter() {
+    missing.add(resource, count.saturating_sub(hand.get(resource)));
+  }
+  missing
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+use crate::game::{
+  action::Rejection,
+  board::Edge,
+  cards::DevelopmentCard,
+  event::Event,
+  resources::{ Resource, Resources, DEVELOPMENT_CARD_COST }
+};
+use super::{ Game, Phase };
+
+// Roads placed for free by road building card
+const ROAD_BUILDING_ROADS: u8 = 2;
+
+impl Game {
+  pub(super) fn buy_development_card(&mut self) -> Result<Vec<Event>, Rejection> {
+    if self.deck.is_empty() {
+      return Err(Rejection::DeckEmpty)
+    }
+
+    self.pay(&DEVELOPMENT_CARD_COST)?;
+
+    let card_option = self.deck.pop();
+    // SAFETY: at start of function we checked that deck is not empty
+    let card = unsafe { card_option.unwrap_unchecked() };
+
+    let seat = self.active;
+    // Bought card can not be played until next turn
+    self.players[seat].new_cards.push(card);
+
+    Ok(vec![Event::DevelopmentCardBought { seat, card: Some(card) }])
+  }
+
+  // Check, that active player can play card now, before card effect validation
+  fn check_card(&self, card: DevelopmentCard) -> Result<(), Rejection> {
+    if self.card_played {
+      return Err(Rejection::CardAlreadyPlayed)
+    }
+
+    if !self.players[self.active].cards.contains(&card) {
+      return Err(Rejection::NoCard)
+    }
+
+    Ok(())
+  }
+
+  fn remove_card(&mut self, card: DevelopmentCard) -> Event {
+    let cards = &mut self.players[self.active].cards;
+    if let Some(index) = cards.iter().position(|held| *held == card) {
+      cards.remove(index);
+    }
+
+    self.card_played = true;
+
+    Event::DevelopmentCardPlayed { seat: self.active, card }
+  }
+
+  pub(super) fn play_knight(&mut self) -> Result<Vec<Event>, Rejection> {
+    self.check_card(DevelopmentCard::Knight)?;
+
+    let mut events = vec![self.remove_card(DevelopmentCard::Knight)];
+
+    self.players[self.active].knights += 1;
+    events.extend(self.update_largest_army(self.active));
+
+    self.resume = self.phase;
+    self.phase = Phase::Robber;
+    events.push(self.phase_event());
+
+    Ok(events)
+  }
+
+  fn has_road_location(&self, seat: usize) -> bool {
+    self.board.edges().into_iter().any(|edge| self.check_road_location(seat, edge).is_ok())
+  }
+
+  // Road building of development and progress cards
+  pub(super) fn check_road_building(&self) -> Result<(), Rejection> {
+    if self.players[self.active].roads == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    if !self.has_road_location(self.active) {
+      return Err(Rejection::NoLegalRoads)
+    }
+
+    Ok(())
+  }
+
+  pub(super) const fn start_road_building(&mut self) -> Event {
+    self.resume = self.phase;
+    self.phase = Phase::RoadBuilding { remaining: ROAD_BUILDING_ROADS };
+    self.phase_event()
+  }
+
+  pub(super) fn play_road_building(&mut self) -> Result<Vec<Event>, Rejection> {
+    self.check_card(DevelopmentCard::RoadBuilding)?;
+    self.check_road_building()?;
+
+    Ok(vec![self.remove_card(DevelopmentCard::RoadBuilding), self.start_road_building()])
+  }
+
+  pub(super) fn build_free_road(
+    &mut self, edge: Edge, remaining: u8
+  ) -> Result<Vec<Event>, Rejection> {
+    self.check_road_location(self.active, edge)?;
+
+    let mut events = vec![self.place_road(edge)];
+    events.extend(self.road_built(self.active));
+
+    // Road building ends early, if player has no more pieces or legal locations
+    let remaining = remaining - 1;
+    self.phase = if remaining == 0
+    || self.players[self.active].roads == 0
+    || !self.has_road_location(self.active)
+    {
+      self.resume
+    } else {
+      Phase::RoadBuilding { remaining }
+    };
+    events.push(self.phase_event());
+
+    Ok(events)
+  }
+
+  pub(super) fn play_year_of_plenty(
+    &mut self, resources: [Resource; 2]
+  ) -> Result<Vec<Event>, Rejection> {
+    self.check_card(DevelopmentCard::YearOfPlenty)?;
+
+    if resources.iter().any(|resource| resource.is_commodity()) {
+      return Err(Rejection::CommodityNotAllowed)
+    }
+
+    let mut taken = Resources::default();
+    for resource in resources {
+      taken.add(resource, 1);
+    }
+
+    if !self.bank.contains(&taken) {
+      return Err(Rejection::NotEnoughInBank)
+    }
+
+    let mut events = vec![self.remove_card(DevelopmentCard::YearOfPlenty)];
+
+    self.bank -= taken;
+    self.players[self.active].resources += taken;
+    events.push(Event::YearOfPlentyTaken { seat: self.active, resources: taken });
+
+    Ok(events)
+  }
+
+  pub(super) fn play_monopoly(&mut self, resource: Resource) -> Result<Vec<Event>, Rejection> {
+    self.check_card(DevelopmentCard::Monopoly)?;
+
+    if resource.is_commodity() {
+      return Err(Rejection::CommodityNotAllowed)
+    }
+
+    let mut events = vec![self.remove_card(DevelopmentCard::Monopoly)];
+
+    let seat = self.active;
+    let mut count = 0;
+    for (other, player) in self.players.iter_mut().enumerate() {
+      if other != seat {
+        let held = player.resources.get(resource);
+        player.resources.remove(resource, held);
+        count += held;
+      }
+    }
+    self.players[seat].resources.add(resource, count);
+
+    events.push(Event::MonopolyTaken { seat, resource, count });
+
+    Ok(events)
+  }
+}
\ No newline at end of file
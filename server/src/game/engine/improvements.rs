@@ -0,0 +1,195 @@
+use crate::game::{
+  action::Rejection,
+  board::Vertex,
+  cards::Track,
+  event::Event,
+  resources::{ Resources, CITY_WALL_COST }
+};
+use super::{ BuildingKind, Game };
+
+const MAX_IMPROVEMENT_LEVEL: u8 = 5;
+// Third improvement of track grants its ability: trading house, fortress or aqueduct
+const ABILITY_LEVEL: u8 = 3;
+const METROPOLIS_LEVEL: u8 = 4;
+// Player draws progress card of gate, when red die is not more than level plus one
+const PROGRESS_DRAW_BONUS: u8 = 1;
+// Progress cards, which player may hold, victory point cards are not held
+const PROGRESS_HAND_LIMIT: usize = 4;
+// Every city wall raises discard limit
+const CITY_WALL_DISCARD_BONUS: u32 = 2;
+
+impl Game {
+  fn level(&self, seat: usize, track: Track) -> u8 {
+    self.players[seat].improvements[track as usize]
+  }
+
+  pub(super) fn has_ability(&self, seat: usize, track: Track) -> bool {
+    self.level(seat, track) >= ABILITY_LEVEL
+  }
+
+  pub(super) fn check_city_wall(&self, vertex: Vertex) -> Result<(), Rejection> {
+    let seat = self.active;
+    let own_city = self.buildings.get(&vertex).is_some_and(|building| {
+      building.owner == seat && building.kind == BuildingKind::City
+    });
+
+    if !own_city {
+      return Err(Rejection::NotOwned)
+    }
+
+    if self.city_walls.contains(&vertex) {
+      return Err(Rejection::Occupied)
+    }
+
+    if self.players[seat].city_walls == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn place_city_wall(&mut self, vertex: Vertex) -> Event {
+    let seat = self.active;
+    self.players[seat].city_walls -= 1;
+    self.city_walls.insert(vertex);
+    Event::CityWallBuilt { seat, vertex }
+  }
+
+  pub(super) fn build_city_wall(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    self.check_city_wall(vertex)?;
+    self.pay(&CITY_WALL_COST)?;
+    Ok(vec![self.place_city_wall(vertex)])
+  }
+
+  pub(super) fn city_wall_bonus(&self, seat: usize) -> u32 {
+    self.city_walls.iter()
+      .filter(|vertex| self.buildings.get(vertex).is_some_and(|building| building.owner == seat))
+      .map(|_| CITY_WALL_DISCARD_BONUS)
+      .sum()
+  }
+
+  // Own cities, which have no metropolis yet
+  fn plain_city(&self, seat: usize) -> Option<Vertex> {
+    self.buildings.iter()
+      .find(|(vertex, building)| {
+        building.owner == seat
+        && building.kind == BuildingKind::City
+        && !self.metropolises.contains(&Some(**vertex))
+      })
+      .map(|(vertex, _)| *vertex)
+  }
+
+  // First player on metropolis level takes metropolis, other player takes it only with
+  // higher level, so it can not be taken from level 5 holder
+  fn takes_metropolis(&self, seat: usize, track: Track, level: u8) -> bool {
+    level >= METROPOLIS_LEVEL
+    && self.metropolises[track as usize].is_none_or(|vertex| {
+      self.buildings.get(&vertex).is_some_and(|building| {
+        building.owner != seat && self.level(building.owner, track) < level
+      })
+    })
+  }
+
+  // Every next level costs one more commodity of track, city is required for improvement
+  pub(super) fn improve_city(&mut self, track: Track) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+    let level = self.level(seat, track) + 1;
+
+    if level > MAX_IMPROVEMENT_LEVEL {
+      return Err(Rejection::MaxLevel)
+    }
+
+    let has_city = self.buildings.values().any(|building| {
+      building.owner == seat && building.kind == BuildingKind::City
+    });
+    if !has_city {
+      return Err(Rejection::NoCity)
+    }
+
+    // Metropolis is placed on own city, which has no other metropolis
+    let metropolis = if self.takes_metropolis(seat, track, level) {
+      Some(self.plain_city(seat).ok_or(Rejection::NoCity)?)
+    } else {
+      None
+    };
+
+    let mut cost = Resources::default();
+    cost.add(track.commodity(), u32::from(level) - u32::from(self.crane));
+    self.pay(&cost)?;
+    self.crane = false;
+
+    self.players[seat].improvements[track as usize] = level;
+
+    let mut events = vec![Event::CityImproved { seat, track, level }];
+
+    // Trading house lowers commodities ratios
+    if track == Track::Trade && level == ABILITY_LEVEL {
+      events.push(self.ratios_event(seat));
+    }
+
+    if let Some(vertex) = metropolis {
+      self.metropolises[track as usize] = Some(vertex);
+      events.push(Event::MetropolisChanged { seat, track, vertex });
+    }
+
+    Ok(events)
+  }
+
+  // Every player with improvement of gate track draws card, when red die is low enough,
+  // starting from active player
+  pub(super) fn draw_progress_cards(&mut self, track: Track, red: u8) -> Vec<Event> {
+    let count = self.players.len();
+    let mut events = Vec::new();
+
+    for offset in 0..count {
+      let seat = (self.active + offset) % count;
+      let level = self.level(seat, track);
+      if level > 0 && red <= level + PROGRESS_DRAW_BONUS {
+        events.extend(self.draw_progress_card(seat, track));
+      }
+    }
+
+    events
+  }
+
+  // Card is not drawn, when hand is full or deck is empty,
+  // victory point card is revealed at once
+  pub(super) fn draw_progress_card(&mut self, seat: usize, track: Track) -> Vec<Event> {
+    if self.players[seat].progress_cards.len() >= PROGRESS_HAND_LIMIT {
+      return Vec::new()
+    }
+
+    let Some(card) = self.progress_decks[track as usize].pop() else { return Vec::new() };
+
+    let player = &mut self.players[seat];
+    if card.is_victory_point() {
+      player.progress_points += 1;
+      return vec![Event::ProgressCardPlayed { seat, card }]
+    }
+
+    player.progress_cards.push(card);
+
+    vec![Event::ProgressCardDrawn { seat, track, card: Some(card) }]
+  }
+
+  // Track with highest level, first one on tie
+  pub(super) fn best_track(&self, seat: usize) -> Track {
+    let mut best = Track::Trade;
+    for track in Track::ALL {
+      if self.level(seat, track) > self.level(seat, best) {
+        best = track;
+      }
+    }
+    best
+  }
+
+  // Aqueduct grants resource of choice to player, who is owed nothing by production,
+  // choice is made in gold phase
+  pub(super) fn aqueduct(&self, owed: &[Resources], gold: &mut [u32]) {
+    for (seat, (resources, count)) in owed.iter().zip(gold.iter_mut()).enumerate() {
+      if self.has_ability(seat, Track::Science) && resources.is_empty() && *count == 0 {
+        *count = 1;
+      }
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,524 @@
+use std::collections::{ BTreeSet, VecDeque };
+use crate::game::{
+  action::Rejection,
+  board::Vertex,
+  cards::Track,
+  event::Event,
+  resources::{ KNIGHT_ACTIVATION_COST, KNIGHT_COST }
+};
+use super::{ Building, BuildingKind, Game, Phase };
+
+// Barbarians attack, when ship reaches end of track
+const BARBARIAN_TRACK: u8 = 7;
+const MAX_KNIGHT_LEVEL: u8 = 3;
+
+// Knight on board, level is from 1 for basic to 3 for mighty knight
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Knight {
+  pub owner: usize,
+  pub level: u8,
+  pub active: bool,
+  // Turns of last activation and promotion: knight does not act in turn of activation
+  // and is promoted only once per turn
+  pub activated: u32,
+  pub promoted: u32
+}
+
+// Supply index of knights of level
+const fn piece(level: u8) -> usize {
+  (level - 1) as usize
+}
+
+impl Game {
+  // Opponent knight breaks roads and blocks building through its vertex
+  pub(super) fn knight_blocks(&self, seat: usize, vertex: Vertex) -> bool {
+    self.knights.get(&vertex).is_some_and(|knight| knight.owner != seat)
+  }
+
+  // Robber is not moved after seven, until barbarians attack first time
+  pub(super) const fn robber_locked(&self) -> bool {
+    self.scenario.cities_and_knights() && !self.robber_released
+  }
+
+  pub(super) const fn robber_phase(&self) -> Phase {
+    if self.robber_locked() { self.resume } else { Phase::Robber }
+  }
+
+  // Knight is placed on free vertex next to own road
+  pub(super) fn check_knight_location(&self, vertex: Vertex) -> Result<(), Rejection> {
+    if !self.board.has_vertex(vertex) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    if self.buildings.contains_key(&vertex) || self.knights.contains_key(&vertex) {
+      return Err(Rejection::Occupied)
+    }
+
+    if !vertex.edges().iter().any(|edge| self.roads.get(edge) == Some(&self.active)) {
+      return Err(Rejection::NotConnected)
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn build_knight(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    self.check_knight_location(vertex)?;
+
+    if self.players[seat].knight_pieces[piece(1)] == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(&KNIGHT_COST)?;
+
+    self.players[seat].knight_pieces[piece(1)] -= 1;
+    self.knights.insert(vertex, Knight {
+      owner: seat,
+      level: 1,
+      active: false,
+      activated: 0,
+      promoted: 0
+    });
+
+    let mut events = vec![Event::KnightBuilt { seat, vertex }];
+    // Knight breaks opponent roads as settlement does
+    events.extend(self.settlement_built(seat, vertex));
+
+    Ok(events)
+  }
+
+  pub(super) fn own_knights(&self, seat: usize) -> Vec<Vertex> {
+    self.knights.iter()
+      .filter(|(_, knight)| knight.owner == seat)
+      .map(|(vertex, _)| *vertex)
+      .collect()
+  }
+
+  fn own_knight(&self, vertex: Vertex) -> Result<Knight, Rejection> {
+    self.knights.get(&vertex)
+      .filter(|knight| knight.owner == self.active)
+      .copied()
+      .ok_or(Rejection::NotOwned)
+  }
+
+  // Knight and its new level, mighty knights require fortress, politics ability
+  fn check_promotion(&self, vertex: Vertex) -> Result<(Knight, u8), Rejection> {
+    let knight = self.own_knight(vertex)?;
+
+    if knight.promoted == self.turn {
+      return Err(Rejection::KnightBusy)
+    }
+
+    let level = knight.level + 1;
+    if level > MAX_KNIGHT_LEVEL
+    || (level == MAX_KNIGHT_LEVEL && !self.has_ability(self.active, Track::Politics))
+    {
+      return Err(Rejection::MaxLevel)
+    }
+
+    Ok((knight, level))
+  }
+
+  fn raise_knight(&mut self, vertex: Vertex, knight: Knight, level: u8) -> Event {
+    let seat = self.active;
+    let pieces = &mut self.players[seat].knight_pieces;
+    pieces[piece(knight.level)] += 1;
+    pieces[piece(level)] -= 1;
+    self.knights.insert(vertex, Knight { level, promoted: self.turn, ..knight });
+
+    Event::KnightPromoted { seat, vertex, level }
+  }
+
+  pub(super) fn promote_knight(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    let (knight, level) = self.check_promotion(vertex)?;
+
+    if self.players[self.active].knight_pieces[piece(level)] == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(&KNIGHT_COST)?;
+
+    Ok(vec![self.raise_knight(vertex, knight, level)])
+  }
+
+  // Smith promotes knights for free, every promotion is checked with pieces left after
+  // previous one, so nothing is changed, if any of them is rejected
+  pub(super) fn promote_knights(&mut self, vertices: &[Vertex]) -> Result<Vec<Event>, Rejection> {
+    let mut pieces = self.players[self.active].knight_pieces;
+    let mut promotions = Vec::with_capacity(vertices.len());
+
+    for (index, vertex) in vertices.iter().enumerate() {
+      if vertices[..index].contains(vertex) {
+        return Err(Rejection::KnightBusy)
+      }
+
+      let (knight, level) = self.check_promotion(*vertex)?;
+      if pieces[piece(level)] == 0 {
+        return Err(Rejection::NoPiecesLeft)
+      }
+
+      pieces[piece(knight.level)] += 1;
+      pieces[piece(level)] -= 1;
+      promotions.push((*vertex, knight, level));
+    }
+
+    Ok(
+      promotions.into_iter()
+        .map(|(vertex, knight, level)| self.raise_knight(vertex, knight, level))
+        .collect()
+    )
+  }
+
+  pub(super) fn activate_knight(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    let knight = self.own_knight(vertex)?;
+
+    if knight.active {
+      return Err(Rejection::KnightActive)
+    }
+
+    self.pay(&KNIGHT_ACTIVATION_COST)?;
+
+    self.knights.insert(vertex, Knight { active: true, activated: self.turn, ..knight });
+
+    Ok(vec![Event::KnightActivated { seat: self.active, vertex }])
+  }
+
+  // Warlord activates every own knight for free
+  pub(super) fn activate_knights(&mut self) -> Vec<Event> {
+    let seat = self.active;
+    let turn = self.turn;
+
+    self.knights.iter_mut()
+      .filter(|(_, knight)| knight.owner == seat && !knight.active)
+      .map(|(vertex, knight)| {
+        knight.active = true;
+        knight.activated = turn;
+        Event::KnightActivated { seat, vertex: *vertex }
+      })
+      .collect()
+  }
+
+  // Knight acts only, if it is active and was activated before current turn
+  fn check_knight_action(&self, vertex: Vertex) -> Result<Knight, Rejection> {
+    let knight = self.own_knight(vertex)?;
+
+    if !knight.active {
+      return Err(Rejection::KnightInactive)
+    }
+
+    if knight.activated == self.turn {
+      return Err(Rejection::KnightBusy)
+    }
+
+    Ok(knight)
+  }
+
+  // Vertices along own roads from vertex, knights and opponent buildings end path,
+  // nearest vertices are first
+  fn knight_reach(&self, seat: usize, from: Vertex) -> Vec<Vertex> {
+    let mut reached = Vec::new();
+    let mut visited = BTreeSet::from([from]);
+    let mut frontier = VecDeque::from([from]);
+
+    while let Some(current) = frontier.pop_front() {
+      for edge in current.edges() {
+        if self.roads.get(&edge) != Some(&seat) {
+          continue
+        }
+
+        let next = edge.other_vertex(current);
+        if !visited.insert(next) {
+          continue
+        }
+
+        reached.push(next);
+
+        let passable = !self.knights.contains_key(&next)
+          && self.buildings.get(&next).is_none_or(|building| building.owner == seat);
+        if passable {
+          frontier.push_back(next);
+        }
+      }
+    }
+
+    reached
+  }
+
+  pub(super) fn move_knight(&mut self, from: Vertex, to: Vertex) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+    let knight = self.check_knight_action(from)?;
+
+    if !self.knight_reach(seat, from).contains(&to) {
+      return Err(Rejection::NotConnected)
+    }
+
+    if self.buildings.contains_key(&to) {
+      return Err(Rejection::Occupied)
+    }
+
+    if let Some(other) = self.knights.get(&to) {
+      if other.owner == seat {
+        return Err(Rejection::Occupied)
+      }
+
+      if other.level >= knight.level {
+        return Err(Rejection::KnightTooWeak)
+      }
+    }
+
+    self.knights.remove(&from);
+    let displaced = self.knights.insert(to, Knight { active: false, ..knight });
+
+    let mut events = vec![Event::KnightMoved { seat, from, to }];
+    events.extend(self.vertex_vacated(from));
+    if let Some(displaced) = displaced {
+      events.extend(self.displace_knight(to, displaced));
+    }
+    events.extend(self.settlement_built(seat, to));
+
+    Ok(events)
+  }
+
+  // Displaced knight, which is already removed from board, is moved to nearest free vertex
+  // along roads of its owner, it returns to supply, if there is no such vertex
+  pub(super) fn displace_knight(&mut self, from: Vertex, knight: Knight) -> Vec<Event> {
+    let seat = knight.owner;
+    let to = self.knight_reach(seat, from).into_iter().find(|vertex| {
+      !self.buildings.contains_key(vertex) && !self.knights.contains_key(vertex)
+    });
+
+    match to {
+      Some(to) => {
+        self.knights.insert(to, knight);
+      },
+      None => self.players[seat].knight_pieces[piece(knight.level)] += 1
+    }
+
+    let mut events = vec![Event::KnightDisplaced { seat, from, to }];
+    if let Some(to) = to {
+      events.extend(self.settlement_built(seat, to));
+    }
+
+    events
+  }
+
+  // Deserter victim must have knight to remove
+  pub(super) fn play_deserter(
+    &mut self, victim: usize, vertex: Option<Vertex>
+  ) -> Result<Vec<Event>, Rejection> {
+    if victim == self.active || self.own_knights(victim).is_empty() {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    if let Some(vertex) = vertex {
+      self.check_knight_location(vertex)?;
+    }
+
+    self.phase = Phase::Desertion { victim, vertex };
+
+    Ok(vec![self.phase_event()])
+  }
+
+  // Victim knight returns to supply, own knight of its level and activity is placed
+  // for free, if such piece is left in supply
+  pub(super) fn desert(
+    &mut self, victim: usize, removed: Vertex, vertex: Option<Vertex>
+  ) -> Result<Vec<Event>, Rejection> {
+    let Some(knight) = self.knights.get(&removed).copied().filter(|knight| knight.owner == victim)
+    else {
+      return Err(Rejection::NotOwned)
+    };
+
+    self.knights.remove(&removed);
+    self.players[victim].knight_pieces[piece(knight.level)] += 1;
+
+    let mut events = vec![Event::KnightRemoved { seat: victim, vertex: removed }];
+    events.extend(self.vertex_vacated(removed));
+
+    let seat = self.active;
+    let placed = vertex.filter(|vertex| {
+      self.players[seat].knight_pieces[piece(knight.level)] > 0
+      && self.check_knight_location(*vertex).is_ok()
+    });
+    if let Some(vertex) = placed {
+      self.players[seat].knight_pieces[piece(knight.level)] -= 1;
+      self.knights.insert(vertex, Knight { owner: seat, ..knight });
+      events.push(Event::KnightPlaced { seat, vertex, level: knight.level, active: knight.active });
+      events.extend(self.settlement_built(seat, vertex));
+    }
+
+    self.phase = Phase::Main;
+    events.push(self.phase_event());
+    // Active player may win, when placed knight breaks longest road of its holder
+    self.check_victory(&mut events);
+
+    Ok(events)
+  }
+
+  // Knight on vertex of robber hex sends robber away
+  pub(super) fn chase_robber(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    let knight = self.check_knight_action(vertex)?;
+
+    if self.robber_locked() {
+      return Err(Rejection::RobberInactive)
+    }
+
+    if !self.board.robber().vertices().contains(&vertex) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    self.knights.insert(vertex, Knight { active: false, ..knight });
+
+    self.resume = Phase::Main;
+    self.phase = Phase::Robber;
+
+    Ok(vec![Event::RobberChased { seat: self.active, vertex }, self.phase_event()])
+  }
+
+  // Barbarian ship is on three faces of event die, every gate is on one face
+  pub(super) fn roll_event_die(&mut self, red: u8) -> Vec<Event> {
+    let gate = match self.rng.u8(0..6) {
+      3 => Some(Track::Trade),
+      4 => Some(Track::Politics),
+      5 => Some(Track::Science),
+      _ => None
+    };
+
+    let mut events = vec![Event::EventDieRolled { seat: self.active, gate }];
+
+    match gate {
+      Some(track) => events.extend(self.draw_progress_cards(track, red)),
+      None => events.extend(self.advance_barbarians())
+    }
+
+    events
+  }
+
+  fn advance_barbarians(&mut self) -> Vec<Event> {
+    self.barbarians += 1;
+
+    let mut events = vec![Event::BarbariansMoved { position: self.barbarians }];
+    if self.barbarians == BARBARIAN_TRACK {
+      events.extend(self.barbarians_attack());
+    }
+
+    events
+  }
+
+  // Barbarians strength is cities count, defense is sum of active knights levels
+  fn barbarians_attack(&mut self) -> Vec<Event> {
+    self.barbarians = 0;
+    self.robber_released = true;
+
+    let strength = self.buildings.values()
+      .filter(|building| building.kind == BuildingKind::City)
+      .map(|_| 1)
+      .sum::<u32>();
+
+    let mut defense = vec![0; self.players.len()];
+    for knight in self.knights.values().filter(|knight| knight.active) {
+      defense[knight.owner] += u32::from(knight.level);
+    }
+    let total = defense.iter().sum::<u32>();
+
+    let mut events = vec![Event::BarbariansAttacked { strength, defense: total }];
+
+    if total >= strength {
+      events.extend(self.reward_defenders(&defense));
+    } else {
+      events.extend(self.pillage(&defense));
+    }
+
+    for knight in self.knights.values_mut() {
+      knight.active = false;
+    }
+
+    events
+  }
+
+  // Single strongest defender becomes defender of Catan, tied defenders draw progress card
+  // of their most developed track
+  fn reward_defenders(&mut self, defense: &[u32]) -> Vec<Event> {
+    let best = defense.iter().copied().max().unwrap_or(0);
+    if best == 0 {
+      return Vec::new()
+    }
+
+    let defenders: Vec<usize> = (0..defense.len()).filter(|seat| defense[*seat] == best).collect();
+
+    if let [seat] = defenders[..] {
+      self.players[seat].defender_points += 1;
+      return vec![Event::DefenderOfCatan { seat }]
+    }
+
+    let mut events = Vec::new();
+    for seat in defenders {
+      let track = self.best_track(seat);
+      events.extend(self.draw_progress_card(seat, track));
+    }
+
+    events
+  }
+
+  // City without metropolis, which player loses, engine picks city without wall first
+  fn pillaged_city(&self, seat: usize) -> Option<Vertex> {
+    self.buildings.iter()
+      .filter(|(vertex, building)| {
+        building.owner == seat
+        && building.kind == BuildingKind::City
+        && !self.metropolises.contains(&Some(**vertex))
+      })
+      .map(|(vertex, _)| *vertex)
+      .min_by_key(|vertex| self.city_walls.contains(vertex))
+  }
+
+  // Weakest defenders among players with cities without metropolis lose one city
+  fn pillage(&mut self, defense: &[u32]) -> Vec<Event> {
+    let vulnerable: Vec<(usize, Vertex)> = (0..self.players.len())
+      .filter_map(|seat| self.pillaged_city(seat).map(|vertex| (seat, vertex)))
+      .collect();
+
+    let Some(weakest) = vulnerable.iter().map(|(seat, _)| defense[*seat]).min() else {
+      return Vec::new()
+    };
+
+    let mut events = Vec::new();
+    for (seat, vertex) in vulnerable {
+      if defense[seat] == weakest {
+        events.extend(self.pillage_city(seat, vertex));
+      }
+    }
+
+    events
+  }
+
+  // City loses its wall and becomes settlement, it is removed, if no settlement is in supply
+  fn pillage_city(&mut self, seat: usize, vertex: Vertex) -> Vec<Event> {
+    let player = &mut self.players[seat];
+
+    if self.city_walls.remove(&vertex) {
+      player.city_walls += 1;
+    }
+
+    player.cities += 1;
+    let removed = player.settlements == 0;
+    if removed {
+      self.buildings.remove(&vertex);
+    } else {
+      player.settlements -= 1;
+      self.buildings.insert(vertex, Building { owner: seat, kind: BuildingKind::Settlement });
+    }
+
+    let mut events = vec![Event::CityPillaged { seat, vertex, removed }];
+
+    if removed {
+      events.extend(self.vertex_vacated(vertex));
+      if self.board.vertex_harbor(vertex).is_some() {
+        events.push(self.ratios_event(seat));
+      }
+    }
+
+    events
+  }
+}
\ No newline at end of file
@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+use crate::game::{ board::{ Edge, Vertex }, event::Event };
+use super::Game;
+
+// Minimal road length to hold longest road
+const MIN_LONGEST_ROAD: u32 = 5;
+
+// Roads and ships both count to longest road
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Route {
+  Road,
+  Ship
+}
+
+impl Game {
+  fn route(&self, seat: usize, edge: Edge) -> Option<Route> {
+    if self.roads.get(&edge) == Some(&seat) {
+      Some(Route::Road)
+    } else if self.ships.get(&edge) == Some(&seat) {
+      Some(Route::Ship)
+    } else {
+      None
+    }
+  }
+
+  // Longest trail of player roads and ships: every route is counted once, but vertices may
+  // be visited again, so cycles and branches are handled, opponent buildings break road
+  fn road_length(&self, seat: usize) -> u32 {
+    let mut visited = BTreeSet::new();
+
+    self.roads.iter()
+      .chain(self.ships.iter())
+      .filter(|(_, owner)| **owner == seat)
+      .flat_map(|(edge, _)| edge.vertices())
+      .map(|vertex| self.trail_length(seat, vertex, None, &mut visited))
+      .max()
+      .unwrap_or(0)
+  }
+
+  fn trail_length(
+    &self, seat: usize, vertex: Vertex, previous: Option<Route>, visited: &mut BTreeSet<Edge>
+  ) -> u32 {
+    // Road and ship are connected only through own building
+    let own_building = self.buildings.get(&vertex).is_some_and(|building| building.owner == seat);
+
+    let mut longest = 0;
+
+    for edge in vertex.edges() {
+      let Some(route) = self.route(seat, edge) else { continue };
+
+      let switched = previous.is_some_and(|previous| previous != route);
+      if visited.contains(&edge) || (switched && !own_building) {
+        continue
+      }
+
+      let next = edge.other_vertex(vertex);
+      // Trail may end at opponent building or knight, but can not pass through it
+      let blocked = self.buildings.get(&next).is_some_and(|building| building.owner != seat)
+        || self.knight_blocks(seat, next);
+
+      visited.insert(edge);
+      let rest = if blocked { 0 } else { self.trail_length(seat, next, Some(route), visited) };
+      let length = 1 + rest;
+      visited.remove(&edge);
+
+      longest = longest.max(length);
+    }
+
+    longest
+  }
+
+  // Only roads of player, who built road, can become longer
+  pub(super) fn road_built(&mut self, seat: usize) -> Option<Event> {
+    self.road_lengths[seat] = self.road_length(seat);
+    self.update_longest_road()
+  }
+
+  fn route_owners(&self, vertex: Vertex) -> BTreeSet<usize> {
+    vertex.edges().iter()
+      .filter_map(|edge| self.roads.get(edge).or_else(|| self.ships.get(edge)).copied())
+      .collect()
+  }
+
+  // Settlement or knight can break only roads of opponents, which pass through its vertex
+  pub(super) fn settlement_built(&mut self, seat: usize, vertex: Vertex) -> Option<Event> {
+    let mut broken = self.route_owners(vertex);
+    broken.remove(&seat);
+
+    if broken.is_empty() {
+      return None
+    }
+
+    for owner in broken {
+      self.road_lengths[owner] = self.road_length(owner);
+    }
+
+    self.update_longest_road()
+  }
+
+  // Removed city or moved knight may join broken roads again
+  pub(super) fn vertex_vacated(&mut self, vertex: Vertex) -> Option<Event> {
+    for owner in self.route_owners(vertex) {
+      self.road_lengths[owner] = self.road_length(owner);
+    }
+
+    self.update_longest_road()
+  }
+
+  // Holder keeps card on tie, other player takes it only with strictly longer road.
+  // If holder road is broken and several other players tie for longest, nobody holds card
+  fn update_longest_road(&mut self) -> Option<Event> {
+    let longest = self.road_lengths.iter().copied().max().unwrap_or(0);
+    let candidates: Vec<usize> = (0..self.road_lengths.len())
+      .filter(|seat| self.road_lengths[*seat] == longest)
+      .collect();
+
+    let holder = if longest < MIN_LONGEST_ROAD {
+      None
+    } else if self.longest_road.is_some_and(|holder| candidates.contains(&holder)) {
+      self.longest_road
+    } else if let [seat] = candidates[..] {
+      Some(seat)
+    } else {
+      None
+    };
+
+    if holder == self.longest_road {
+      return None
+    }
+
+    self.longest_road = holder;
+
+    Some(Event::LongestRoadChanged {
+      seat: holder,
+      length: holder.map_or(0, |seat| self.road_lengths[seat])
+    })
+  }
+}
\ No newline at end of file
@@ -0,0 +1,95 @@
+use crate::game::{
+  action::Rejection,
+  board::{ HarborKind, Vertex },
+  cards::Track,
+  event::Event,
+  resources::{ Resource, Resources }
+};
+use super::Game;
+
+const BANK_RATIO: u32 = 4;
+const GENERIC_HARBOR_RATIO: u32 = 3;
+const SPECIFIC_HARBOR_RATIO: u32 = 2;
+
+impl Game {
+  // Best ratio of every resource and commodity, count of resource in result is its ratio
+  pub(super) fn trade_ratios(&self, seat: usize) -> Resources {
+    let mut ratios = [BANK_RATIO; 8];
+
+    let harbors = self.buildings.iter()
+      .filter(|(_, building)| building.owner == seat)
+      .filter_map(|(vertex, _)| self.board.vertex_harbor(*vertex));
+
+    for harbor in harbors {
+      match harbor {
+        HarborKind::Generic => {
+          for ratio in &mut ratios {
+            *ratio = (*ratio).min(GENERIC_HARBOR_RATIO);
+          }
+        },
+        HarborKind::Specific(resource) => ratios[resource as usize] = SPECIFIC_HARBOR_RATIO
+      }
+    }
+
+    // Cities & Knights trading house, merchant and merchant fleet
+    if self.has_ability(seat, Track::Trade) {
+      for commodity in Resource::COMMODITIES {
+        ratios[commodity as usize] = SPECIFIC_HARBOR_RATIO;
+      }
+    }
+
+    let merchant = self.merchant
+      .filter(|(_, owner)| *owner == seat)
+      .and_then(|(hex, _)| self.board.tile(hex))
+      .and_then(|tile| tile.terrain.resource());
+    let fleet = self.merchant_fleet.filter(|_| seat == self.active);
+    for resource in merchant.into_iter().chain(fleet) {
+      ratios[resource as usize] = SPECIFIC_HARBOR_RATIO;
+    }
+
+    let [brick, lumber, ore, grain, wool, paper, cloth, coin] = ratios;
+    let mut result = Resources::new(brick, lumber, ore, grain, wool);
+    result += Resources::commodities(paper, cloth, coin);
+    result
+  }
+
+  // Ratios are sent to every client, so it never computes harbors itself
+  pub(super) fn ratios_event(&self, seat: usize) -> Event {
+    Event::TradeRatiosChanged { seat, ratios: self.trade_ratios(seat) }
+  }
+
+  // Only settlement on harbor changes ratios, city replaces own settlement
+  pub(super) fn harbor_reached(&self, vertex: Vertex) -> Option<Event> {
+    self.board.vertex_harbor(vertex).map(|_| self.ratios_event(self.active))
+  }
+
+  pub(super) fn maritime_trade(
+    &mut self, give: Resource, receive: Resource
+  ) -> Result<Vec<Event>, Rejection> {
+    if give == receive {
+      return Err(Rejection::InvalidTrade)
+    }
+
+    let mut given = Resources::default();
+    given.add(give, self.trade_ratios(self.active).get(give));
+
+    let mut received = Resources::default();
+    received.add(receive, 1);
+
+    let seat = self.active;
+    if !self.players[seat].resources.contains(&given) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    if !self.bank.contains(&received) {
+      return Err(Rejection::NotEnoughInBank)
+    }
+
+    self.players[seat].resources -= given;
+    self.bank += given;
+    self.bank -= received;
+    self.players[seat].resources += received;
+
+    Ok(vec![Event::MaritimeTraded { seat, give: given, receive: received }])
+  }
+}
\ No newline at end of file
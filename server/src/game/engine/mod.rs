@@ -0,0 +1,959 @@
+mod bot;
+mod development;
+mod improvements;
+mod knights;
+mod longest_road;
+mod maritime;
+mod observation;
+mod progress;
+mod score;
+mod seafarers;
+mod timeout;
+mod trade;
+mod undo;
+#[cfg(test)]
+mod tests;
+
+use fastrand::Rng;
+use std::collections::{ BTreeMap, BTreeSet };
+use self::{ knights::Knight, trade::{ Offer, Response }, undo::UndoRequest };
+use super::{
+  action::{ Action, Rejection },
+  board::{ Board, Edge, Hex, Terrain, Vertex },
+  cards::{ DevelopmentCard, ProgressCard, Track, deck, progress_deck },
+  dice::DiceSource,
+  event::Event,
+  fairness::Proof,
+  player::Player,
+  resources::{ Resource, Resources, CITY_COST, ROAD_COST, SETTLEMENT_COST },
+  rules::Rules,
+  scenario::Scenario
+};
+
+pub use self::{ bot::Difficulty, observation::Observation, score::Score };
+
+pub const MIN_PLAYERS: usize = 3;
+pub const MAX_PLAYERS: usize = 6;
+// Games with this players count or more use 5-6 player extension rules
+pub const EXTENSION_PLAYERS: usize = 5;
+
+// Cards of every resource in bank at game start
+const BANK_RESOURCES: u32 = 19;
+const EXTENSION_BANK_RESOURCES: u32 = 24;
+const BANK_COMMODITIES: u32 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+  // Active player places settlement, then road next to it, placed settlement held in phase
+  Setup { round: u8, settlement: Option<Vertex> },
+  Roll,
+  // Trade and build
+  Main,
+  // Players with too many cards discard concurrently after seven is rolled, robber phase
+  // follows, without robber they discard after saboteur
+  Discard { robber: bool },
+  // Active player moves robber and steals
+  Robber,
+  // Active player places free roads of road building card
+  RoadBuilding { remaining: u8 },
+  // In extension games other players build in order after turn of ended player,
+  // active player is current builder
+  SpecialBuilding { ended: usize },
+  // Players with gold produced choose resources concurrently
+  Gold,
+  // Players give cards of own choice concurrently after wedding or commercial harbor
+  Gift,
+  // Victim of deserter removes knight of own choice, then active player knight is placed
+  // at vertex, if any
+  Desertion { victim: usize, vertex: Option<Vertex> },
+  // Active player takes cards from hand of master merchant victim, which is shown to it
+  Taking { victim: usize },
+  // Game is over, no more actions accepted
+  Finished { winner: usize }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildingKind {
+  Settlement,
+  City
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Building {
+  pub owner: usize,
+  pub kind: BuildingKind
+}
+
+// Everything game is created from, with same setup and actions log game is rebuilt exactly
+#[derive(Clone, Debug)]
+pub struct Setup {
+  pub scenario: Scenario,
+  pub rules: Rules,
+  // Board before first action
+  pub board: Board,
+  pub players: usize,
+  pub seed: u64,
+  // Revealed at game end, None for game without verifiable randomness
+  pub proof: Option<Proof>
+}
+
+// Authoritative game state, changed only by validated actions
+#[allow(clippy::struct_excessive_bools)]
+pub struct Game {
+  setup: Setup,
+  // Validated actions with seats of their players in order of applying
+  log: Vec<(usize, Action)>,
+  // Last action revealed hidden or random information, so it can not be undone
+  revealed: bool,
+  undo: Option<UndoRequest>,
+  scenario: Scenario,
+  rules: Rules,
+  board: Board,
+  players: Vec<Player>,
+  bank: Resources,
+  buildings: BTreeMap<Vertex, Building>,
+  roads: BTreeMap<Edge, usize>,
+  ships: BTreeMap<Edge, usize>,
+  // Ships built in current turn, they can not be moved until next turn
+  new_ships: Vec<Edge>,
+  ship_moved: bool,
+  // Cached longest road length of every player, updated when roads or settlements are built
+  road_lengths: Vec<u32>,
+  longest_road: Option<usize>,
+  largest_army: Option<usize>,
+  offer: Option<Offer>,
+  // Cards count every player must discard in discard phase
+  discards: Vec<u32>,
+  // Gold resources count every player must choose in gold phase
+  gold: Vec<u32>,
+  // Cards count every player must give in gift phase
+  gifts: Vec<u32>,
+  // Resource offered to every player with commercial harbor, it is received for commodity
+  exchanges: Vec<Option<Resource>>,
+  deck: Vec<DevelopmentCard>,
+  // Development card was played in current turn
+  card_played: bool,
+  knights: BTreeMap<Vertex, Knight>,
+  city_walls: BTreeSet<Vertex>,
+  // City with metropolis of every improvement track, indexed as track
+  metropolises: [Option<Vertex>; 3],
+  progress_decks: [Vec<ProgressCard>; 3],
+  // Barbarian ship position on track, it attacks on reaching end of track
+  barbarians: u8,
+  // Robber stays in desert until first barbarian attack
+  robber_released: bool,
+  // Merchant hex and its owner
+  merchant: Option<(Hex, usize)>,
+  // Resource, which active player trades at 2:1 in current turn
+  merchant_fleet: Option<Resource>,
+  // Dice chosen with alchemist for next roll
+  alchemist: Option<[u8; 2]>,
+  // Next city improvement in current turn costs one commodity less
+  crane: bool,
+  phase: Phase,
+  // Phase to return after robber, road building or gold, which interrupt turn
+  resume: Phase,
+  active: usize,
+  turn: u32,
+  rng: Rng,
+  dice: Box<dyn DiceSource>
+}
+
+impl Game {
+  pub fn new(setup: Setup) -> Self {
+    let dice = setup.rules.dice.source();
+    Self::with_dice(setup, dice)
+  }
+
+  // Game with custom dice instead of dice of its rules, they are restarted on undo
+  pub fn with_dice(setup: Setup, dice: Box<dyn DiceSource>) -> Self {
+    let Setup { scenario, rules, players: players_count, seed, .. } = setup;
+    let rng = Rng::with_seed(seed);
+    let extension = players_count >= EXTENSION_PLAYERS;
+    let cities_and_knights = scenario.cities_and_knights();
+
+    // Progress cards replace development cards in Cities & Knights
+    let mut deck = if cities_and_knights { Vec::new() } else { deck(extension) };
+    rng.shuffle(&mut deck);
+
+    let progress_decks = Track::ALL.map(|track| {
+      let mut cards = if cities_and_knights { progress_deck(track) } else { Vec::new() };
+      rng.shuffle(&mut cards);
+      cards
+    });
+
+    let count = if extension { EXTENSION_BANK_RESOURCES } else { BANK_RESOURCES };
+    let mut bank = Resources::new(count, count, count, count, count);
+    if cities_and_knights {
+      bank += Resources::commodities(BANK_COMMODITIES, BANK_COMMODITIES, BANK_COMMODITIES);
+    }
+
+    // Starting bonus is limited by bank, so every player gets same cards
+    let mut players: Vec<Player> = (0..players_count).map(|_| Player::new()).collect();
+    let seats = u32::try_from(players_count).unwrap_or(u32::MAX);
+    for (resource, count) in rules.starting_bonus.iter() {
+      let count = count.min(bank.get(resource) / seats);
+      bank.remove(resource, count * seats);
+      for player in &mut players {
+        player.resources.add(resource, count);
+      }
+    }
+
+    Self {
+      board: setup.board.clone(),
+      setup,
+      log: Vec::new(),
+      revealed: false,
+      undo: None,
+      scenario,
+      rules,
+      players,
+      bank,
+      buildings: BTreeMap::new(),
+      roads: BTreeMap::new(),
+      ships: BTreeMap::new(),
+      new_ships: Vec::new(),
+      ship_moved: false,
+      road_lengths: vec![0; players_count],
+      longest_road: None,
+      largest_army: None,
+      offer: None,
+      discards: vec![0; players_count],
+      gold: vec![0; players_count],
+      gifts: vec![0; players_count],
+      exchanges: vec![None; players_count],
+      deck,
+      card_played: false,
+      knights: BTreeMap::new(),
+      city_walls: BTreeSet::new(),
+      metropolises: [None; 3],
+      progress_decks,
+      barbarians: 0,
+      robber_released: false,
+      merchant: None,
+      merchant_fleet: None,
+      alchemist: None,
+      crane: false,
+      phase: Phase::Setup { round: 0, settlement: None },
+      resume: Phase::Main,
+      active: 0,
+      turn: 0,
+      rng,
+      dice
+    }
+  }
+
+  // Rebuild game by applying logged actions to new game, events include start events,
+  // so they describe whole game from its start
+  pub fn replay(setup: Setup, log: &[(usize, Action)]) -> Result<(Self, Vec<Event>), Rejection> {
+    let dice = setup.rules.dice.source();
+    Self::replay_with_dice(setup, dice, log)
+  }
+
+  fn replay_with_dice(
+    setup: Setup, dice: Box<dyn DiceSource>, log: &[(usize, Action)]
+  ) -> Result<(Self, Vec<Event>), Rejection> {
+    let mut game = Self::with_dice(setup, dice);
+    let mut events = game.start_events();
+    for (seat, action) in log {
+      events.extend(game.apply(*seat, *action)?);
+    }
+    Ok((game, events))
+  }
+
+  // Same game rebuilt from start with restarted dice up to given log
+  fn rebuild(&self, log: &[(usize, Action)]) -> Result<(Self, Vec<Event>), Rejection> {
+    Self::replay_with_dice(self.setup.clone(), self.dice.restart(), log)
+  }
+
+  // Events of whole game, logged actions are replayed without rejections
+  pub fn history(&self) -> Vec<Event> {
+    self.rebuild(&self.log).map(|(_, events)| events).unwrap_or_default()
+  }
+
+  pub const fn setup(&self) -> &Setup {
+    &self.setup
+  }
+
+  pub const fn turn(&self) -> u32 {
+    self.turn
+  }
+
+  pub const fn phase(&self) -> Phase {
+    self.phase
+  }
+
+  // Player of current turn, or current builder in special building phase
+  pub const fn active(&self) -> usize {
+    self.active
+  }
+
+  pub const fn is_finished(&self) -> bool {
+    matches!(self.phase, Phase::Finished { .. })
+  }
+
+  // Events describing game state before first action
+  pub fn start_events(&self) -> Vec<Event> {
+    let mut events: Vec<Event> = (0..self.players.len())
+      .map(|seat| self.ratios_event(seat))
+      .collect();
+    // Starting bonus is given as production
+    for (seat, player) in self.players.iter().enumerate() {
+      if !player.resources.is_empty() {
+        events.push(Event::ResourcesProduced { seat, resources: player.resources });
+      }
+    }
+    events.push(self.phase_event());
+    events
+  }
+
+  // Only accepted actions are logged, so log replays without rejections
+  pub fn apply(&mut self, seat: usize, action: Action) -> Result<Vec<Event>, Rejection> {
+    let mut events = self.execute(seat, action)?;
+    self.log.push((seat, action));
+    self.revealed = events.iter().any(Event::reveals);
+    events.extend(self.cancel_undo());
+    Ok(events)
+  }
+
+  fn execute(&mut self, seat: usize, action: Action) -> Result<Vec<Event>, Rejection> {
+    if action.cities_and_knights() && !self.scenario.cities_and_knights() {
+      return Err(Rejection::WrongPhase)
+    }
+
+    // Only actions, which players make not in their turns
+    match (self.phase, action) {
+      (Phase::Discard { robber }, Action::Discard(resources)) => {
+        return self.discard(seat, resources, robber)
+      },
+      (Phase::Gold, Action::ChooseGold(resources)) => return self.choose_gold(seat, resources),
+      (Phase::Gift, Action::GiveCards(resources)) => return self.give_cards(seat, resources),
+      (Phase::Desertion { victim, vertex }, Action::RemoveKnight(removed)) if seat == victim => {
+        return self.desert(victim, removed, vertex)
+      },
+      (_, Action::AcceptTrade) if self.trading() => {
+        return self.respond_trade(seat, Response::Accepted)
+      },
+      (_, Action::RejectTrade) if self.trading() => {
+        return self.respond_trade(seat, Response::Rejected)
+      },
+      (_, Action::CounterTrade { give, receive }) if self.trading() => {
+        return self.respond_trade(seat, Response::Countered { give, receive })
+      },
+      _ => {}
+    }
+
+    if seat != self.active {
+      return Err(Rejection::NotYourTurn)
+    }
+
+    let mut events = match (self.phase, action) {
+      (Phase::Setup { round, settlement: None }, Action::BuildSettlement(vertex)) => {
+        self.place_setup_settlement(round, vertex, false)
+      },
+      (Phase::Setup { round, settlement: None }, Action::BuildCity(vertex)) => {
+        self.place_setup_settlement(round, vertex, true)
+      },
+      (Phase::Setup { round, settlement: Some(settlement) }, Action::BuildRoad(edge)) => {
+        self.place_setup_road(round, settlement, edge, false)
+      },
+      (Phase::Setup { round, settlement: Some(settlement) }, Action::BuildShip(edge)) => {
+        self.place_setup_road(round, settlement, edge, true)
+      },
+      (Phase::Roll, Action::RollDice) => Ok(self.roll()),
+      // Only building and buying are allowed in special building phase
+      (Phase::Main | Phase::SpecialBuilding { .. }, Action::BuildRoad(edge)) => {
+        self.build_road(edge)
+      },
+      (Phase::Main | Phase::SpecialBuilding { .. }, Action::BuildSettlement(vertex)) => {
+        self.build_settlement(vertex)
+      },
+      (Phase::Main | Phase::SpecialBuilding { .. }, Action::BuildCity(vertex)) => {
+        self.build_city(vertex, &CITY_COST)
+      },
+      (Phase::Main | Phase::SpecialBuilding { .. }, Action::BuyDevelopmentCard) => {
+        self.buy_development_card()
+      },
+      (Phase::Main | Phase::SpecialBuilding { .. }, Action::BuildShip(edge)) => {
+        self.build_ship(edge)
+      },
+      (Phase::Main, Action::MoveShip { from, to }) => self.move_ship(from, to),
+      (Phase::Main, Action::EndTurn) => Ok(self.end_turn()),
+      (Phase::Robber, Action::MoveRobber { hex, victim }) => self.move_robber(hex, victim),
+      // Development cards may be played before dice roll too
+      (Phase::Roll | Phase::Main, Action::PlayKnight) => self.play_knight(),
+      (Phase::Roll | Phase::Main, Action::PlayRoadBuilding) => self.play_road_building(),
+      (Phase::Roll | Phase::Main, Action::PlayYearOfPlenty(resources)) => {
+        self.play_year_of_plenty(resources)
+      },
+      (Phase::Roll | Phase::Main, Action::PlayMonopoly(resource)) => self.play_monopoly(resource),
+      (_, Action::OfferTrade { give, receive }) if self.trading() => {
+        self.offer_trade(give, receive)
+      },
+      (_, Action::ConfirmTrade { partner }) if self.trading() => self.confirm_trade(partner),
+      (_, Action::CancelTrade) if self.trading() => self.cancel_trade(),
+      (_, Action::MaritimeTrade { give, receive }) if self.trading() => {
+        self.maritime_trade(give, receive)
+      },
+      (Phase::RoadBuilding { remaining }, Action::BuildRoad(edge)) => {
+        self.build_free_road(edge, remaining)
+      },
+      (Phase::SpecialBuilding { ended }, Action::EndTurn) => Ok(self.end_special_building(ended)),
+      (Phase::Main, Action::BuildCityWall(vertex)) => self.build_city_wall(vertex),
+      (Phase::Main, Action::ImproveCity(track)) => self.improve_city(track),
+      (Phase::Main, Action::BuildKnight(vertex)) => self.build_knight(vertex),
+      (Phase::Main, Action::PromoteKnight(vertex)) => self.promote_knight(vertex),
+      (Phase::Main, Action::ActivateKnight(vertex)) => self.activate_knight(vertex),
+      (Phase::Main, Action::MoveKnight { from, to }) => self.move_knight(from, to),
+      (Phase::Main, Action::ChaseRobber(vertex)) => self.chase_robber(vertex),
+      // Alchemist is played before roll, other progress cards after it
+      (Phase::Roll | Phase::Main, Action::PlayProgressCard { card, target }) => {
+        self.play_progress_card(card, target)
+      },
+      (Phase::Taking { victim }, Action::TakeCards(resources)) => {
+        self.take_cards(victim, resources)
+      },
+      _ => Err(Rejection::WrongPhase)
+    }?;
+
+    self.check_victory(&mut events);
+
+    Ok(events)
+  }
+
+  // Trading is allowed after dice roll, with house rule before it too
+  fn trading(&self) -> bool {
+    self.phase == Phase::Main || (self.rules.trade_between_rolls && self.phase == Phase::Roll)
+  }
+
+  const fn phase_event(&self) -> Event {
+    Event::PhaseChanged { seat: self.active, phase: self.phase, turn: self.turn }
+  }
+
+  fn check_settlement_location(
+    &self, seat: usize, vertex: Vertex, need_road: bool
+  ) -> Result<(), Rejection> {
+    if !self.board.has_vertex(vertex) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    if self.buildings.contains_key(&vertex) || self.knights.contains_key(&vertex) {
+      return Err(Rejection::Occupied)
+    }
+
+    if vertex.neighbours().iter().any(|neighbour| self.buildings.contains_key(neighbour)) {
+      return Err(Rejection::TooClose)
+    }
+
+    let connected = vertex.edges().iter().any(|edge| {
+      self.roads.get(edge) == Some(&seat) || self.ships.get(edge) == Some(&seat)
+    });
+    if need_road && !connected {
+      return Err(Rejection::NotConnected)
+    }
+
+    Ok(())
+  }
+
+  fn check_road_location(&self, seat: usize, edge: Edge) -> Result<(), Rejection> {
+    if !self.board.has_edge(edge) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    if self.roads.contains_key(&edge) || self.ships.contains_key(&edge) {
+      return Err(Rejection::Occupied)
+    }
+
+    // Road connects to own building, or to own road through vertex without opponent building
+    // or knight
+    let connected = edge.vertices().iter().any(|vertex| {
+      self.buildings.get(vertex).map_or_else(
+        || {
+          !self.knight_blocks(seat, *vertex)
+          && vertex.edges().iter().any(|other| self.roads.get(other) == Some(&seat))
+        },
+        |building| building.owner == seat
+      )
+    });
+
+    if !connected {
+      return Err(Rejection::NotConnected)
+    }
+
+    Ok(())
+  }
+
+  fn pay(&mut self, cost: &Resources) -> Result<(), Rejection> {
+    let player = &mut self.players[self.active];
+    if !player.resources.contains(cost) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    player.resources -= *cost;
+    self.bank += *cost;
+
+    Ok(())
+  }
+
+  fn place_settlement(&mut self, vertex: Vertex) -> Event {
+    let seat = self.active;
+    self.players[seat].settlements -= 1;
+    self.buildings.insert(vertex, Building { owner: seat, kind: BuildingKind::Settlement });
+    Event::SettlementBuilt { seat, vertex }
+  }
+
+  fn place_city(&mut self, vertex: Vertex) -> Event {
+    let seat = self.active;
+    let player = &mut self.players[seat];
+    player.cities -= 1;
+    player.settlements += 1;
+    self.buildings.insert(vertex, Building { owner: seat, kind: BuildingKind::City });
+    Event::CityBuilt { seat, vertex }
+  }
+
+  fn place_road(&mut self, edge: Edge) -> Event {
+    let seat = self.active;
+    self.players[seat].roads -= 1;
+    self.roads.insert(edge, seat);
+    Event::RoadBuilt { seat, edge }
+  }
+
+  // In Cities & Knights city is placed instead of settlement in last round
+  fn place_setup_settlement(
+    &mut self, round: u8, vertex: Vertex, city: bool
+  ) -> Result<Vec<Event>, Rejection> {
+    if city != (self.scenario.cities_and_knights() && round + 1 == self.rules.setup_rounds) {
+      return Err(Rejection::WrongPhase)
+    }
+
+    self.check_settlement_location(self.active, vertex, false)?;
+
+    if let Some(home) = self.scenario.setup_island() {
+      if self.board.vertex_island(vertex) != self.board.island(home) {
+        return Err(Rejection::InvalidLocation)
+      }
+    }
+
+    let mut events = vec![self.place_settlement(vertex)];
+    // Setup city is placed as settlement, which is upgraded at once
+    if city {
+      events.push(self.place_city(vertex));
+    }
+    events.extend(self.settlement_built(self.active, vertex));
+    events.extend(self.harbor_reached(vertex));
+
+    // Setup islands are home islands, so they grant no bonus
+    if let Some(island) = self.board.vertex_island(vertex) {
+      self.players[self.active].islands.insert(island);
+    }
+
+    // Settlement or city of last round yields one card of every adjacent terrain
+    let mut gold = vec![0; self.players.len()];
+    if round + 1 == self.rules.setup_rounds {
+      let mut resources = Resources::default();
+      for (_, tile) in self.board.vertex_tiles(vertex) {
+        if tile.terrain == Terrain::Gold {
+          gold[self.active] += 1;
+        } else if let Some(resource) = tile.terrain.resource() {
+          if self.bank.get(resource) > resources.get(resource) {
+            resources.add(resource, 1);
+          }
+        }
+      }
+
+      if !resources.is_empty() {
+        events.push(self.give(self.active, resources));
+      }
+    }
+
+    events.extend(self.require_gold(gold));
+    self.phase = self.gold_or(Phase::Setup { round, settlement: Some(vertex) });
+    events.push(self.phase_event());
+
+    Ok(events)
+  }
+
+  // In seafarers scenarios ship may be placed instead of setup road
+  fn place_setup_road(
+    &mut self, round: u8, settlement: Vertex, edge: Edge, ship: bool
+  ) -> Result<Vec<Event>, Rejection> {
+    let valid = if ship {
+      self.scenario.seafarers() && self.board.has_sea_edge(edge)
+    } else {
+      self.board.has_edge(edge)
+    };
+    if !valid {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    if self.roads.contains_key(&edge) || self.ships.contains_key(&edge) {
+      return Err(Rejection::Occupied)
+    }
+
+    if !edge.vertices().contains(&settlement) {
+      return Err(Rejection::NotConnected)
+    }
+
+    let mut events = vec![if ship { self.place_ship(edge) } else { self.place_road(edge) }];
+    events.extend(self.road_built(self.active));
+
+    // Snake order: forward in even rounds, backward in odd, last player of round
+    // places again as first player of next round
+    let forward = round.is_multiple_of(2);
+    let round_end = if forward { self.active == self.players.len() - 1 } else { self.active == 0 };
+
+    if !round_end {
+      self.active = if forward { self.active + 1 } else { self.active - 1 };
+      self.phase = Phase::Setup { round, settlement: None };
+    } else if round + 1 < self.rules.setup_rounds {
+      self.phase = Phase::Setup { round: round + 1, settlement: None };
+    } else {
+      self.active = 0;
+      self.turn = 1;
+      self.phase = Phase::Roll;
+    }
+
+    events.push(self.phase_event());
+
+    Ok(events)
+  }
+
+  // Move resources from bank to player hand, caller must check bank has them
+  fn give(&mut self, seat: usize, resources: Resources) -> Event {
+    self.bank -= resources;
+    self.players[seat].resources += resources;
+    Event::ResourcesProduced { seat, resources }
+  }
+
+  fn roll(&mut self) -> Vec<Event> {
+    let dice = self.alchemist.take().unwrap_or_else(|| self.dice.roll(&self.rng));
+
+    let mut events = vec![Event::DiceRolled { seat: self.active, dice }];
+
+    // Event die is resolved before production, second die is red one
+    if self.scenario.cities_and_knights() {
+      events.extend(self.roll_event_die(dice[1]));
+    }
+
+    let number = dice[0] + dice[1];
+    if number == 7 {
+      events.extend(self.start_robber());
+      return events
+    }
+
+    events.extend(self.produce(number));
+
+    self.phase = self.gold_or(Phase::Main);
+    events.push(self.phase_event());
+
+    events
+  }
+
+  fn start_robber(&mut self) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for (seat, player) in self.players.iter().enumerate() {
+      let total = player.resources.total();
+      if total > self.rules.discard_limit + self.city_wall_bonus(seat) {
+        // Valid discard limit is at least 1, so there is always a card to discard
+        let count = total / 2;
+        self.discards[seat] = count;
+        events.push(Event::DiscardRequired { seat, count });
+      }
+    }
+
+    self.resume = Phase::Main;
+    self.phase = if events.is_empty() {
+      self.robber_phase()
+    } else {
+      Phase::Discard { robber: true }
+    };
+    events.push(self.phase_event());
+
+    events
+  }
+
+  fn discard(
+    &mut self, seat: usize, resources: Resources, robber: bool
+  ) -> Result<Vec<Event>, Rejection> {
+    let count = self.discards.get(seat).copied().unwrap_or(0);
+    if count == 0 {
+      return Err(Rejection::NothingToDiscard)
+    }
+
+    if resources.total() != count {
+      return Err(Rejection::WrongDiscardCount)
+    }
+
+    let player = &mut self.players[seat];
+    if !player.resources.contains(&resources) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    player.resources -= resources;
+    self.bank += resources;
+    self.discards[seat] = 0;
+
+    let mut events = vec![Event::Discarded { seat, resources }];
+
+    if self.discards.iter().all(|count| *count == 0) {
+      self.phase = if robber { self.robber_phase() } else { self.resume };
+      events.push(self.phase_event());
+    }
+
+    Ok(events)
+  }
+
+  // Robbable opponents with building next to hex
+  fn robber_victims(&self, hex: Hex) -> Vec<usize> {
+    let mut victims = hex.vertices().iter()
+      .filter_map(|vertex| self.buildings.get(vertex))
+      .map(|building| building.owner)
+      .filter(|owner| *owner != self.active && self.robbable(*owner))
+      .collect::<Vec<usize>>();
+
+    victims.sort_unstable();
+    victims.dedup();
+
+    victims
+  }
+
+  // In seafarers scenarios pirate is moved instead of robber, when sea hex is chosen
+  fn move_robber(&mut self, hex: Hex, victim: Option<usize>) -> Result<Vec<Event>, Rejection> {
+    let pirate = self.scenario.seafarers() && self.board.is_sea(hex);
+
+    let valid_hex = if pirate {
+      self.board.pirate() != Some(hex)
+    } else {
+      self.board.is_land(hex) && hex != self.board.robber()
+    };
+    if !valid_hex {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    let victims = if pirate { self.pirate_victims(hex) } else { self.robber_victims(hex) };
+    let valid_victim = victim.map_or_else(
+      || victims.is_empty(),
+      |victim| victims.contains(&victim)
+    );
+    if !valid_victim {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    let seat = self.active;
+    let mut events = if pirate {
+      self.board.set_pirate(hex);
+      vec![Event::PirateMoved { seat, hex }]
+    } else {
+      self.board.set_robber(hex);
+      vec![Event::RobberMoved { seat, hex }]
+    };
+
+    if let Some(victim) = victim {
+      events.push(self.steal(victim));
+    }
+
+    self.phase = self.resume;
+    events.push(self.phase_event());
+
+    Ok(events)
+  }
+
+  // Take random card from victim hand, victim must have cards
+  fn steal(&mut self, victim: usize) -> Event {
+    let thief = self.active;
+    let hand = self.players[victim].resources;
+
+    let mut index = self.rng.u32(..hand.total());
+    let resource_option = hand.iter().find_map(|(resource, count)| {
+      if index < count { return Some(resource) }
+      index -= count;
+      None
+    });
+    // SAFETY: index is less than total cards count, so it always points to some resource
+    let resource = unsafe { resource_option.unwrap_unchecked() };
+
+    self.players[victim].resources.remove(resource, 1);
+    self.players[thief].resources.add(resource, 1);
+
+    Event::CardStolen { thief, victim, resource: Some(resource) }
+  }
+
+  fn produce(&mut self, number: u8) -> Vec<Event> {
+    let mut owed = vec![Resources::default(); self.players.len()];
+    let mut gold = vec![0; self.players.len()];
+    let cities_and_knights = self.scenario.cities_and_knights();
+
+    for (hex, tile) in self.board.tiles() {
+      if tile.token != Some(number) || *hex == self.board.robber() {
+        continue
+      }
+
+      let resource = tile.terrain.resource();
+      if resource.is_none() && tile.terrain != Terrain::Gold {
+        continue
+      }
+
+      for vertex in hex.vertices() {
+        if let Some(building) = self.buildings.get(&vertex) {
+          let count = match building.kind {
+            BuildingKind::Settlement => 1,
+            BuildingKind::City => 2
+          };
+          let Some(resource) = resource else {
+            // Gold is chosen by player after bank pays other resources
+            gold[building.owner] += count;
+            continue
+          };
+
+          // In Cities & Knights city produces commodity instead of second card of some terrains
+          let debt = &mut owed[building.owner];
+          match resource.commodity().filter(|_| cities_and_knights && count > 1) {
+            Some(commodity) => {
+              debt.add(resource, 1);
+              debt.add(commodity, 1);
+            },
+            None => debt.add(resource, count)
+          }
+        }
+      }
+    }
+
+    let mut events = Vec::new();
+
+    // Bank shortage rule: if bank can not pay everyone owed resource, nobody receives it,
+    // except when only one player is owed it, then that player gets all cards left in bank
+    for resource in Resource::ALL.into_iter().chain(Resource::COMMODITIES) {
+      let total = owed.iter().map(|resources| resources.get(resource)).sum::<u32>();
+      let available = self.bank.get(resource);
+
+      if total <= available {
+        continue
+      }
+
+      let mut creditors = owed.iter_mut().filter(|resources| resources.get(resource) > 0);
+      // SAFETY: total is more than available, so at least one player is owed resource
+      let first = unsafe { creditors.next().unwrap_unchecked() };
+
+      if creditors.next().is_none() {
+        first.remove(resource, total - available);
+      } else {
+        for resources in &mut owed {
+          let count = resources.get(resource);
+          resources.remove(resource, count);
+        }
+        events.push(Event::ProductionBlocked { resource });
+      }
+    }
+
+    self.aqueduct(&owed, &mut gold);
+
+    for (seat, resources) in owed.into_iter().enumerate() {
+      if !resources.is_empty() {
+        events.push(self.give(seat, resources));
+      }
+    }
+
+    events.extend(self.require_gold(gold));
+
+    events
+  }
+
+  fn build_road(&mut self, edge: Edge) -> Result<Vec<Event>, Rejection> {
+    self.check_road_location(self.active, edge)?;
+
+    if self.players[self.active].roads == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(&ROAD_COST)?;
+
+    let mut events = vec![self.place_road(edge)];
+    events.extend(self.road_built(self.active));
+
+    Ok(events)
+  }
+
+  fn build_settlement(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    self.check_settlement_location(self.active, vertex, true)?;
+
+    if self.players[self.active].settlements == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(&SETTLEMENT_COST)?;
+
+    let mut events = vec![self.place_settlement(vertex)];
+    events.extend(self.settlement_built(self.active, vertex));
+    events.extend(self.harbor_reached(vertex));
+    events.extend(self.island_reached(vertex));
+
+    Ok(events)
+  }
+
+  // Medicine progress card upgrades settlement with lower cost
+  fn build_city(&mut self, vertex: Vertex, cost: &Resources) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    let Some(building) = self.buildings.get(&vertex) else {
+      return Err(Rejection::NotOwned)
+    };
+
+    if building.owner != seat || building.kind != BuildingKind::Settlement {
+      return Err(Rejection::NotOwned)
+    }
+
+    if self.players[seat].cities == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(cost)?;
+
+    Ok(vec![self.place_city(vertex)])
+  }
+
+  // Cards bought in turn or special building phase become playable
+  fn unlock_cards(&mut self) {
+    let player = &mut self.players[self.active];
+    let new_cards = std::mem::take(&mut player.new_cards);
+    player.cards.extend(new_cards);
+  }
+
+  fn end_turn(&mut self) -> Vec<Event> {
+    self.unlock_cards();
+    self.card_played = false;
+    self.offer = None;
+    self.new_ships.clear();
+    self.ship_moved = false;
+
+    let ended = self.active;
+    let mut events: Vec<Event> = self.end_progress_effects().into_iter().collect();
+
+    self.active = (ended + 1) % self.players.len();
+
+    if self.players.len() >= EXTENSION_PLAYERS {
+      self.phase = Phase::SpecialBuilding { ended };
+    } else {
+      self.turn += 1;
+      self.phase = Phase::Roll;
+    }
+
+    events.push(self.phase_event());
+    events
+  }
+
+  // Builder passes to next player, after last one next turn starts
+  fn end_special_building(&mut self, ended: usize) -> Vec<Event> {
+    self.unlock_cards();
+
+    self.active = (self.active + 1) % self.players.len();
+
+    if self.active == ended {
+      self.active = (ended + 1) % self.players.len();
+      self.turn += 1;
+      self.phase = Phase::Roll;
+    }
+
+    vec![self.phase_event()]
+  }
+}
\ No newline at end of file
@@ -0,0 +1,150 @@
+use crate::game::{
+  board::{ Edge, Hex, Vertex },
+  cards::{ DevelopmentCard, ProgressCard },
+  resources::Resources
+};
+use super::{ Building, Game, Phase, Score };
+
+// Player state, as every player sees it
+#[derive(Clone, Debug)]
+pub struct PlayerView {
+  // Cards counts in hand
+  pub resources: u32,
+  pub development_cards: u32,
+  pub progress_cards: u32,
+  // Played knights
+  pub knights: u32,
+  // Hidden victory point cards are counted only for observer itself
+  pub score: Score,
+  // Pieces left in supply
+  pub roads: u8,
+  pub settlements: u8,
+  pub cities: u8,
+  pub ships: u8,
+  pub improvements: [u8; 3]
+}
+
+// Knight on board, as every player sees it
+#[derive(Clone, Copy, Debug)]
+pub struct KnightView {
+  pub vertex: Vertex,
+  pub owner: usize,
+  pub level: u8,
+  pub active: bool
+}
+
+// Everything player of seat knows about game, hidden cards of opponents are counts only,
+// decks order and random state are never observed
+#[derive(Clone, Debug)]
+pub struct Observation {
+  pub seat: usize,
+  pub phase: Phase,
+  pub active: usize,
+  pub turn: u32,
+  pub resources: Resources,
+  // Own cards, including bought in current turn
+  pub development_cards: Vec<DevelopmentCard>,
+  pub progress_cards: Vec<ProgressCard>,
+  // Cards count, which observer must discard, choose or give
+  pub discard: u32,
+  pub gold: u32,
+  pub gift: u32,
+  pub ratios: Resources,
+  // Ordered by seat
+  pub players: Vec<PlayerView>,
+  pub buildings: Vec<(Vertex, Building)>,
+  pub roads: Vec<(Edge, usize)>,
+  pub ships: Vec<(Edge, usize)>,
+  pub knights: Vec<KnightView>,
+  pub city_walls: Vec<Vertex>,
+  // City with metropolis of every improvement track, indexed as track
+  pub metropolises: [Option<Vertex>; 3],
+  pub robber: Hex,
+  pub pirate: Option<Hex>,
+  pub bank: Resources,
+  // Open offer of active player as its give and receive
+  pub offer: Option<(Resources, Resources)>,
+  pub barbarians: u8
+}
+
+impl Game {
+  // Game waits for decision of seat: its turn, discard, gold choice, gift, removed knight,
+  // trade answer or undo vote
+  pub fn awaits(&self, seat: usize) -> bool {
+    match self.phase {
+      Phase::Finished { .. } => false,
+      _ if self.undo_vote_pending(seat) || self.unanswered_offer(seat).is_some() => true,
+      Phase::Discard { .. } => self.discards[seat] > 0,
+      Phase::Gold => self.gold[seat] > 0,
+      Phase::Gift => self.gifts[seat] > 0,
+      Phase::Desertion { victim, .. } => seat == victim,
+      _ => seat == self.active
+    }
+  }
+
+  pub fn observe(&self, seat: usize) -> Observation {
+    let player = &self.players[seat];
+
+    let players = self.players.iter().enumerate()
+      .map(|(other, player)| {
+        let mut score = self.score(other);
+        if other != seat {
+          score.victory_cards = 0;
+        }
+
+        PlayerView {
+          resources: player.resources.total(),
+          development_cards: count(player.cards.len() + player.new_cards.len()),
+          progress_cards: count(player.progress_cards.len()),
+          knights: player.knights,
+          score,
+          roads: player.roads,
+          settlements: player.settlements,
+          cities: player.cities,
+          ships: player.ships,
+          improvements: player.improvements
+        }
+      })
+      .collect();
+
+    let knights = self.knights.iter()
+      .map(|(vertex, knight)| KnightView {
+        vertex: *vertex,
+        owner: knight.owner,
+        level: knight.level,
+        active: knight.active
+      })
+      .collect();
+
+    Observation {
+      seat,
+      phase: self.phase,
+      active: self.active,
+      turn: self.turn,
+      resources: player.resources,
+      development_cards: player.cards.iter().chain(&player.new_cards).copied().collect(),
+      progress_cards: player.progress_cards.clone(),
+      discard: self.discards[seat],
+      gold: self.gold[seat],
+      gift: self.gifts[seat],
+      ratios: self.trade_ratios(seat),
+      players,
+      buildings: self.buildings.iter().map(|(vertex, building)| (*vertex, *building)).collect(),
+      roads: self.roads.iter().map(|(edge, owner)| (*edge, *owner)).collect(),
+      ships: self.ships.iter().map(|(edge, owner)| (*edge, *owner)).collect(),
+      knights,
+      city_walls: self.city_walls.iter().copied().collect(),
+      metropolises: self.metropolises,
+      robber: self.board.robber(),
+      pirate: self.board.pirate(),
+      bank: self.bank,
+      offer: self.offer_terms(),
+      barbarians: self.barbarians
+    }
+  }
+}
+
+// Cards count never exceeds deck size
+fn count(cards: usize) -> u32 {
+  u32::try_from(cards).unwrap_or(u32::MAX)
+}
\ No newline at end of file
@@ -0,0 +1,526 @@
+use std::iter;
+use crate::game::{
+  action::{ Rejection, Target },
+  board::{ Edge, Hex, Terrain, Vertex },
+  cards::ProgressCard,
+  event::Event,
+  resources::{ Resource, Resources }
+};
+use super::{ Game, Phase, MAX_PLAYERS };
+
+const MEDICINE_CITY_COST: Resources = Resources::new(0, 0, 2, 1, 0);
+// Cards taken from every other player with resource and trade monopoly
+const RESOURCE_MONOPOLY_CARDS: u32 = 2;
+const TRADE_MONOPOLY_CARDS: u32 = 1;
+// Cards given by every player with more points with wedding and taken with master merchant
+pub(super) const GIFT_CARDS: u32 = 2;
+// Cards yielded by every own fields or mountains hex with irrigation and mining
+const HARVEST_CARDS: u32 = 2;
+// Numbers, which inventor can not move
+const FIXED_TOKENS: [u8; 4] = [2, 6, 8, 12];
+
+impl Game {
+  // Target kind must match card, played card goes under its deck
+  pub(super) fn play_progress_card(
+    &mut self, card: ProgressCard, target: Target
+  ) -> Result<Vec<Event>, Rejection> {
+    if !self.players[self.active].progress_cards.contains(&card) {
+      return Err(Rejection::NoCard)
+    }
+
+    if (card == ProgressCard::Alchemist) != (self.phase == Phase::Roll) {
+      return Err(Rejection::WrongPhase)
+    }
+
+    let effect = match (card, target) {
+      (ProgressCard::Alchemist, Target::Dice(dice)) => self.play_alchemist(dice),
+      (ProgressCard::Crane, Target::None) => {
+        self.crane = true;
+        Ok(Vec::new())
+      },
+      (ProgressCard::Engineer, Target::Vertex(vertex)) => {
+        self.check_city_wall(vertex)?;
+        Ok(vec![self.place_city_wall(vertex)])
+      },
+      (ProgressCard::Inventor, Target::Hexes(first, second)) => {
+        self.play_inventor(first, second)
+      },
+      (ProgressCard::Irrigation, Target::None) => Ok(self.harvest(Terrain::Fields)),
+      (ProgressCard::Mining, Target::None) => Ok(self.harvest(Terrain::Mountains)),
+      (ProgressCard::Medicine, Target::Vertex(vertex)) => {
+        self.build_city(vertex, &MEDICINE_CITY_COST)
+      },
+      (ProgressCard::RoadBuilding, Target::None) => {
+        self.check_road_building()?;
+        Ok(vec![self.start_road_building()])
+      },
+      (ProgressCard::Smith, Target::Vertices(first, second)) => {
+        self.promote_knights(&iter::once(first).chain(second).collect::<Vec<_>>())
+      },
+      (ProgressCard::CommercialHarbor, Target::Offers(offers)) => {
+        self.play_commercial_harbor(offers)
+      },
+      (ProgressCard::Merchant, Target::Hex(hex)) => self.play_merchant(hex),
+      (ProgressCard::MerchantFleet, Target::Resource(resource)) => {
+        self.merchant_fleet = Some(resource);
+        Ok(vec![self.ratios_event(self.active)])
+      },
+      (ProgressCard::MasterMerchant, Target::Seat(victim)) => {
+        self.check_richer(victim)?;
+        Ok(self.show_hand(victim))
+      },
+      (ProgressCard::ResourceMonopoly, Target::Resource(resource)) => {
+        if resource.is_commodity() {
+          return Err(Rejection::CommodityNotAllowed)
+        }
+        Ok(vec![self.take_from_others(resource, RESOURCE_MONOPOLY_CARDS)])
+      },
+      (ProgressCard::TradeMonopoly, Target::Resource(resource)) => {
+        if !resource.is_commodity() {
+          return Err(Rejection::InvalidTarget)
+        }
+        Ok(vec![self.take_from_others(resource, TRADE_MONOPOLY_CARDS)])
+      },
+      (ProgressCard::Bishop, Target::Hex(hex)) => self.play_bishop(hex),
+      (ProgressCard::Deserter, Target::SeatVertex(victim, vertex)) => {
+        self.play_deserter(victim, vertex)
+      },
+      (ProgressCard::Diplomat, Target::Edges(edge, replacement)) => {
+        self.play_diplomat(edge, replacement)
+      },
+      (ProgressCard::Intrigue, Target::Vertex(vertex)) => self.play_intrigue(vertex),
+      (ProgressCard::Saboteur, Target::None) => Ok(self.play_saboteur()),
+      (ProgressCard::Spy, Target::Seat(victim)) => self.play_spy(victim),
+      (ProgressCard::Warlord, Target::None) => Ok(self.activate_knights()),
+      (ProgressCard::Wedding, Target::None) => Ok(self.play_wedding()),
+      // Victory point cards are never held, so they are never played
+      _ => Err(Rejection::InvalidTarget)
+    }?;
+
+    // Card is played before its phase change
+    let mut events = vec![self.remove_progress_card(card)];
+    events.extend(effect);
+
+    Ok(events)
+  }
+
+  fn remove_progress_card(&mut self, card: ProgressCard) -> Event {
+    let cards = &mut self.players[self.active].progress_cards;
+    if let Some(index) = cards.iter().position(|held| *held == card) {
+      cards.remove(index);
+    }
+
+    self.progress_decks[card.track() as usize].insert(0, card);
+
+    Event::ProgressCardPlayed { seat: self.active, card }
+  }
+
+  // Effects of progress cards, which last until end of turn
+  pub(super) fn end_progress_effects(&mut self) -> Option<Event> {
+    self.crane = false;
+    self.merchant_fleet.take().map(|_| self.ratios_event(self.active))
+  }
+
+  fn play_alchemist(&mut self, dice: [u8; 2]) -> Result<Vec<Event>, Rejection> {
+    if dice.iter().any(|die| !(1..=6).contains(die)) {
+      return Err(Rejection::InvalidTarget)
+    }
+
+    self.alchemist = Some(dice);
+
+    Ok(Vec::new())
+  }
+
+  fn play_inventor(&mut self, first: Hex, second: Hex) -> Result<Vec<Event>, Rejection> {
+    let movable = |hex| {
+      self.board.tile(hex)
+        .and_then(|tile| tile.token)
+        .is_some_and(|token| !FIXED_TOKENS.contains(&token))
+    };
+
+    if first == second || !movable(first) || !movable(second) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    self.board.swap_tokens(first, second);
+
+    Ok(vec![Event::TokensSwapped { first, second }])
+  }
+
+  // Every hex of terrain next to own building yields cards once, limited by bank
+  fn harvest(&mut self, terrain: Terrain) -> Vec<Event> {
+    let seat = self.active;
+    let Some(resource) = terrain.resource() else { return Vec::new() };
+
+    let count = self.board.tiles()
+      .filter(|(hex, tile)| {
+        tile.terrain == terrain
+        && hex.vertices().iter().any(|vertex| {
+          self.buildings.get(vertex).is_some_and(|building| building.owner == seat)
+        })
+      })
+      .map(|_| HARVEST_CARDS)
+      .sum::<u32>()
+      .min(self.bank.get(resource));
+
+    if count == 0 {
+      return Vec::new()
+    }
+
+    let mut resources = Resources::default();
+    resources.add(resource, count);
+
+    vec![self.give(seat, resources)]
+  }
+
+  // Merchant is placed on producing hex next to own building, holder trades its
+  // resource at 2:1 and gets victory point
+  fn play_merchant(&mut self, hex: Hex) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    let produces = self.board.tile(hex).is_some_and(|tile| tile.terrain.resource().is_some());
+    let near = hex.vertices().iter().any(|vertex| {
+      self.buildings.get(vertex).is_some_and(|building| building.owner == seat)
+    });
+    if !produces || !near {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    let previous = self.merchant.replace((hex, seat));
+
+    let mut events = vec![Event::MerchantPlaced { seat, hex }, self.ratios_event(seat)];
+    if let Some((_, owner)) = previous.filter(|(_, owner)| *owner != seat) {
+      events.push(self.ratios_event(owner));
+    }
+
+    Ok(events)
+  }
+
+  fn check_richer(&self, victim: usize) -> Result<(), Rejection> {
+    if victim == self.active || victim >= self.players.len() {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    if self.score(victim).total() <= self.score(self.active).total() {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    Ok(())
+  }
+
+  // Hand of victim is shown to active player, who takes cards of own choice from it
+  fn show_hand(&mut self, victim: usize) -> Vec<Event> {
+    let resources = self.players[victim].resources;
+    if resources.is_empty() {
+      return Vec::new()
+    }
+
+    self.phase = Phase::Taking { victim };
+
+    vec![
+      Event::HandShown { seat: victim, viewer: self.active, resources: Some(resources) },
+      self.phase_event()
+    ]
+  }
+
+  pub(super) fn take_cards(
+    &mut self, victim: usize, resources: Resources
+  ) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+    let count = self.players[victim].resources.total().min(GIFT_CARDS);
+
+    if resources.total() != count {
+      return Err(Rejection::WrongGiftCount)
+    }
+
+    if !self.players[victim].resources.contains(&resources) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    self.players[victim].resources -= resources;
+    self.players[seat].resources += resources;
+
+    self.phase = Phase::Main;
+
+    Ok(vec![
+      Event::CardsGiven { seat: victim, receiver: seat, count, resources: Some(resources) },
+      self.phase_event()
+    ])
+  }
+
+  fn take_from_others(&mut self, resource: Resource, limit: u32) -> Event {
+    let seat = self.active;
+    let mut count = 0;
+
+    for (other, player) in self.players.iter_mut().enumerate() {
+      if other != seat {
+        let taken = player.resources.get(resource).min(limit);
+        player.resources.remove(resource, taken);
+        count += taken;
+      }
+    }
+    self.players[seat].resources.add(resource, count);
+
+    Event::MonopolyTaken { seat, resource, count }
+  }
+
+  // Bishop moves robber and steals from every player next to hex
+  fn play_bishop(&mut self, hex: Hex) -> Result<Vec<Event>, Rejection> {
+    if self.robber_locked() {
+      return Err(Rejection::RobberInactive)
+    }
+
+    if !self.board.is_land(hex) || hex == self.board.robber() {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    self.board.set_robber(hex);
+
+    let mut events = vec![Event::RobberMoved { seat: self.active, hex }];
+    for victim in self.robber_victims(hex) {
+      events.push(self.steal(victim));
+    }
+
+    Ok(events)
+  }
+
+  // Opponent knight next to own road is displaced
+  fn play_intrigue(&mut self, vertex: Vertex) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    let Some(knight) = self.knights.get(&vertex).copied().filter(|knight| knight.owner != seat)
+    else {
+      return Err(Rejection::InvalidTarget)
+    };
+
+    if !vertex.edges().iter().any(|edge| self.roads.get(edge) == Some(&seat)) {
+      return Err(Rejection::NotConnected)
+    }
+
+    self.knights.remove(&vertex);
+
+    let mut events = self.displace_knight(vertex, knight);
+    events.extend(self.vertex_vacated(vertex));
+
+    Ok(events)
+  }
+
+  fn play_spy(&mut self, victim: usize) -> Result<Vec<Event>, Rejection> {
+    let thief = self.active;
+
+    if victim == thief || victim >= self.players.len() {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    let cards = &mut self.players[victim].progress_cards;
+    if cards.is_empty() {
+      return Err(Rejection::InvalidVictim)
+    }
+
+    let card = cards.remove(self.rng.usize(..cards.len()));
+    self.players[thief].progress_cards.push(card);
+
+    Ok(vec![Event::ProgressCardStolen { thief, victim, card: Some(card) }])
+  }
+
+  // Every player with more points gives cards of own choice
+  fn play_wedding(&mut self) -> Vec<Event> {
+    let points = self.score(self.active).total();
+
+    let mut events = Vec::new();
+    for giver in 0..self.players.len() {
+      let count = self.players[giver].resources.total().min(GIFT_CARDS);
+      if giver != self.active && count > 0 && self.score(giver).total() > points {
+        self.gifts[giver] = count;
+        events.push(Event::GiftRequired { seat: giver, count });
+      }
+    }
+
+    self.start_gifts(events)
+  }
+
+  // Every opponent with commodities gives commodity of own choice for offered resource,
+  // offers to other opponents are not made
+  fn play_commercial_harbor(
+    &mut self, offers: [Option<Resource>; MAX_PLAYERS]
+  ) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    let mut offered = Resources::default();
+    for (other, offer) in offers.into_iter().enumerate() {
+      let Some(resource) = offer else { continue };
+
+      if other == seat || other >= self.players.len() {
+        return Err(Rejection::InvalidVictim)
+      }
+
+      if resource.is_commodity() {
+        return Err(Rejection::CommodityNotAllowed)
+      }
+
+      offered.add(resource, 1);
+    }
+
+    if !self.players[seat].resources.contains(&offered) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    let mut events = Vec::new();
+    for (other, offer) in offers.into_iter().enumerate() {
+      if offer.is_some() && self.players[other].resources.has_commodities() {
+        self.gifts[other] = 1;
+        self.exchanges[other] = offer;
+        events.push(Event::GiftRequired { seat: other, count: 1 });
+      }
+    }
+
+    Ok(self.start_gifts(events))
+  }
+
+  // Cards seat may give in gift phase: commodities for commercial harbor, any for wedding
+  pub(super) fn giftable(&self, seat: usize) -> Resources {
+    let hand = self.players[seat].resources;
+    if self.exchanges[seat].is_none() {
+      return hand
+    }
+
+    let [paper, cloth, coin] = Resource::COMMODITIES.map(|commodity| hand.get(commodity));
+    Resources::commodities(paper, cloth, coin)
+  }
+
+  fn start_gifts(&mut self, mut events: Vec<Event>) -> Vec<Event> {
+    if !events.is_empty() {
+      self.phase = Phase::Gift;
+      events.push(self.phase_event());
+    }
+
+    events
+  }
+
+  // Givers hold active player, active player hand only grows until every gift is given,
+  // so offered resources are still in it
+  pub(super) fn give_cards(
+    &mut self, seat: usize, resources: Resources
+  ) -> Result<Vec<Event>, Rejection> {
+    let count = self.gifts.get(seat).copied().unwrap_or(0);
+    if count == 0 {
+      return Err(Rejection::NothingToGive)
+    }
+
+    if resources.total() != count {
+      return Err(Rejection::WrongGiftCount)
+    }
+
+    let exchange = self.exchanges[seat];
+    if exchange.is_some() && resources.iter().any(|(resource, _)| !resource.is_commodity()) {
+      return Err(Rejection::CommodityRequired)
+    }
+
+    if !self.players[seat].resources.contains(&resources) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    let receiver = self.active;
+    self.players[seat].resources -= resources;
+    self.players[receiver].resources += resources;
+    self.gifts[seat] = 0;
+
+    let mut events =
+      vec![Event::CardsGiven { seat, receiver, count, resources: Some(resources) }];
+
+    if let Some(resource) = self.exchanges[seat].take() {
+      let mut offered = Resources::default();
+      offered.add(resource, 1);
+      self.players[receiver].resources -= offered;
+      self.players[seat].resources += offered;
+      events.push(Event::CardsGiven {
+        seat: receiver,
+        receiver: seat,
+        count: 1,
+        resources: Some(offered)
+      });
+    }
+
+    if self.gifts.iter().all(|count| *count == 0) {
+      self.phase = Phase::Main;
+      events.push(self.phase_event());
+    }
+
+    Ok(events)
+  }
+
+  // Every player with at least as many points as active player discards half of hand
+  fn play_saboteur(&mut self) -> Vec<Event> {
+    let points = self.score(self.active).total();
+
+    let mut events = Vec::new();
+    for seat in 0..self.players.len() {
+      let count = self.players[seat].resources.total() / 2;
+      if seat != self.active && count > 0 && self.score(seat).total() >= points {
+        self.discards[seat] = count;
+        events.push(Event::DiscardRequired { seat, count });
+      }
+    }
+
+    if !events.is_empty() {
+      self.resume = Phase::Main;
+      self.phase = Phase::Discard { robber: false };
+      events.push(self.phase_event());
+    }
+
+    events
+  }
+
+  // Open road has no building, knight or other route of its owner at one of its ends,
+  // only own removed road may be placed again for free
+  fn play_diplomat(
+    &mut self, edge: Edge, replacement: Option<Edge>
+  ) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+    let Some(owner) = self.roads.get(&edge).copied() else {
+      return Err(Rejection::InvalidTarget)
+    };
+
+    let open = edge.vertices().iter().any(|vertex| {
+      self.buildings.get(vertex).is_none_or(|building| building.owner != owner)
+      && self.knights.get(vertex).is_none_or(|knight| knight.owner != owner)
+      && vertex.edges().iter().all(|other| {
+        *other == edge
+        || (self.roads.get(other) != Some(&owner) && self.ships.get(other) != Some(&owner))
+      })
+    });
+    if !open {
+      return Err(Rejection::RoadNotOpen)
+    }
+
+    if replacement.is_some() && owner != seat {
+      return Err(Rejection::NotOwned)
+    }
+
+    self.roads.remove(&edge);
+
+    if let Some(replacement) = replacement {
+      let location = if replacement == edge {
+        Err(Rejection::InvalidLocation)
+      } else {
+        self.check_road_location(seat, replacement)
+      };
+      if let Err(rejection) = location {
+        self.roads.insert(edge, owner);
+        return Err(rejection)
+      }
+    }
+
+    self.players[owner].roads += 1;
+
+    let mut events = vec![Event::RoadRemoved { seat: owner, edge }];
+    events.extend(self.road_built(owner));
+
+    if let Some(replacement) = replacement {
+      events.push(self.place_road(replacement));
+      events.extend(self.road_built(seat));
+    }
+
+    Ok(events)
+  }
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::game::{ cards::DevelopmentCard, event::Event };
+use super::{ BuildingKind, Game, Phase };
+
+// Minimal played knights to hold largest army
+const MIN_LARGEST_ARMY: u32 = 3;
+
+const SETTLEMENT_POINTS: u32 = 1;
+const CITY_POINTS: u32 = 2;
+const LONGEST_ROAD_POINTS: u32 = 2;
+const LARGEST_ARMY_POINTS: u32 = 2;
+const VICTORY_CARD_POINTS: u32 = 1;
+const ISLAND_BONUS_POINTS: u32 = 2;
+const METROPOLIS_POINTS: u32 = 2;
+const DEFENDER_POINTS: u32 = 1;
+const MERCHANT_POINTS: u32 = 1;
+const PROGRESS_POINTS: u32 = 1;
+
+// Victory points breakdown of player
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+  pub settlements: u32,
+  pub cities: u32,
+  pub longest_road: bool,
+  pub largest_army: bool,
+  // Hidden victory point cards, including bought in current turn
+  pub victory_cards: u32,
+  // Settled islands, which are new for player
+  pub island_bonuses: u32,
+  // Metropolises are counted in addition to their cities
+  pub metropolises: u32,
+  pub defender_points: u32,
+  pub merchant: bool,
+  // Revealed victory point progress cards
+  pub progress_points: u32
+}
+
+impl Score {
+  // Points known to every player, hidden victory point cards are excluded
+  pub const fn visible(&self) -> u32 {
+    self.total() - self.victory_cards * VICTORY_CARD_POINTS
+  }
+
+  pub const fn total(&self) -> u32 {
+    let mut total = self.settlements * SETTLEMENT_POINTS
+      + self.cities * CITY_POINTS
+      + self.victory_cards * VICTORY_CARD_POINTS
+      + self.island_bonuses * ISLAND_BONUS_POINTS
+      + self.metropolises * METROPOLIS_POINTS
+      + self.defender_points * DEFENDER_POINTS
+      + self.progress_points * PROGRESS_POINTS;
+
+    if self.longest_road {
+      total += LONGEST_ROAD_POINTS;
+    }
+
+    if self.largest_army {
+      total += LARGEST_ARMY_POINTS;
+    }
+
+    if self.merchant {
+      total += MERCHANT_POINTS;
+    }
+
+    total
+  }
+}
+
+impl Game {
+  pub(super) fn score(&self, seat: usize) -> Score {
+    let mut score = Score {
+      longest_road: self.longest_road == Some(seat),
+      largest_army: self.largest_army == Some(seat),
+      merchant: self.merchant.is_some_and(|(_, owner)| owner == seat),
+      ..Score::default()
+    };
+
+    for building in self.buildings.values().filter(|building| building.owner == seat) {
+      match building.kind {
+        BuildingKind::Settlement => score.settlements += 1,
+        BuildingKind::City => score.cities += 1
+      }
+    }
+
+    score.metropolises = self.metropolises.iter()
+      .flatten()
+      .filter(|vertex| self.buildings.get(vertex).is_some_and(|building| building.owner == seat))
+      .map(|_| 1)
+      .sum();
+
+    let player = &self.players[seat];
+    score.island_bonuses = player.island_bonuses;
+    score.defender_points = player.defender_points;
+    score.progress_points = player.progress_points;
+    for card in player.cards.iter().chain(player.new_cards.iter()) {
+      if *card == DevelopmentCard::VictoryPoint {
+        score.victory_cards += 1;
+      }
+    }
+
+    score
+  }
+
+  // Player has cards and is not protected by friendly robber
+  pub(super) fn robbable(&self, seat: usize) -> bool {
+    !self.players[seat].resources.is_empty()
+    && self.score(seat).visible() >= self.rules.friendly_robber
+  }
+
+  // Player takes largest army only with strictly more knights than holder
+  pub(super) fn update_largest_army(&mut self, seat: usize) -> Option<Event> {
+    let knights = self.players[seat].knights;
+    if knights < MIN_LARGEST_ARMY || self.largest_army == Some(seat) {
+      return None
+    }
+
+    if self.largest_army.is_some_and(|holder| self.players[holder].knights >= knights) {
+      return None
+    }
+
+    self.largest_army = Some(seat);
+
+    Some(Event::LargestArmyChanged { seat, knights })
+  }
+
+  // Player wins only in own turn, so victory is checked after every active player action
+  pub(super) fn check_victory(&mut self, events: &mut Vec<Event>) {
+    // Player can not win in special building phase, because it is not own turn
+    if matches!(
+      self.phase,
+      Phase::Setup { .. } | Phase::SpecialBuilding { .. } | Phase::Gold | Phase::Finished { .. }
+    ) {
+      return
+    }
+
+    let winner = self.active;
+    // Victory points target is house rule, scenario target by default
+    if self.score(winner).total() < self.rules.victory_points {
+      return
+    }
+
+    self.phase = Phase::Finished { winner };
+    events.push(self.phase_event());
+    events.push(Event::GameFinished {
+      winner,
+      scores: (0..self.players.len()).map(|seat| self.score(seat)).collect()
+    });
+  }
+}
\ No newline at end of file
@@ -0,0 +1,195 @@
+use crate::game::{
+  action::Rejection,
+  board::{ Edge, Hex, Vertex },
+  event::Event,
+  resources::{ Resource, Resources, SHIP_COST }
+};
+use super::{ Game, Phase };
+
+impl Game {
+  // Ships connect to own buildings or to own ships through vertex without opponent building,
+  // moved ship is ignored, because it leaves its edge
+  fn check_ship_location(
+    &self, seat: usize, edge: Edge, moved: Option<Edge>
+  ) -> Result<(), Rejection> {
+    if !self.scenario.seafarers() || !self.board.has_sea_edge(edge) {
+      return Err(Rejection::InvalidLocation)
+    }
+
+    if self.roads.contains_key(&edge) || self.ships.contains_key(&edge) {
+      return Err(Rejection::Occupied)
+    }
+
+    if self.board.pirate().is_some_and(|pirate| edge.hexes().contains(&pirate)) {
+      return Err(Rejection::PirateBlocks)
+    }
+
+    let connected = edge.vertices().iter().any(|vertex| {
+      self.buildings.get(vertex).map_or_else(
+        || vertex.edges().iter().any(|other| {
+          Some(*other) != moved && self.ships.get(other) == Some(&seat)
+        }),
+        |building| building.owner == seat
+      )
+    });
+
+    if !connected {
+      return Err(Rejection::NotConnected)
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn place_ship(&mut self, edge: Edge) -> Event {
+    let seat = self.active;
+    self.players[seat].ships -= 1;
+    self.ships.insert(edge, seat);
+    Event::ShipBuilt { seat, edge }
+  }
+
+  pub(super) fn build_ship(&mut self, edge: Edge) -> Result<Vec<Event>, Rejection> {
+    self.check_ship_location(self.active, edge, None)?;
+
+    if self.players[self.active].ships == 0 {
+      return Err(Rejection::NoPiecesLeft)
+    }
+
+    self.pay(&SHIP_COST)?;
+
+    let mut events = vec![self.place_ship(edge)];
+    // Ship built in turn can not be moved in same turn
+    self.new_ships.push(edge);
+    events.extend(self.road_built(self.active));
+
+    Ok(events)
+  }
+
+  // Open ship is last ship of shipping line: one of its ends has neither own building,
+  // nor other own ship
+  fn is_open_ship(&self, seat: usize, edge: Edge) -> bool {
+    edge.vertices().iter().any(|vertex| {
+      self.buildings.get(vertex).is_none_or(|building| building.owner != seat)
+      && vertex.edges().iter().all(|other| *other == edge || self.ships.get(other) != Some(&seat))
+    })
+  }
+
+  // Only one ship may be moved per turn
+  pub(super) fn move_ship(&mut self, from: Edge, to: Edge) -> Result<Vec<Event>, Rejection> {
+    let seat = self.active;
+
+    if self.ship_moved {
+      return Err(Rejection::ShipAlreadyMoved)
+    }
+
+    if self.ships.get(&from) != Some(&seat) {
+      return Err(Rejection::NotOwned)
+    }
+
+    let near_pirate = self.board.pirate().is_some_and(|pirate| from.hexes().contains(&pirate));
+    if self.new_ships.contains(&from) || near_pirate || !self.is_open_ship(seat, from) {
+      return Err(Rejection::ShipNotMovable)
+    }
+
+    self.check_ship_location(seat, to, Some(from))?;
+
+    self.ships.remove(&from);
+    self.ships.insert(to, seat);
+    self.ship_moved = true;
+
+    let mut events = vec![Event::ShipMoved { seat, from, to }];
+    // Moved ship may shorten or lengthen trade route
+    events.extend(self.road_built(seat));
+
+    Ok(events)
+  }
+
+  // Robbable opponents with ship next to sea hex
+  pub(super) fn pirate_victims(&self, hex: Hex) -> Vec<usize> {
+    let mut victims = hex.edges().iter()
+      .filter_map(|edge| self.ships.get(edge).copied())
+      .filter(|owner| *owner != self.active && self.robbable(*owner))
+      .collect::<Vec<usize>>();
+
+    victims.sort_unstable();
+    victims.dedup();
+
+    victims
+  }
+
+  // Gold owed by production is limited by resource cards left in bank
+  pub(super) fn require_gold(&mut self, owed: Vec<u32>) -> Vec<Event> {
+    let mut available = Resource::ALL.iter().map(|resource| self.bank.get(*resource)).sum::<u32>();
+    let mut events = Vec::new();
+
+    for (seat, count) in owed.into_iter().enumerate() {
+      let count = count.min(available);
+      if count > 0 {
+        available -= count;
+        self.gold[seat] = count;
+        events.push(Event::GoldRequired { seat, count });
+      }
+    }
+
+    events
+  }
+
+  // Players choose gold resources concurrently, then game continues with next phase
+  pub(super) fn gold_or(&mut self, next: Phase) -> Phase {
+    if self.gold.iter().all(|count| *count == 0) {
+      return next
+    }
+
+    self.resume = next;
+    Phase::Gold
+  }
+
+  pub(super) fn choose_gold(
+    &mut self, seat: usize, resources: Resources
+  ) -> Result<Vec<Event>, Rejection> {
+    let count = self.gold.get(seat).copied().unwrap_or(0);
+    if count == 0 {
+      return Err(Rejection::NothingToChoose)
+    }
+
+    if resources.total() != count {
+      return Err(Rejection::WrongGoldCount)
+    }
+
+    if resources.has_commodities() {
+      return Err(Rejection::CommodityNotAllowed)
+    }
+
+    if !self.bank.contains(&resources) {
+      return Err(Rejection::NotEnoughInBank)
+    }
+
+    self.gold[seat] = 0;
+
+    let mut events = vec![self.give(seat, resources)];
+
+    if self.gold.iter().all(|count| *count == 0) {
+      self.phase = self.resume;
+      events.push(self.phase_event());
+    }
+
+    Ok(events)
+  }
+
+  // First settlement on island, which is new for player, grants island bonus
+  pub(super) fn island_reached(&mut self, vertex: Vertex) -> Option<Event> {
+    if !self.scenario.seafarers() {
+      return None
+    }
+
+    let island = self.board.vertex_island(vertex)?;
+    let seat = self.active;
+    let player = &mut self.players[seat];
+    if !player.islands.insert(island) {
+      return None
+    }
+
+    player.island_bonuses += 1;
+
+    Some(Event::IslandDiscovered { seat })
+  }
+}
\ No newline at end of file
@@ -0,0 +1,821 @@
+use fastrand::Rng;
+use crate::game::{
+  action::{ Action, Rejection, Target },
+  board::{ Board, Edge, Hex, Vertex },
+  cards::{ DevelopmentCard, ProgressCard, Track, progress_deck },
+  dice::{ Dice, ScriptedDice },
+  event::Event,
+  resources::{ Resource, Resources, CITY_COST, DEVELOPMENT_CARD_COST },
+  rules::Rules,
+  scenario::Scenario
+};
+use super::{
+  Building, BuildingKind, Difficulty, Game, Phase, Setup, MAX_PLAYERS, knights::Knight
+};
+
+fn setup(players: usize) -> Setup {
+  Setup {
+    scenario: Scenario::Base,
+    rules: Rules::new(Scenario::Base),
+    board: Board::standard(),
+    players,
+    seed: 0,
+    proof: None
+  }
+}
+
+fn game(players: usize) -> Game {
+  Game::new(setup(players))
+}
+
+// Game after setup, where first player rolls given dice in loop
+fn scripted(players: usize, rolls: Vec<[u8; 2]>) -> Game {
+  let mut game = Game::with_dice(setup(players), Box::new(ScriptedDice::new(rolls)));
+  game.phase = Phase::Roll;
+  game
+}
+
+// Edges between every two consecutive vertices, vertices must be neighbours
+fn trail(vertices: &[Vertex]) -> Vec<Edge> {
+  vertices.windows(2)
+    .map(|pair| {
+      pair[0].edges().into_iter()
+        .find(|edge| edge.other_vertex(pair[0]) == pair[1])
+        .unwrap_or_else(|| panic!("{:?} and {:?} are not neighbours", pair[0], pair[1]))
+    })
+    .collect()
+}
+
+// Perimeter of hex and its east neighbour from south-east corner of hex, so trail of given
+// length up to 9 edges has no cycles
+fn perimeter(hex: Hex, length: usize) -> Vec<Vertex> {
+  let [north, north_east, south_east, south, south_west, north_west] = hex.vertices();
+  let east = Hex::new(hex.q + 1, hex.r).vertices();
+  let vertices = [
+    south_east, south, south_west, north_west, north, north_east, east[0], east[1], east[2],
+    east[3]
+  ];
+  vertices[..=length].to_vec()
+}
+
+fn build_roads(game: &mut Game, seat: usize, edges: &[Edge]) -> Option<Event> {
+  for edge in edges {
+    game.roads.insert(*edge, seat);
+  }
+  game.road_built(seat)
+}
+
+fn build_settlement(game: &mut Game, seat: usize, vertex: Vertex) -> Option<Event> {
+  game.buildings.insert(vertex, Building { owner: seat, kind: BuildingKind::Settlement });
+  game.settlement_built(seat, vertex)
+}
+
+const fn changed(seat: Option<usize>, length: u32) -> Event {
+  Event::LongestRoadChanged { seat, length }
+}
+
+// Far enough from each other, so trails of different seats share no vertices
+const FIRST: Hex = Hex::new(-1, 0);
+const SECOND: Hex = Hex::new(-1, 2);
+const THIRD: Hex = Hex::new(1, -2);
+
+#[test]
+fn simple_chain() {
+  let mut game = game(3);
+
+  assert_eq!(build_roads(&mut game, 0, &trail(&perimeter(FIRST, 4))), None);
+  assert_eq!(game.road_lengths[0], 4);
+
+  assert_eq!(build_roads(&mut game, 0, &trail(&perimeter(FIRST, 5))), Some(changed(Some(0), 5)));
+  assert_eq!(game.longest_road, Some(0));
+}
+
+#[test]
+fn closed_cycle() {
+  let mut game = game(3);
+  let mut vertices = FIRST.vertices().to_vec();
+  vertices.push(vertices[0]);
+
+  assert_eq!(build_roads(&mut game, 0, &trail(&vertices)), Some(changed(Some(0), 6)));
+}
+
+#[test]
+fn branching_trail_takes_longer_branch() {
+  let mut game = game(3);
+  let vertices = FIRST.vertices();
+  // Branch of one road from third corner, the other branch goes around hex for three roads
+  let outer = vertices[2].neighbours().into_iter()
+    .find(|vertex| !vertices.contains(vertex))
+    .unwrap();
+
+  let mut edges = trail(&vertices);
+  edges.extend(trail(&[vertices[2], outer]));
+
+  assert_eq!(build_roads(&mut game, 0, &edges), Some(changed(Some(0), 5)));
+  assert_eq!(game.road_lengths[0], 5);
+}
+
+#[test]
+fn opponent_settlement_splits_trail() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 9);
+  build_roads(&mut game, 0, &trail(&vertices));
+
+  // Longer part of split trail is still long enough, so holder keeps card
+  assert_eq!(build_settlement(&mut game, 1, vertices[3]), None);
+  assert_eq!(game.road_lengths[0], 6);
+  assert_eq!(game.longest_road, Some(0));
+}
+
+#[test]
+fn opponent_settlement_drops_holder_below_minimum() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 7);
+  assert_eq!(build_roads(&mut game, 0, &trail(&vertices)), Some(changed(Some(0), 7)));
+
+  assert_eq!(build_settlement(&mut game, 1, vertices[3]), Some(changed(None, 0)));
+  assert_eq!(game.road_lengths[0], 4);
+  assert_eq!(game.longest_road, None);
+}
+
+#[test]
+fn own_settlement_does_not_split_trail() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 5);
+  build_roads(&mut game, 0, &trail(&vertices));
+
+  assert_eq!(build_settlement(&mut game, 0, vertices[2]), None);
+  assert_eq!(game.road_lengths[0], 5);
+}
+
+#[test]
+fn roads_and_ships_connect_only_through_own_building() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 6);
+  let edges = trail(&vertices);
+
+  for edge in &edges[..3] {
+    game.roads.insert(*edge, 0);
+  }
+  for edge in &edges[3..] {
+    game.ships.insert(*edge, 0);
+  }
+  assert_eq!(game.road_built(0), None);
+  assert_eq!(game.road_lengths[0], 3);
+
+  // Opponent building at junction does not connect them either
+  game.buildings.insert(vertices[3], Building { owner: 1, kind: BuildingKind::Settlement });
+  assert_eq!(game.road_built(0), None);
+  assert_eq!(game.road_lengths[0], 3);
+
+  game.buildings.insert(vertices[3], Building { owner: 0, kind: BuildingKind::Settlement });
+  assert_eq!(game.road_built(0), Some(changed(Some(0), 6)));
+}
+
+#[test]
+fn strictly_longer_road_takes_card() {
+  let mut game = game(3);
+  build_roads(&mut game, 0, &trail(&perimeter(FIRST, 5)));
+
+  // Equal road does not take card from holder
+  assert_eq!(build_roads(&mut game, 1, &trail(&perimeter(SECOND, 5))), None);
+  assert_eq!(game.longest_road, Some(0));
+
+  assert_eq!(build_roads(&mut game, 1, &trail(&perimeter(SECOND, 6))), Some(changed(Some(1), 6)));
+  assert_eq!(game.longest_road, Some(1));
+}
+
+#[test]
+fn tie_after_break_leaves_card_unheld() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 7);
+  build_roads(&mut game, 0, &trail(&vertices));
+  build_roads(&mut game, 1, &trail(&perimeter(SECOND, 5)));
+  build_roads(&mut game, 2, &trail(&perimeter(THIRD, 5)));
+  assert_eq!(game.longest_road, Some(0));
+
+  // Two other players tie for longest road, so neither of them takes card
+  assert_eq!(build_settlement(&mut game, 1, vertices[3]), Some(changed(None, 0)));
+  assert_eq!(game.longest_road, None);
+}
+
+#[test]
+fn tie_after_break_keeps_holder() {
+  let mut game = game(3);
+  let vertices = perimeter(FIRST, 9);
+  build_roads(&mut game, 0, &trail(&vertices));
+  build_roads(&mut game, 1, &trail(&perimeter(SECOND, 6)));
+  assert_eq!(game.longest_road, Some(0));
+
+  // Broken road of holder is still as long as longest other road
+  assert_eq!(build_settlement(&mut game, 2, vertices[3]), None);
+  assert_eq!(game.road_lengths[0], 6);
+  assert_eq!(game.longest_road, Some(0));
+}
+
+// Hills with token 6 and mountains with token 8 of standard board
+const HILLS: Hex = Hex::new(0, -1);
+const MOUNTAINS: Hex = Hex::new(2, 0);
+
+fn build(game: &mut Game, seat: usize, vertex: Vertex, kind: BuildingKind) {
+  game.buildings.insert(vertex, Building { owner: seat, kind });
+}
+
+fn leave_in_bank(game: &mut Game, resource: Resource, count: u32) {
+  let extra = game.bank.get(resource) - count;
+  game.bank.remove(resource, extra);
+}
+
+#[test]
+fn scripted_rolls_repeat_in_loop() {
+  let mut game = scripted(3, vec![[1, 2], [6, 6]]);
+
+  for (seat, dice) in [(0, [1, 2]), (1, [6, 6]), (2, [1, 2]), (0, [6, 6])] {
+    let events = game.apply(seat, Action::RollDice).unwrap();
+    assert_eq!(events[0], Event::DiceRolled { seat, dice });
+    assert_eq!(game.phase, Phase::Main);
+    game.apply(seat, Action::EndTurn).unwrap();
+  }
+}
+
+#[test]
+fn seven_requires_discards_above_limit() {
+  let mut game = scripted(3, vec![[3, 4]]);
+  game.players[0].resources = Resources::new(3, 3, 3, 0, 0);
+  game.players[1].resources = Resources::new(3, 4, 0, 0, 0);
+  game.players[2].resources = Resources::new(0, 0, 0, 4, 4);
+
+  let events = game.apply(0, Action::RollDice).unwrap();
+  assert!(events.contains(&Event::DiscardRequired { seat: 0, count: 4 }));
+  assert!(events.contains(&Event::DiscardRequired { seat: 2, count: 4 }));
+  assert!(!events.iter().any(|event| matches!(event, Event::DiscardRequired { seat: 1, .. })));
+  assert_eq!(game.phase, Phase::Discard { robber: true });
+
+  let too_few = Resources::new(1, 1, 1, 0, 0);
+  assert_eq!(game.apply(0, Action::Discard(too_few)), Err(Rejection::WrongDiscardCount));
+  let hand = Resources::new(3, 4, 0, 0, 0);
+  assert_eq!(game.apply(1, Action::Discard(hand)), Err(Rejection::NothingToDiscard));
+
+  game.apply(0, Action::Discard(Resources::new(2, 1, 1, 0, 0))).unwrap();
+  assert_eq!(game.phase, Phase::Discard { robber: true });
+  game.apply(2, Action::Discard(Resources::new(0, 0, 0, 2, 2))).unwrap();
+  assert_eq!(game.phase, Phase::Robber);
+  assert_eq!(game.players[0].resources, Resources::new(1, 2, 2, 0, 0));
+}
+
+#[test]
+fn seven_without_large_hands_moves_robber() {
+  let mut game = scripted(3, vec![[6, 1]]);
+  game.players[0].resources = Resources::new(1, 0, 0, 0, 0);
+
+  let events = game.apply(0, Action::RollDice).unwrap();
+  assert!(!events.iter().any(|event| matches!(event, Event::DiscardRequired { .. })));
+  assert_eq!(game.phase, Phase::Robber);
+}
+
+#[test]
+fn production_pays_settlements_and_cities() {
+  let mut game = scripted(3, vec![[2, 4], [5, 3]]);
+  let [north, _, _, south, _, _] = HILLS.vertices();
+  build(&mut game, 0, north, BuildingKind::Settlement);
+  build(&mut game, 1, south, BuildingKind::City);
+  build(&mut game, 2, MOUNTAINS.vertices()[0], BuildingKind::Settlement);
+
+  let events = game.apply(0, Action::RollDice).unwrap();
+  let brick = |count| Resources::new(count, 0, 0, 0, 0);
+  assert!(events.contains(&Event::ResourcesProduced { seat: 0, resources: brick(1) }));
+  assert!(events.contains(&Event::ResourcesProduced { seat: 1, resources: brick(2) }));
+  assert_eq!(game.players[2].resources, Resources::default());
+
+  game.apply(0, Action::EndTurn).unwrap();
+  game.apply(1, Action::RollDice).unwrap();
+  assert_eq!(game.players[2].resources, Resources::new(0, 0, 1, 0, 0));
+}
+
+#[test]
+fn bank_shortage_blocks_production_for_everyone() {
+  let mut game = scripted(3, vec![[3, 3]]);
+  let [north, _, _, south, _, _] = HILLS.vertices();
+  build(&mut game, 0, north, BuildingKind::Settlement);
+  build(&mut game, 1, south, BuildingKind::Settlement);
+  leave_in_bank(&mut game, Resource::Brick, 1);
+
+  let events = game.apply(0, Action::RollDice).unwrap();
+  assert!(events.contains(&Event::ProductionBlocked { resource: Resource::Brick }));
+  assert!(!events.iter().any(|event| matches!(event, Event::ResourcesProduced { .. })));
+  assert_eq!(game.bank.get(Resource::Brick), 1);
+}
+
+#[test]
+fn bank_shortage_pays_single_player_what_is_left() {
+  let mut game = scripted(3, vec![[3, 3]]);
+  build(&mut game, 0, HILLS.vertices()[0], BuildingKind::City);
+  leave_in_bank(&mut game, Resource::Brick, 1);
+
+  let events = game.apply(0, Action::RollDice).unwrap();
+  let resources = Resources::new(1, 0, 0, 0, 0);
+  assert!(events.contains(&Event::ResourcesProduced { seat: 0, resources }));
+  assert!(!events.iter().any(|event| matches!(event, Event::ProductionBlocked { .. })));
+  assert_eq!(game.bank.get(Resource::Brick), 0);
+}
+
+// Cities & Knights game in main phase of first turn, first player holds progress card
+fn progress(players: usize, card: ProgressCard) -> Game {
+  let scenario = Scenario::CitiesAndKnights;
+  let mut game = Game::new(Setup { scenario, rules: Rules::new(scenario), ..setup(players) });
+  game.phase = Phase::Main;
+  game.turn = 1;
+  game.players[0].progress_cards.push(card);
+  game
+}
+
+fn play(game: &mut Game, card: ProgressCard, target: Target) -> Result<Vec<Event>, Rejection> {
+  game.apply(0, Action::PlayProgressCard { card, target })
+}
+
+const fn knight(owner: usize, level: u8, active: bool) -> Knight {
+  Knight { owner, level, active, activated: 0, promoted: 0 }
+}
+
+#[test]
+fn progress_decks_have_published_sizes() {
+  for track in Track::ALL {
+    assert_eq!(progress_deck(track).len(), 18);
+  }
+}
+
+#[test]
+fn wedding_givers_choose_cards() {
+  let mut game = progress(3, ProgressCard::Wedding);
+  build(&mut game, 1, HILLS.vertices()[0], BuildingKind::Settlement);
+  game.players[0].resources = Resources::default();
+  game.players[1].resources = Resources::new(2, 1, 0, 0, 0);
+  game.players[2].resources = Resources::new(1, 0, 0, 0, 0);
+
+  let events = play(&mut game, ProgressCard::Wedding, Target::None).unwrap();
+  assert!(events.contains(&Event::GiftRequired { seat: 1, count: 2 }));
+  assert!(!events.iter().any(|event| matches!(event, Event::GiftRequired { seat: 2, .. })));
+  assert_eq!(game.phase, Phase::Gift);
+  assert!(game.awaits(1) && !game.awaits(0) && !game.awaits(2));
+
+  let brick = Resources::new(1, 0, 0, 0, 0);
+  assert_eq!(game.apply(2, Action::GiveCards(brick)), Err(Rejection::NothingToGive));
+  assert_eq!(game.apply(1, Action::GiveCards(brick)), Err(Rejection::WrongGiftCount));
+  assert_eq!(game.apply(0, Action::EndTurn), Err(Rejection::WrongPhase));
+
+  let gift = Resources::new(1, 1, 0, 0, 0);
+  let events = game.apply(1, Action::GiveCards(gift)).unwrap();
+  let given = Event::CardsGiven { seat: 1, receiver: 0, count: 2, resources: Some(gift) };
+  assert_eq!(events[0], given);
+  let hidden = Event::CardsGiven { seat: 1, receiver: 0, count: 2, resources: None };
+  assert_eq!(events[0].project(2), hidden);
+  assert_eq!(game.players[0].resources, gift);
+  assert_eq!(game.players[1].resources, brick);
+  assert_eq!(game.phase, Phase::Main);
+}
+
+#[test]
+fn commercial_harbor_exchanges_resources_for_chosen_commodities() {
+  let mut game = progress(3, ProgressCard::CommercialHarbor);
+  game.players[0].resources = Resources::new(1, 1, 0, 0, 0);
+  game.players[1].resources = Resources::new(0, 0, 0, 1, 0);
+  game.players[1].resources += Resources::commodities(1, 1, 0);
+  game.players[2].resources = Resources::new(0, 0, 0, 0, 1);
+
+  let mut offers = [None; MAX_PLAYERS];
+  offers[1] = Some(Resource::Paper);
+  let target = Target::Offers(offers);
+  let rejected = play(&mut game, ProgressCard::CommercialHarbor, target);
+  assert_eq!(rejected, Err(Rejection::CommodityNotAllowed));
+
+  offers[1] = Some(Resource::Brick);
+  offers[2] = Some(Resource::Lumber);
+  let events = play(&mut game, ProgressCard::CommercialHarbor, Target::Offers(offers)).unwrap();
+  assert!(events.contains(&Event::GiftRequired { seat: 1, count: 1 }));
+  assert!(!events.iter().any(|event| matches!(event, Event::GiftRequired { seat: 2, .. })));
+
+  let grain = Resources::new(0, 0, 0, 1, 0);
+  assert_eq!(game.apply(1, Action::GiveCards(grain)), Err(Rejection::CommodityRequired));
+
+  game.apply(1, Action::GiveCards(Resources::commodities(0, 1, 0))).unwrap();
+  let mut active = Resources::new(0, 1, 0, 0, 0);
+  active += Resources::commodities(0, 1, 0);
+  let mut giver = Resources::new(1, 0, 0, 1, 0);
+  giver += Resources::commodities(1, 0, 0);
+  assert_eq!(game.players[0].resources, active);
+  assert_eq!(game.players[1].resources, giver);
+  assert_eq!(game.phase, Phase::Main);
+}
+
+#[test]
+fn master_merchant_takes_chosen_cards_from_shown_hand() {
+  let mut game = progress(3, ProgressCard::MasterMerchant);
+  build(&mut game, 1, HILLS.vertices()[0], BuildingKind::Settlement);
+  let hand = Resources::new(3, 0, 0, 0, 1);
+  game.players[1].resources = hand;
+
+  let rejected = play(&mut game, ProgressCard::MasterMerchant, Target::Seat(2));
+  assert_eq!(rejected, Err(Rejection::InvalidVictim));
+
+  let events = play(&mut game, ProgressCard::MasterMerchant, Target::Seat(1)).unwrap();
+  let shown = Event::HandShown { seat: 1, viewer: 0, resources: Some(hand) };
+  assert!(events.contains(&shown));
+  assert_eq!(shown.project(2), Event::HandShown { seat: 1, viewer: 0, resources: None });
+  assert_eq!(game.phase, Phase::Taking { victim: 1 });
+
+  let ore = Resources::new(0, 0, 1, 0, 0);
+  assert_eq!(game.apply(0, Action::TakeCards(ore)), Err(Rejection::WrongGiftCount));
+  let ore = Resources::new(0, 0, 2, 0, 0);
+  assert_eq!(game.apply(0, Action::TakeCards(ore)), Err(Rejection::NotEnoughResources));
+
+  let taken = Resources::new(1, 0, 0, 0, 1);
+  game.apply(0, Action::TakeCards(taken)).unwrap();
+  assert_eq!(game.players[0].resources, taken);
+  assert_eq!(game.players[1].resources, Resources::new(2, 0, 0, 0, 0));
+  assert_eq!(game.phase, Phase::Main);
+}
+
+#[test]
+fn saboteur_halves_hands_without_robber() {
+  let mut game = progress(3, ProgressCard::Saboteur);
+  build(&mut game, 1, HILLS.vertices()[0], BuildingKind::Settlement);
+  build(&mut game, 2, MOUNTAINS.vertices()[0], BuildingKind::Settlement);
+  build(&mut game, 0, MOUNTAINS.vertices()[3], BuildingKind::City);
+  game.players[1].resources = Resources::new(5, 0, 0, 0, 0);
+  game.players[2].resources = Resources::new(0, 5, 0, 0, 0);
+
+  let events = play(&mut game, ProgressCard::Saboteur, Target::None).unwrap();
+  assert!(!events.iter().any(|event| matches!(event, Event::DiscardRequired { seat: 1, .. })));
+  assert!(!events.iter().any(|event| matches!(event, Event::DiscardRequired { seat: 2, .. })));
+  assert_eq!(game.phase, Phase::Main);
+
+  game.players[0].progress_cards.push(ProgressCard::Saboteur);
+  build(&mut game, 1, HILLS.vertices()[3], BuildingKind::Settlement);
+  let events = play(&mut game, ProgressCard::Saboteur, Target::None).unwrap();
+  assert!(events.contains(&Event::DiscardRequired { seat: 1, count: 2 }));
+  assert_eq!(game.phase, Phase::Discard { robber: false });
+
+  game.apply(1, Action::Discard(Resources::new(2, 0, 0, 0, 0))).unwrap();
+  assert_eq!(game.phase, Phase::Main);
+}
+
+#[test]
+fn deserter_replaces_removed_knight_with_own_one() {
+  let mut game = progress(3, ProgressCard::Deserter);
+  let [north, north_east, ..] = FIRST.vertices();
+  game.roads.insert(trail(&[north, north_east])[0], 0);
+  let [strong, _, _, basic, _, _] = SECOND.vertices();
+  game.knights.insert(strong, knight(1, 2, true));
+  game.knights.insert(basic, knight(1, 1, false));
+
+  let target = Target::SeatVertex(2, Some(north));
+  assert_eq!(play(&mut game, ProgressCard::Deserter, target), Err(Rejection::InvalidVictim));
+  let target = Target::SeatVertex(1, Some(THIRD.vertices()[0]));
+  assert_eq!(play(&mut game, ProgressCard::Deserter, target), Err(Rejection::NotConnected));
+
+  play(&mut game, ProgressCard::Deserter, Target::SeatVertex(1, Some(north))).unwrap();
+  assert_eq!(game.phase, Phase::Desertion { victim: 1, vertex: Some(north) });
+  assert!(game.awaits(1) && !game.awaits(0));
+  assert_eq!(game.apply(0, Action::RemoveKnight(strong)), Err(Rejection::WrongPhase));
+  assert_eq!(game.apply(1, Action::RemoveKnight(north)), Err(Rejection::NotOwned));
+
+  let events = game.apply(1, Action::RemoveKnight(strong)).unwrap();
+  assert!(events.contains(&Event::KnightRemoved { seat: 1, vertex: strong }));
+  assert!(events.contains(&Event::KnightPlaced { seat: 0, vertex: north, level: 2, active: true }));
+  assert_eq!(game.knights.get(&north), Some(&knight(0, 2, true)));
+  assert!(!game.knights.contains_key(&strong));
+  assert_eq!(game.players[0].knight_pieces, [2, 1, 2]);
+  assert_eq!(game.players[1].knight_pieces, [2, 3, 2]);
+  assert_eq!(game.phase, Phase::Main);
+}
+
+#[test]
+fn diplomat_removes_only_open_roads() {
+  let mut game = progress(3, ProgressCard::Diplomat);
+  let vertices = perimeter(SECOND, 2);
+  let edges = trail(&vertices);
+  build_roads(&mut game, 1, &edges);
+  build(&mut game, 1, vertices[0], BuildingKind::Settlement);
+
+  let target = Target::Edges(edges[0], None);
+  assert_eq!(play(&mut game, ProgressCard::Diplomat, target), Err(Rejection::RoadNotOpen));
+  let target = Target::Edges(edges[1], Some(edges[0]));
+  assert_eq!(play(&mut game, ProgressCard::Diplomat, target), Err(Rejection::NotOwned));
+
+  let events = play(&mut game, ProgressCard::Diplomat, Target::Edges(edges[1], None)).unwrap();
+  assert!(events.contains(&Event::RoadRemoved { seat: 1, edge: edges[1] }));
+  assert!(!game.roads.contains_key(&edges[1]));
+  assert_eq!(game.road_lengths[1], 1);
+}
+
+#[test]
+fn diplomat_moves_own_road() {
+  let mut game = progress(3, ProgressCard::Diplomat);
+  let [north, north_east, _, _, _, north_west] = FIRST.vertices();
+  build(&mut game, 0, north, BuildingKind::Settlement);
+  let removed = trail(&[north, north_east])[0];
+  let placed = trail(&[north, north_west])[0];
+  build_roads(&mut game, 0, &[removed]);
+
+  let target = Target::Edges(removed, Some(removed));
+  assert_eq!(play(&mut game, ProgressCard::Diplomat, target), Err(Rejection::InvalidLocation));
+  assert_eq!(game.roads.get(&removed), Some(&0));
+
+  let events = play(&mut game, ProgressCard::Diplomat, Target::Edges(removed, Some(placed)));
+  let events = events.unwrap();
+  assert!(events.contains(&Event::RoadRemoved { seat: 0, edge: removed }));
+  assert!(events.contains(&Event::RoadBuilt { seat: 0, edge: placed }));
+  assert!(!game.roads.contains_key(&removed));
+  assert_eq!(game.roads.get(&placed), Some(&0));
+}
+
+#[test]
+fn smith_promotes_knights_for_free() {
+  let mut game = progress(3, ProgressCard::Smith);
+  let [first, _, _, second, _, _] = FIRST.vertices();
+  game.knights.insert(first, knight(0, 1, false));
+  game.knights.insert(second, knight(0, 1, true));
+  game.players[0].knight_pieces = [0, 2, 2];
+
+  let target = Target::Vertices(first, Some(first));
+  assert_eq!(play(&mut game, ProgressCard::Smith, target), Err(Rejection::KnightBusy));
+  game.players[0].knight_pieces = [0, 1, 2];
+  let target = Target::Vertices(first, Some(second));
+  assert_eq!(play(&mut game, ProgressCard::Smith, target), Err(Rejection::NoPiecesLeft));
+  assert_eq!(game.knights.get(&first), Some(&knight(0, 1, false)));
+
+  game.players[0].knight_pieces = [0, 2, 2];
+  play(&mut game, ProgressCard::Smith, Target::Vertices(first, Some(second))).unwrap();
+  assert_eq!(game.knights.get(&first).map(|knight| knight.level), Some(2));
+  assert_eq!(game.knights.get(&second).map(|knight| knight.level), Some(2));
+  assert_eq!(game.players[0].knight_pieces, [2, 0, 2]);
+  assert_eq!(game.players[0].resources, Resources::default());
+}
+
+// Passes turns of every other player, so first player rolls again
+fn next_round(game: &mut Game) {
+  game.apply(0, Action::EndTurn).unwrap();
+  for seat in 1..game.players.len() {
+    game.apply(seat, Action::RollDice).unwrap();
+    game.apply(seat, Action::EndTurn).unwrap();
+  }
+}
+
+#[test]
+fn bought_card_is_playable_from_next_turn() {
+  let mut game = scripted(3, vec![[1, 2]]);
+  game.apply(0, Action::RollDice).unwrap();
+  assert_eq!(game.apply(0, Action::BuyDevelopmentCard), Err(Rejection::NotEnoughResources));
+
+  game.players[0].resources = DEVELOPMENT_CARD_COST;
+  game.deck.push(DevelopmentCard::Knight);
+  let (deck, mut bank) = (game.deck.len(), game.bank);
+  let events = game.apply(0, Action::BuyDevelopmentCard).unwrap();
+  let card = Some(DevelopmentCard::Knight);
+  assert_eq!(events, vec![Event::DevelopmentCardBought { seat: 0, card }]);
+  assert_eq!(game.deck.len(), deck - 1);
+  bank += DEVELOPMENT_CARD_COST;
+  assert_eq!(game.bank, bank);
+  assert_eq!(game.players[0].resources, Resources::default());
+  assert_eq!(game.apply(0, Action::PlayKnight), Err(Rejection::NoCard));
+
+  // Card may be played before dice roll of next turn
+  next_round(&mut game);
+  assert_eq!(game.phase, Phase::Roll);
+  game.apply(0, Action::PlayKnight).unwrap();
+  assert_eq!(game.phase, Phase::Robber);
+  assert_eq!(game.players[0].knights, 1);
+}
+
+#[test]
+fn empty_deck_rejects_purchase() {
+  let mut game = scripted(3, vec![[1, 2]]);
+  game.apply(0, Action::RollDice).unwrap();
+  game.players[0].resources = DEVELOPMENT_CARD_COST;
+  game.deck.clear();
+
+  assert_eq!(game.apply(0, Action::BuyDevelopmentCard), Err(Rejection::DeckEmpty));
+  assert_eq!(game.players[0].resources, DEVELOPMENT_CARD_COST);
+}
+
+#[test]
+fn one_development_card_is_played_per_turn() {
+  let mut game = scripted(3, vec![[1, 2]]);
+  game.players[0].cards = vec![DevelopmentCard::YearOfPlenty, DevelopmentCard::Monopoly];
+  game.apply(0, Action::RollDice).unwrap();
+
+  let resources = [Resource::Brick, Resource::Ore];
+  game.apply(0, Action::PlayYearOfPlenty(resources)).unwrap();
+  let monopoly = Action::PlayMonopoly(Resource::Brick);
+  assert_eq!(game.apply(0, monopoly), Err(Rejection::CardAlreadyPlayed));
+
+  next_round(&mut game);
+  game.apply(0, Action::RollDice).unwrap();
+  game.apply(0, monopoly).unwrap();
+  assert!(game.players[0].cards.is_empty());
+}
+
+#[test]
+fn development_cards_are_not_played_outside_own_turn() {
+  let mut game = game(5);
+  game.players[0].cards = vec![DevelopmentCard::Knight];
+  assert!(matches!(game.phase, Phase::Setup { .. }));
+  assert_eq!(game.apply(0, Action::PlayKnight), Err(Rejection::WrongPhase));
+
+  // Special building phase allows buying, but not playing cards
+  game.phase = Phase::SpecialBuilding { ended: 4 };
+  game.players[0].resources = DEVELOPMENT_CARD_COST;
+  assert_eq!(game.apply(0, Action::PlayKnight), Err(Rejection::WrongPhase));
+  game.apply(0, Action::BuyDevelopmentCard).unwrap();
+  assert_eq!(game.players[0].cards, vec![DevelopmentCard::Knight]);
+  assert_eq!(game.players[0].new_cards.len(), 1);
+}
+
+#[test]
+fn largest_army_needs_strictly_more_knights_than_holder() {
+  let mut game = game(3);
+  game.players[0].knights = 2;
+  assert_eq!(game.update_largest_army(0), None);
+
+  game.players[0].knights = 3;
+  let changed = Event::LargestArmyChanged { seat: 0, knights: 3 };
+  assert_eq!(game.update_largest_army(0), Some(changed));
+
+  game.players[1].knights = 3;
+  assert_eq!(game.update_largest_army(1), None);
+  assert_eq!(game.largest_army, Some(0));
+
+  game.players[1].knights = 4;
+  let changed = Event::LargestArmyChanged { seat: 1, knights: 4 };
+  assert_eq!(game.update_largest_army(1), Some(changed));
+  assert!(game.score(1).largest_army && !game.score(0).largest_army);
+}
+
+#[test]
+fn victory_is_checked_only_in_own_turn() {
+  let mut game = game(5);
+  game.rules.victory_points = 3;
+  let (first, second) = (HILLS.vertices()[0], MOUNTAINS.vertices()[0]);
+  build(&mut game, 0, first, BuildingKind::Settlement);
+  build(&mut game, 0, second, BuildingKind::Settlement);
+
+  game.apply(0, Action::BuildSettlement(SECOND.vertices()[0])).unwrap();
+  assert_eq!(game.score(0).total(), 3);
+  assert!(matches!(game.phase, Phase::Setup { .. }));
+
+  game.phase = Phase::SpecialBuilding { ended: 4 };
+  game.players[0].resources = CITY_COST;
+  game.apply(0, Action::BuildCity(first)).unwrap();
+  assert_eq!(game.phase, Phase::SpecialBuilding { ended: 4 });
+
+  game.phase = Phase::Main;
+  game.players[0].resources = CITY_COST;
+  let events = game.apply(0, Action::BuildCity(second)).unwrap();
+  assert_eq!(game.phase, Phase::Finished { winner: 0 });
+  assert!(events.iter().any(|event| matches!(event, Event::GameFinished { winner: 0, .. })));
+}
+
+// Main phase of first player, who holds two brick, second player holds ore and grain
+fn trade() -> Game {
+  let mut game = scripted(3, vec![[1, 2]]);
+  game.players[0].resources = Resources::new(2, 0, 0, 0, 0);
+  game.players[1].resources = Resources::new(0, 0, 0, 1, 1);
+  game.apply(0, Action::RollDice).unwrap();
+  game
+}
+
+const fn offer(give: Resources, receive: Resources) -> Action {
+  Action::OfferTrade { give, receive }
+}
+
+const BRICK: Resources = Resources::new(1, 0, 0, 0, 0);
+const ORE: Resources = Resources::new(0, 0, 0, 0, 1);
+const CONFIRM: Action = Action::ConfirmTrade { partner: 1 };
+
+#[test]
+fn accepted_offer_exchanges_cards() {
+  let mut game = trade();
+  assert_eq!(game.apply(1, Action::AcceptTrade), Err(Rejection::NoOffer));
+
+  let offered = Event::TradeOffered { seat: 0, give: BRICK, receive: ORE };
+  assert_eq!(game.apply(0, offer(BRICK, ORE)), Ok(vec![offered]));
+  assert_eq!(game.apply(1, Action::AcceptTrade), Ok(vec![Event::TradeAccepted { seat: 1 }]));
+  assert_eq!(game.apply(2, Action::RejectTrade), Ok(vec![Event::TradeRejected { seat: 2 }]));
+  assert_eq!(game.apply(0, Action::AcceptTrade), Err(Rejection::NotYourTurn));
+
+  assert_eq!(game.apply(0, Action::ConfirmTrade { partner: 2 }), Err(Rejection::NotAccepted));
+  assert_eq!(game.apply(0, Action::ConfirmTrade { partner: 0 }), Err(Rejection::InvalidPartner));
+  let completed = Event::TradeCompleted { seat: 0, partner: 1, give: BRICK, receive: ORE };
+  assert_eq!(game.apply(0, CONFIRM), Ok(vec![completed]));
+  assert_eq!(game.players[0].resources, Resources::new(1, 0, 0, 0, 1));
+  assert_eq!(game.players[1].resources, Resources::new(1, 0, 0, 1, 0));
+  assert_eq!(game.apply(0, CONFIRM), Err(Rejection::NoOffer));
+}
+
+#[test]
+fn counter_offer_is_confirmed_on_its_terms() {
+  let mut game = trade();
+  game.apply(0, offer(BRICK, ORE)).unwrap();
+
+  let receive = Resources::new(0, 0, 0, 1, 1);
+  let counter = Action::CounterTrade { give: Resources::new(2, 0, 0, 0, 0), receive };
+  game.apply(1, counter).unwrap();
+  game.apply(0, CONFIRM).unwrap();
+  assert_eq!(game.players[0].resources, receive);
+  assert_eq!(game.players[1].resources, Resources::new(2, 0, 0, 0, 0));
+}
+
+#[test]
+fn trade_is_rejected_when_side_lacks_resources() {
+  let mut game = trade();
+  let nothing = Resources::default();
+  assert_eq!(game.apply(0, offer(BRICK, nothing)), Err(Rejection::InvalidTrade));
+  assert_eq!(game.apply(0, offer(BRICK, BRICK)), Err(Rejection::InvalidTrade));
+  let three = Resources::new(3, 0, 0, 0, 0);
+  assert_eq!(game.apply(0, offer(three, ORE)), Err(Rejection::NotEnoughResources));
+
+  game.apply(0, offer(BRICK, Resources::new(0, 0, 0, 0, 2))).unwrap();
+  game.apply(1, Action::AcceptTrade).unwrap();
+  assert_eq!(game.apply(0, CONFIRM), Err(Rejection::NotEnoughResources));
+
+  // Hands are checked again at confirmation, because they could change since offer
+  game.apply(0, offer(BRICK, ORE)).unwrap();
+  game.apply(1, Action::AcceptTrade).unwrap();
+  game.players[0].resources = nothing;
+  assert_eq!(game.apply(0, CONFIRM), Err(Rejection::NotEnoughResources));
+  assert_eq!(game.players[1].resources, Resources::new(0, 0, 0, 1, 1));
+}
+
+#[test]
+fn cancelled_offer_can_not_be_answered() {
+  let mut game = scripted(3, vec![[1, 2]]);
+  game.players[0].resources = BRICK;
+  assert_eq!(game.apply(0, offer(BRICK, ORE)), Err(Rejection::WrongPhase));
+
+  game.apply(0, Action::RollDice).unwrap();
+  game.apply(0, offer(BRICK, ORE)).unwrap();
+  assert_eq!(game.apply(0, Action::CancelTrade), Ok(vec![Event::TradeCancelled { seat: 0 }]));
+  assert_eq!(game.apply(1, Action::AcceptTrade), Err(Rejection::NoOffer));
+  assert_eq!(game.apply(0, Action::CancelTrade), Err(Rejection::NoOffer));
+}
+
+// Bots make first accepted action of any awaited seat, until condition holds
+fn play_bots(game: &mut Game, rng: &Rng, until: impl Fn(&Game) -> bool) {
+  for _ in 0..10_000 {
+    if until(game) {
+      return
+    }
+
+    let applied = (0..game.players.len()).find_map(|seat| {
+      game.bot_actions(seat, Difficulty::Medium, rng).into_iter()
+        .find_map(|action| game.apply(seat, action).ok())
+    });
+    assert!(applied.is_some(), "bots are stuck in {:?}", game.phase);
+  }
+  panic!("bots never reached condition");
+}
+
+// Everything every seat observes, observations have no equality, so their debug output is used
+fn observed(game: &Game) -> Vec<String> {
+  (0..game.players.len()).map(|seat| format!("{:?}", game.observe(seat))).collect()
+}
+
+#[test]
+fn undo_rebuilds_state_before_action() {
+  let rules = Rules { dice: Dice::Balanced, ..Rules::new(Scenario::Base) };
+  let mut game = Game::new(Setup { rules, ..setup(3) });
+  let rng = Rng::with_seed(1);
+  // Few rolls are made, so rebuilt game must draw from same position of dice deck
+  play_bots(&mut game, &rng, |game| {
+    game.phase == Phase::Main && game.active == 0 && game.turn > 6
+    && game.players[0].resources.total() > 0
+  });
+
+  let (before, seed, log) = (observed(&game), game.rng.get_seed(), game.log.clone());
+  let (resource, _) = game.players[0].resources.iter().next().unwrap();
+  let other = Resource::ALL.into_iter().find(|other| *other != resource).unwrap();
+  let (mut give, mut receive) = (Resources::default(), Resources::default());
+  give.add(resource, 1);
+  receive.add(other, 1);
+  game.apply(0, Action::OfferTrade { give, receive }).unwrap();
+
+  game.request_undo(0).unwrap();
+  assert!(!game.vote_undo(1, true).unwrap().1);
+  assert!(game.vote_undo(2, true).unwrap().1);
+  assert_eq!(observed(&game), before);
+  assert_eq!(game.rng.get_seed(), seed);
+  assert_eq!(game.log, log);
+
+  // Rolls after undo are same as in game, which never had undone action
+  let (mut replayed, _) = Game::replay(game.setup.clone(), &log).unwrap();
+  for _ in 0..10 {
+    let seat = game.active;
+    let action = if game.phase == Phase::Roll { Action::RollDice } else { Action::EndTurn };
+    let events = game.apply(seat, action);
+    assert_eq!(events, replayed.apply(seat, action));
+    if events.is_err() {
+      break
+    }
+  }
+  assert_eq!(observed(&game), observed(&replayed));
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use fastrand::Rng;
+use crate::game::{ action::Action, event::Event, resources::{ Resource, Resources } };
+use super::{ Game, Phase, progress::GIFT_CARDS };
+
+impl Game {
+  // Players, who ran out of time, make safe default actions: dice are rolled, random cards
+  // are discarded, chosen, given or taken, random knight is removed, robber is placed on random
+  // legal hex, turn is ended
+  // Choices use given rng, not game one, so game randomness stays same as without timeouts,
+  // actions are applied as usual, so they are logged and replayed
+  pub fn time_out(&mut self, rng: &Rng) -> Vec<(usize, Action, Vec<Event>)> {
+    let seats = match self.phase {
+      Phase::Discard { .. } => pending(&self.discards),
+      Phase::Gold => pending(&self.gold),
+      Phase::Gift => pending(&self.gifts),
+      Phase::Desertion { victim, .. } => vec![victim],
+      Phase::Finished { .. } => Vec::new(),
+      _ => vec![self.active]
+    };
+
+    let mut applied = Vec::new();
+    // Candidates of every seat are made after previous seat action, so they see changed bank
+    for seat in seats {
+      for action in self.default_actions(seat, rng) {
+        if let Ok(events) = self.apply(seat, action) {
+          applied.push((seat, action, events));
+          break
+        }
+      }
+    }
+
+    applied
+  }
+
+  // Candidate actions in preference order, first accepted one is made
+  fn default_actions(&self, seat: usize, rng: &Rng) -> Vec<Action> {
+    match self.phase {
+      Phase::Setup { settlement: None, .. } => {
+        let mut vertices = self.board.vertices().into_iter().collect::<Vec<_>>();
+        rng.shuffle(&mut vertices);
+        // Only one of them is accepted in current round
+        vertices.into_iter()
+          .flat_map(|vertex| [Action::BuildSettlement(vertex), Action::BuildCity(vertex)])
+          .collect()
+      },
+      Phase::Setup { settlement: Some(settlement), .. } => {
+        settlement.edges().into_iter()
+          .flat_map(|edge| [Action::BuildRoad(edge), Action::BuildShip(edge)])
+          .collect()
+      },
+      Phase::Roll => vec![Action::RollDice],
+      Phase::Main | Phase::SpecialBuilding { .. } => vec![Action::EndTurn],
+      Phase::Discard { .. } => {
+        let hand = self.players[seat].resources;
+        vec![Action::Discard(random_cards(hand, self.discards[seat], rng))]
+      },
+      Phase::Gold => {
+        let mut bank = Resources::default();
+        for resource in Resource::ALL {
+          bank.add(resource, self.bank.get(resource));
+        }
+        vec![Action::ChooseGold(random_cards(bank, self.gold[seat], rng))]
+      },
+      Phase::Gift => {
+        vec![Action::GiveCards(random_cards(self.giftable(seat), self.gifts[seat], rng))]
+      },
+      Phase::Desertion { .. } => {
+        let mut knights = self.own_knights(seat);
+        rng.shuffle(&mut knights);
+        knights.into_iter().map(Action::RemoveKnight).collect()
+      },
+      Phase::Taking { victim } => {
+        vec![Action::TakeCards(random_cards(self.players[victim].resources, GIFT_CARDS, rng))]
+      },
+      Phase::Robber => {
+        let mut hexes = self.board.tiles()
+          .map(|(hex, _)| *hex)
+          .filter(|hex| self.board.is_land(*hex))
+          .collect::<Vec<_>>();
+        rng.shuffle(&mut hexes);
+        hexes.into_iter()
+          .map(|hex| {
+            let victims = self.robber_victims(hex);
+            let victim = (!victims.is_empty()).then(|| victims[rng.usize(..victims.len())]);
+            Action::MoveRobber { hex, victim }
+          })
+          .collect()
+      },
+      Phase::RoadBuilding { .. } => {
+        let mut edges = self.board.edges().into_iter().collect::<Vec<_>>();
+        rng.shuffle(&mut edges);
+        edges.into_iter().map(Action::BuildRoad).collect()
+      },
+      Phase::Finished { .. } => Vec::new()
+    }
+  }
+}
+
+// Seats with cards count to give left
+fn pending(counts: &[u32]) -> Vec<usize> {
+  counts.iter().enumerate()
+    .filter(|(_, count)| **count > 0)
+    .map(|(seat, _)| seat)
+    .collect()
+}
+
+// Random cards drawn one by one from pool, pool must have enough cards
+pub(super) fn random_cards(mut pool: Resources, count: u32, rng: &Rng) -> Resources {
+  let mut cards = Resources::default();
+
+  for _ in 0..count.min(pool.total()) {
+    let mut index = rng.u32(..pool.total());
+    let resource = pool.iter().find_map(|(resource, count)| {
+      if index < count { return Some(resource) }
+      index -= count;
+      None
+    });
+
+    if let Some(resource) = resource {
+      pool.remove(resource, 1);
+      cards.add(resource, 1);
+    }
+  }
+
+  cards
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+use crate::game::{ action::Rejection, event::Event, resources::Resources };
+use super::Game;
+
+// Answer of other player to active player offer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Response {
+  Pending,
+  Accepted,
+  Rejected,
+  // Counter terms, as offer, from active player point of view
+  Countered { give: Resources, receive: Resources }
+}
+
+// Domestic trade offer of active player, open until confirmed, cancelled or turn ended
+#[derive(Clone, Debug)]
+pub(super) struct Offer {
+  give: Resources,
+  receive: Resources,
+  // Response of every seat, active player own response is never changed
+  responses: Vec<Response>
+}
+
+impl Game {
+  // Both sides must be non-empty and resource can not be given and received at once
+  fn check_terms(give: &Resources, receive: &Resources) -> Result<(), Rejection> {
+    if give.is_empty() || receive.is_empty() {
+      return Err(Rejection::InvalidTrade)
+    }
+
+    if give.iter().any(|(resource, _)| receive.get(resource) > 0) {
+      return Err(Rejection::InvalidTrade)
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn offer_terms(&self) -> Option<(Resources, Resources)> {
+    self.offer.as_ref().map(|offer| (offer.give, offer.receive))
+  }
+
+  // Terms of open offer, which seat has not answered yet, as give and receive of that seat
+  pub(super) fn unanswered_offer(&self, seat: usize) -> Option<(Resources, Resources)> {
+    self.offer.as_ref()
+      .filter(|offer| seat != self.active && offer.responses[seat] == Response::Pending)
+      .map(|offer| (offer.receive, offer.give))
+  }
+
+  // New offer replaces previous one with all its responses
+  pub(super) fn offer_trade(
+    &mut self, give: Resources, receive: Resources
+  ) -> Result<Vec<Event>, Rejection> {
+    Self::check_terms(&give, &receive)?;
+
+    if !self.players[self.active].resources.contains(&give) {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    self.offer = Some(Offer {
+      give,
+      receive,
+      responses: vec![Response::Pending; self.players.len()]
+    });
+
+    Ok(vec![Event::TradeOffered { seat: self.active, give, receive }])
+  }
+
+  // Other players may respond any number of times, until active player confirms
+  pub(super) fn respond_trade(
+    &mut self, seat: usize, response: Response
+  ) -> Result<Vec<Event>, Rejection> {
+    if seat == self.active {
+      return Err(Rejection::NotYourTurn)
+    }
+
+    if let Response::Countered { give, receive } = response {
+      Self::check_terms(&give, &receive)?;
+    }
+
+    let offer = self.offer.as_mut().ok_or(Rejection::NoOffer)?;
+    offer.responses[seat] = response;
+
+    Ok(vec![match response {
+      Response::Accepted => Event::TradeAccepted { seat },
+      Response::Countered { give, receive } => Event::TradeCountered { seat, give, receive },
+      Response::Pending | Response::Rejected => Event::TradeRejected { seat }
+    }])
+  }
+
+  // Hands of both players are validated at confirmation, because they could change
+  // since offer or response were made
+  pub(super) fn confirm_trade(&mut self, partner: usize) -> Result<Vec<Event>, Rejection> {
+    let offer = self.offer.as_ref().ok_or(Rejection::NoOffer)?;
+
+    if partner == self.active || partner >= self.players.len() {
+      return Err(Rejection::InvalidPartner)
+    }
+
+    let (give, receive) = match offer.responses[partner] {
+      Response::Accepted => (offer.give, offer.receive),
+      Response::Countered { give, receive } => (give, receive),
+      Response::Pending | Response::Rejected => return Err(Rejection::NotAccepted)
+    };
+
+    if !self.players[self.active].resources.contains(&give)
+    || !self.players[partner].resources.contains(&receive)
+    {
+      return Err(Rejection::NotEnoughResources)
+    }
+
+    let seat = self.active;
+    self.players[seat].resources -= give;
+    self.players[seat].resources += receive;
+    self.players[partner].resources -= receive;
+    self.players[partner].resources += give;
+
+    self.offer = None;
+
+    Ok(vec![Event::TradeCompleted { seat, partner, give, receive }])
+  }
+
+  pub(super) fn cancel_trade(&mut self) -> Result<Vec<Event>, Rejection> {
+    self.offer.take().ok_or(Rejection::NoOffer)?;
+
+    Ok(vec![Event::TradeCancelled { seat: self.active }])
+  }
+}
\ No newline at end of file
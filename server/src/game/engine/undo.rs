@@ -0,0 +1,82 @@
+use crate::game::{ action::Rejection, event::Event };
+use super::{ Game, Phase };
+
+// Undo of last action requested by its player, other players vote for it
+#[derive(Clone, Debug)]
+pub(super) struct UndoRequest {
+  seat: usize,
+  // Vote of every seat, None until player votes, requester vote is always approval
+  votes: Vec<Option<bool>>
+}
+
+impl Game {
+  // Active player may undo own last action, if it revealed nothing hidden or random
+  pub fn request_undo(&mut self, seat: usize) -> Result<Vec<Event>, Rejection> {
+    if self.undo.is_some() {
+      return Err(Rejection::UndoPending)
+    }
+
+    if seat != self.active {
+      return Err(Rejection::NotYourTurn)
+    }
+
+    let own_last = self.log.last().is_some_and(|(last, _)| *last == seat);
+    if !own_last || self.revealed || matches!(self.phase, Phase::Finished { .. }) {
+      return Err(Rejection::NotUndoable)
+    }
+
+    let mut votes = vec![None; self.players.len()];
+    votes[seat] = Some(true);
+    self.undo = Some(UndoRequest { seat, votes });
+
+    Ok(vec![Event::UndoRequested { seat }])
+  }
+
+  // Single rejection declines request, last approval rolls game back by replaying log
+  // without last action, so flag is set, when game is rolled back
+  pub fn vote_undo(
+    &mut self, seat: usize, approve: bool
+  ) -> Result<(Vec<Event>, bool), Rejection> {
+    let request = self.undo.as_mut().ok_or(Rejection::NoUndoRequest)?;
+
+    if request.votes[seat].is_some() {
+      return Err(Rejection::AlreadyVoted)
+    }
+    request.votes[seat] = Some(approve);
+
+    let requester = request.seat;
+    let mut events = vec![Event::UndoVoted { seat, approve }];
+
+    if !approve {
+      self.undo = None;
+      events.push(Event::UndoFinished { seat: requester, approved: false });
+      return Ok((events, false))
+    }
+
+    if request.votes.iter().any(Option::is_none) {
+      return Ok((events, false))
+    }
+
+    let mut log = self.log.clone();
+    log.pop();
+    // Log without its last action replays without rejections, as whole log
+    if let Ok((game, _)) = self.rebuild(&log) {
+      *self = game;
+    }
+
+    events.push(Event::UndoFinished { seat: requester, approved: true });
+
+    Ok((events, true))
+  }
+
+  // Seat still has to vote for pending request
+  pub fn undo_vote_pending(&self, seat: usize) -> bool {
+    self.undo.as_ref().is_some_and(|request| request.votes[seat].is_none())
+  }
+
+  // Any applied action declines pending request, because it is not last action anymore
+  pub(super) fn cancel_undo(&mut self) -> Option<Event> {
+    let request = self.undo.take()?;
+    Some(Event::UndoFinished { seat: request.seat, approved: false })
+  }
+}
\ No newline at end of file
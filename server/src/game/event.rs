@@ -0,0 +1,111 @@
+use super::{
+  board::{ Edge, Hex, Vertex },
+  cards::{ DevelopmentCard, ProgressCard, Track },
+  engine::{ Phase, Score },
+  resources::{ Resource, Resources }
+};
+
+// Event die is name of Cities & Knights die
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+  PhaseChanged { seat: usize, phase: Phase, turn: u32 },
+  DiceRolled { seat: usize, dice: [u8; 2] },
+  ResourcesProduced { seat: usize, resources: Resources },
+  // Bank has not enough cards of resource for every player owed it
+  ProductionBlocked { resource: Resource },
+  DiscardRequired { seat: usize, count: u32 },
+  Discarded { seat: usize, resources: Resources },
+  RobberMoved { seat: usize, hex: Hex },
+  // Resource is hidden by projection for everyone except thief and victim
+  CardStolen { thief: usize, victim: usize, resource: Option<Resource> },
+  RoadBuilt { seat: usize, edge: Edge },
+  SettlementBuilt { seat: usize, vertex: Vertex },
+  CityBuilt { seat: usize, vertex: Vertex },
+  // Card is hidden by projection for everyone except buyer
+  DevelopmentCardBought { seat: usize, card: Option<DevelopmentCard> },
+  DevelopmentCardPlayed { seat: usize, card: DevelopmentCard },
+  YearOfPlentyTaken { seat: usize, resources: Resources },
+  MonopolyTaken { seat: usize, resource: Resource, count: u32 },
+  // Seat is None, when nobody holds longest road, length is holder road length
+  LongestRoadChanged { seat: Option<usize>, length: u32 },
+  LargestArmyChanged { seat: usize, knights: u32 },
+  // Final summary with score of every seat, including hidden victory point cards
+  GameFinished { winner: usize, scores: Vec<Score> },
+  TradeOffered { seat: usize, give: Resources, receive: Resources },
+  TradeAccepted { seat: usize },
+  TradeRejected { seat: usize },
+  TradeCountered { seat: usize, give: Resources, receive: Resources },
+  // Seat is active player, who gives and receives resources
+  TradeCompleted { seat: usize, partner: usize, give: Resources, receive: Resources },
+  TradeCancelled { seat: usize },
+  MaritimeTraded { seat: usize, give: Resources, receive: Resources },
+  // Count of every resource is its best trade ratio with bank
+  TradeRatiosChanged { seat: usize, ratios: Resources },
+  ShipBuilt { seat: usize, edge: Edge },
+  ShipMoved { seat: usize, from: Edge, to: Edge },
+  PirateMoved { seat: usize, hex: Hex },
+  GoldRequired { seat: usize, count: u32 },
+  IslandDiscovered { seat: usize },
+  // Gate is None, when event die shows barbarian ship
+  EventDieRolled { seat: usize, gate: Option<Track> },
+  BarbariansMoved { position: u8 },
+  // Barbarians are repelled, if defense is not less than strength,
+  // every knight is inactive after attack
+  BarbariansAttacked { strength: u32, defense: u32 },
+  // City is reduced to settlement, or removed, when no settlement is left in supply
+  CityPillaged { seat: usize, vertex: Vertex, removed: bool },
+  DefenderOfCatan { seat: usize },
+  CityWallBuilt { seat: usize, vertex: Vertex },
+  CityImproved { seat: usize, track: Track, level: u8 },
+  MetropolisChanged { seat: usize, track: Track, vertex: Vertex },
+  // Card is hidden by projection for everyone except player
+  ProgressCardDrawn { seat: usize, track: Track, card: Option<ProgressCard> },
+  ProgressCardPlayed { seat: usize, card: ProgressCard },
+  // Card is hidden by projection for everyone except thief and victim
+  ProgressCardStolen { thief: usize, victim: usize, card: Option<ProgressCard> },
+  KnightBuilt { seat: usize, vertex: Vertex },
+  KnightPromoted { seat: usize, vertex: Vertex, level: u8 },
+  KnightActivated { seat: usize, vertex: Vertex },
+  // Moved knight becomes inactive
+  KnightMoved { seat: usize, from: Vertex, to: Vertex },
+  // Seat is owner of displaced knight, it returns to supply, when destination is None
+  KnightDisplaced { seat: usize, from: Vertex, to: Option<Vertex> },
+  // Knight becomes inactive, robber phase follows
+  RobberChased { seat: usize, vertex: Vertex },
+  MerchantPlaced { seat: usize, hex: Hex },
+  TokensSwapped { first: Hex, second: Hex },
+  GiftRequired { seat: usize, count: u32 },
+  // Resources are hidden by projection for everyone except giver and receiver
+  CardsGiven { seat: usize, receiver: usize, count: u32, resources: Option<Resources> },
+  // Hand of seat is shown to viewer, it is hidden by projection for everyone else
+  HandShown { seat: usize, viewer: usize, resources: Option<Resources> },
+  // Seat is owner of removed road, it returns to supply
+  RoadRemoved { seat: usize, edge: Edge },
+  // Seat is owner of removed knight, it returns to supply
+  KnightRemoved { seat: usize, vertex: Vertex },
+  // Knight is placed for free with given level and activity
+  KnightPlaced { seat: usize, vertex: Vertex, level: u8, active: bool },
+  UndoRequested { seat: usize },
+  UndoVoted { seat: usize, approve: bool },
+  // Seat is requester, game is rolled back, when undo is approved
+  UndoFinished { seat: usize, approved: bool }
+}
+
+impl Event {
+  // Random results and cards hidden before, actions with them can not be undone
+  pub const fn reveals(&self) -> bool {
+    matches!(
+      self,
+      Self::DiceRolled { .. }
+      | Self::EventDieRolled { .. }
+      | Self::CardStolen { .. }
+      | Self::DevelopmentCardBought { .. }
+      | Self::ProgressCardDrawn { .. }
+      | Self::ProgressCardStolen { .. }
+      | Self::MonopolyTaken { .. }
+      | Self::CardsGiven { .. }
+      | Self::HandShown { .. }
+    )
+  }
+}
\ No newline at end of file
@@ -0,0 +1,123 @@
+use sha2::{ Digest, Sha256 };
+
+pub const SECRET_SIZE: usize = 32;
+// Maximum bytes count of single player entropy
+pub const MAX_ENTROPY: usize = 64;
+
+// Verifiable randomness of game: server commits to hash of its secret before players
+// contribute their entropy, seeds are derived from both, so nobody chooses them alone
+// Secret is revealed at game end, so anyone can check commitment and recompute board,
+// every roll and shuffle from revealed setup and actions log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof {
+  pub secret: [u8; SECRET_SIZE],
+  // Entropy of every seat, empty if player contributed nothing
+  pub contributions: Vec<Vec<u8>>
+}
+
+impl Proof {
+  // Secret is taken from operating system, because game random generator is predictable
+  pub fn new() -> Result<Self, getrandom::Error> {
+    let mut secret = [0; SECRET_SIZE];
+    getrandom::getrandom(&mut secret)?;
+
+    Ok(Self { secret, contributions: Vec::new() })
+  }
+
+  // SHA-256 hash of secret
+  pub fn commitment(&self) -> Vec<u8> {
+    Sha256::digest(self.secret).to_vec()
+  }
+
+  // Later contribution of seat replaces previous one, entropy must not exceed maximum size
+  pub fn contribute(&mut self, seat: usize, entropy: Vec<u8>) {
+    if self.contributions.len() <= seat {
+      self.contributions.resize(seat + 1, Vec::new());
+    }
+    self.contributions[seat] = entropy;
+  }
+
+  // Contributions are fixed for every seat of started game
+  pub fn seal(&mut self, players: usize) {
+    self.contributions.resize(players, Vec::new());
+  }
+
+  // Board and game seeds are first and second 8 bytes (little endian) of SHA-256 hash of secret
+  // and every seat entropy, which is prefixed with its length as 4 bytes (little endian)
+  pub fn seeds(&self) -> (u64, u64) {
+    let mut hasher = Sha256::new();
+    hasher.update(self.secret);
+
+    for entropy in &self.contributions {
+      // Entropy size never exceeds maximum, so it fits u32
+      let size = u32::try_from(entropy.len()).unwrap_or(u32::MAX);
+      hasher.update(size.to_le_bytes());
+      hasher.update(entropy);
+    }
+
+    let hash = hasher.finalize();
+    let mut board = [0; 8];
+    let mut game = [0; 8];
+    board.copy_from_slice(&hash[..8]);
+    game.copy_from_slice(&hash[8..16]);
+
+    (u64::from_le_bytes(board), u64::from_le_bytes(game))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Secret of zero bytes, first seat contributed "abc", second one nothing
+  fn proof() -> Proof {
+    let mut proof = Proof { secret: [0; SECRET_SIZE], contributions: Vec::new() };
+    proof.contribute(0, b"abc".to_vec());
+    proof.seal(2);
+    proof
+  }
+
+  // Check of revealed secret against commitment got at join, as player does it
+  fn verifies(proof: &Proof, commitment: &[u8]) -> bool {
+    Sha256::digest(proof.secret).as_slice() == commitment
+  }
+
+  #[test]
+  fn commitment_is_hash_of_secret() {
+    let hash = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925";
+    let commitment = proof().commitment();
+
+    let hex = commitment.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    assert_eq!(hex, hash);
+    assert!(verifies(&proof(), &commitment));
+  }
+
+  #[test]
+  fn seeds_are_derived_from_secret_and_contributions() {
+    assert_eq!(proof().contributions, vec![b"abc".to_vec(), Vec::new()]);
+    assert_eq!(proof().seeds(), (15_995_065_299_332_837_113, 18_156_514_685_716_980_834));
+
+    // Length prefix keeps entropy of neighbouring seats apart
+    let mut moved = proof();
+    moved.contribute(0, b"ab".to_vec());
+    moved.contribute(1, b"c".to_vec());
+    assert_ne!(moved.seeds(), proof().seeds());
+  }
+
+  #[test]
+  fn tampered_secret_is_rejected() {
+    let commitment = proof().commitment();
+    let mut tampered = proof();
+    tampered.secret[SECRET_SIZE - 1] ^= 1;
+
+    assert!(!verifies(&tampered, &commitment));
+    assert_ne!(tampered.seeds(), proof().seeds());
+  }
+
+  #[test]
+  fn generated_secrets_differ() {
+    let (first, second) = (Proof::new().unwrap(), Proof::new().unwrap());
+    assert_ne!(first.secret, second.secret);
+    assert_ne!(first.commitment(), second.commitment());
+  }
+}
\ No newline at end of file
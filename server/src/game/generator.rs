@@ -0,0 +1,328 @@
+use fastrand::Rng;
+use std::{ collections::BTreeMap, fmt::{ Display, Formatter, Result as FmtResult } };
+use super::{
+  board::{
+    Board, Edge, Harbor, HarborKind, Hex, Terrain, Tile,
+    extension_harbor_edges, pips, standard_harbor_edges
+  },
+  resources::Resource
+};
+
+// Shuffling attempts for each of terrains and tokens stages before giving up
+const MAX_ATTEMPTS: u32 = 100_000;
+
+#[derive(Clone, Debug)]
+pub struct Template {
+  pub hexes: Vec<Hex>,
+  pub terrains: Vec<Terrain>,
+  pub tokens: Vec<u8>,
+  pub harbor_edges: Vec<Edge>,
+  pub harbor_kinds: Vec<HarborKind>
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+  // Tokens 6 and 8 are not placed on neighbouring tiles
+  pub separate_red_tokens: bool,
+  // Tiles with same terrain are not placed next to each other
+  pub separate_terrains: bool,
+  // Maximum sum of token pips around single intersection
+  pub max_vertex_pips: Option<u8>
+}
+
+// Everything needed to reproduce and audit generated board
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+  pub seed: u64,
+  pub constraints: Constraints,
+  pub terrain_attempts: u32,
+  pub token_attempts: u32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenerateError {
+  TemplateMismatch,
+  TerrainsUnsatisfiable,
+  TokensUnsatisfiable
+}
+
+impl Display for GenerateError {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    match self {
+      Self::TemplateMismatch => write!(f, "template pools sizes do not match positions"),
+      Self::TerrainsUnsatisfiable => {
+        write!(f, "terrain constraints not satisfied in {MAX_ATTEMPTS} attempts")
+      },
+      Self::TokensUnsatisfiable => {
+        write!(f, "token constraints not satisfied in {MAX_ATTEMPTS} attempts")
+      }
+    }
+  }
+}
+
+impl Template {
+  pub fn standard() -> Self {
+    let mut hexes = Vec::with_capacity(19);
+    for r in -2..=2_i8 {
+      for q in (-2).max(-2 - r)..=2.min(2 - r) {
+        hexes.push(Hex::new(q, r));
+      }
+    }
+
+    let mut terrains = vec![Terrain::Desert];
+    for (terrain, count) in [
+      (Terrain::Hills, 3), (Terrain::Forest, 4), (Terrain::Mountains, 3),
+      (Terrain::Fields, 4), (Terrain::Pasture, 4)
+    ] {
+      terrains.extend([terrain].repeat(count));
+    }
+
+    let mut harbor_kinds = [HarborKind::Generic].repeat(4);
+    harbor_kinds.extend(Resource::ALL.map(HarborKind::Specific));
+
+    Self {
+      hexes,
+      terrains,
+      tokens: vec![2, 3, 3, 4, 4, 5, 5, 6, 6, 8, 8, 9, 9, 10, 10, 11, 11, 12],
+      harbor_edges: standard_harbor_edges(),
+      harbor_kinds
+    }
+  }
+
+  // 5-6 player extension board: rows of 3, 4, 5, 6, 5, 4 and 3 tiles
+  pub fn extension() -> Self {
+    let mut hexes = Vec::with_capacity(30);
+    for r in -3..=3_i8 {
+      for q in (-3).max(-3 - r)..=2.min(2 - r) {
+        hexes.push(Hex::new(q, r));
+      }
+    }
+
+    let mut terrains = vec![Terrain::Desert, Terrain::Desert];
+    for (terrain, count) in [
+      (Terrain::Hills, 5), (Terrain::Forest, 6), (Terrain::Mountains, 5),
+      (Terrain::Fields, 6), (Terrain::Pasture, 6)
+    ] {
+      terrains.extend([terrain].repeat(count));
+    }
+
+    let mut harbor_kinds = [HarborKind::Generic].repeat(5);
+    harbor_kinds.extend(Resource::ALL.map(HarborKind::Specific));
+    harbor_kinds.push(HarborKind::Specific(Resource::Wool));
+
+    Self {
+      hexes,
+      terrains,
+      tokens: vec![
+        2, 2, 3, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6, 8, 8, 8, 9, 9, 9, 10, 10, 10, 11, 11, 11, 12, 12
+      ],
+      harbor_edges: extension_harbor_edges(),
+      harbor_kinds
+    }
+  }
+
+  fn productive_count(&self) -> usize {
+    self.terrains.iter().filter(|terrain| terrain.resource().is_some()).count()
+  }
+}
+
+pub struct Generator {
+  template: Template,
+  constraints: Constraints
+}
+
+impl Generator {
+  pub const fn new(template: Template, constraints: Constraints) -> Self {
+    Self { template, constraints }
+  }
+
+  pub fn generate(&self, seed: u64) -> Result<(Board, Report), GenerateError> {
+    let template = &self.template;
+
+    if template.hexes.len() != template.terrains.len()
+    || template.productive_count() != template.tokens.len()
+    || template.harbor_edges.len() != template.harbor_kinds.len()
+    {
+      return Err(GenerateError::TemplateMismatch)
+    }
+
+    let rng = Rng::with_seed(seed);
+
+    let mut terrains = template.terrains.clone();
+    let terrain_attempts = attempt(|| {
+      rng.shuffle(&mut terrains);
+      !self.constraints.separate_terrains || self.terrains_separated(&terrains)
+    }).ok_or(GenerateError::TerrainsUnsatisfiable)?;
+
+    let mut tokens = template.tokens.clone();
+    let mut tiles = BTreeMap::new();
+    let token_attempts = attempt(|| {
+      rng.shuffle(&mut tokens);
+      tiles = self.place_tokens(&terrains, &tokens);
+      self.tokens_satisfied(&tiles)
+    }).ok_or(GenerateError::TokensUnsatisfiable)?;
+
+    let mut harbor_kinds = template.harbor_kinds.clone();
+    rng.shuffle(&mut harbor_kinds);
+    let harbors = template.harbor_edges.iter().zip(harbor_kinds)
+      .map(|(edge, kind)| Harbor { edge: *edge, kind })
+      .collect();
+
+    let robber = tiles.iter()
+      .find(|(_, tile)| tile.terrain.resource().is_none())
+      .map_or(template.hexes[0], |(hex, _)| *hex);
+
+    let report = Report { seed, constraints: self.constraints, terrain_attempts, token_attempts };
+
+    Ok((Board::new(tiles, harbors, robber), report))
+  }
+
+  fn terrains_separated(&self, terrains: &[Terrain]) -> bool {
+    let placed = self.template.hexes.iter().copied().zip(terrains.iter().copied())
+      .collect::<BTreeMap<Hex, Terrain>>();
+
+    placed.iter().all(|(hex, terrain)| {
+      terrain.resource().is_none()
+      || hex.neighbours().iter().all(|neighbour| placed.get(neighbour) != Some(terrain))
+    })
+  }
+
+  fn place_tokens(&self, terrains: &[Terrain], tokens: &[u8]) -> BTreeMap<Hex, Tile> {
+    let mut tokens = tokens.iter();
+
+    self.template.hexes.iter().zip(terrains).map(|(hex, terrain)| {
+      let token = if terrain.resource().is_some() { tokens.next().copied() } else { None };
+      (*hex, Tile { terrain: *terrain, token })
+    }).collect()
+  }
+
+  fn tokens_satisfied(&self, tiles: &BTreeMap<Hex, Tile>) -> bool {
+    let is_red = |hex: &Hex| {
+      tiles.get(hex).and_then(|tile| tile.token).is_some_and(|token| token == 6 || token == 8)
+    };
+
+    if self.constraints.separate_red_tokens
+    && tiles.keys().any(|hex| is_red(hex) && hex.neighbours().iter().any(is_red))
+    {
+      return false
+    }
+
+    if let Some(max_vertex_pips) = self.constraints.max_vertex_pips {
+      return tiles.keys().flat_map(|hex| hex.vertices()).all(|vertex| {
+        let sum = vertex.hexes().iter()
+          .filter_map(|hex| tiles.get(hex).and_then(|tile| tile.token))
+          .map(pips)
+          .sum::<u8>();
+        sum <= max_vertex_pips
+      })
+    }
+
+    true
+  }
+}
+
+// Call shuffling function until it succeed, return used attempts count
+fn attempt<F: FnMut() -> bool>(mut shuffle: F) -> Option<u32> {
+  (1..=MAX_ATTEMPTS).find(|_| shuffle())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ALL: Constraints = Constraints {
+    separate_red_tokens: true,
+    separate_terrains: true,
+    max_vertex_pips: Some(12)
+  };
+
+  fn tiles(board: &Board) -> Vec<(Hex, Tile)> {
+    board.tiles().map(|(hex, tile)| (*hex, *tile)).collect()
+  }
+
+  // Two neighbouring hills with given tokens and no harbors
+  fn pair(tokens: Vec<u8>) -> Template {
+    Template {
+      hexes: vec![Hex::new(0, 0), Hex::new(1, 0)],
+      terrains: vec![Terrain::Hills, Terrain::Hills],
+      tokens,
+      harbor_edges: Vec::new(),
+      harbor_kinds: Vec::new()
+    }
+  }
+
+  #[test]
+  fn same_seed_generates_same_board() {
+    let red = Constraints { separate_red_tokens: true, ..Constraints::default() };
+    for template in [Template::standard(), Template::extension()] {
+      let generator = Generator::new(template, red);
+      let (first, first_report) = generator.generate(42).unwrap();
+      let (second, second_report) = generator.generate(42).unwrap();
+
+      assert_eq!(tiles(&first), tiles(&second));
+      assert_eq!(first.harbors(), second.harbors());
+      assert_eq!(first.robber(), second.robber());
+      assert_eq!(first_report, second_report);
+    }
+  }
+
+  #[test]
+  fn generated_boards_satisfy_constraints() {
+    let generator = Generator::new(Template::standard(), ALL);
+
+    for seed in 0..20 {
+      let (board, report) = generator.generate(seed).unwrap();
+      assert_eq!(report.constraints, ALL);
+
+      let token = |hex: &Hex| board.tile(*hex).and_then(|tile| tile.token);
+      for (hex, tile) in board.tiles() {
+        let neighbours = hex.neighbours();
+
+        if matches!(tile.token, Some(6 | 8)) {
+          assert!(!neighbours.iter().any(|other| matches!(token(other), Some(6 | 8))));
+        }
+
+        if tile.terrain.resource().is_some() {
+          let same = |other: &Hex| {
+            board.tile(*other).is_some_and(|other| other.terrain == tile.terrain)
+          };
+          assert!(!neighbours.iter().any(same));
+        }
+
+        for vertex in hex.vertices() {
+          let sum = vertex.hexes().iter().filter_map(token).map(pips).sum::<u8>();
+          assert!(sum <= 12);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn impossible_constraints_fail_after_max_attempts() {
+    let terrains = Constraints { separate_terrains: true, ..Constraints::default() };
+    let result = Generator::new(pair(vec![5, 9]), terrains).generate(0);
+    assert_eq!(result.err(), Some(GenerateError::TerrainsUnsatisfiable));
+
+    let red = Constraints { separate_red_tokens: true, ..Constraints::default() };
+    let result = Generator::new(pair(vec![6, 8]), red).generate(0);
+    assert_eq!(result.err(), Some(GenerateError::TokensUnsatisfiable));
+
+    let pips = Constraints { max_vertex_pips: Some(0), ..Constraints::default() };
+    let result = Generator::new(Template::standard(), pips).generate(0);
+    assert_eq!(result.err(), Some(GenerateError::TokensUnsatisfiable));
+  }
+
+  #[test]
+  fn mismatched_template_is_rejected() {
+    let result = Generator::new(pair(vec![5]), Constraints::default()).generate(0);
+    assert_eq!(result.err(), Some(GenerateError::TemplateMismatch));
+  }
+
+  #[test]
+  fn attempts_are_counted_up_to_limit() {
+    let mut calls = 0;
+    assert_eq!(attempt(|| { calls += 1; false }), None);
+    assert_eq!(calls, MAX_ATTEMPTS);
+    assert_eq!(attempt(|| true), Some(1));
+  }
+}
\ No newline at end of file
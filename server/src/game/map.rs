@@ -0,0 +1,214 @@
+use fastrand::Rng;
+use std::{ collections::{ BTreeMap, BTreeSet }, fmt::{ Display, Formatter, Result as FmtResult } };
+use super::board::{ Board, Edge, Harbor, HarborKind, Hex, Terrain, Tile };
+
+// Tile of custom map, None terrain is drawn from terrain pool, None token of tile,
+// which gets token, is drawn from token pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapTile {
+  pub hex: Hex,
+  pub terrain: Option<Terrain>,
+  pub token: Option<u8>
+}
+
+// None kind is drawn from harbor pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapHarbor {
+  pub edge: Edge,
+  pub kind: Option<HarborKind>
+}
+
+// Board layout designed by community, fixed parts stay on their places,
+// randomized parts are shuffled on every game start
+#[derive(Clone, Debug)]
+pub struct Map {
+  pub tiles: Vec<MapTile>,
+  pub terrain_pool: Vec<Terrain>,
+  pub token_pool: Vec<u8>,
+  pub harbors: Vec<MapHarbor>,
+  pub harbor_pool: Vec<HarborKind>,
+  // Robber starts on first tile without token, when it is not set
+  pub robber: Option<Hex>
+}
+
+// Errors hold index of wrong tile or harbor, so error path points to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapError {
+  NoLand,
+  DuplicateHex(usize),
+  InvalidToken(usize),
+  UnexpectedToken(usize),
+  InvalidPoolToken(usize),
+  TerrainPoolMismatch,
+  TokenPoolMismatch,
+  HarborNotOnCoast(usize),
+  DuplicateHarbor(usize),
+  HarborPoolMismatch,
+  RobberNotOnLand
+}
+
+impl MapError {
+  // Path of wrong value in map file
+  pub fn path(&self) -> String {
+    match self {
+      Self::NoLand => "tiles".to_string(),
+      Self::DuplicateHex(index) => format!("tiles[{index}].hex"),
+      Self::InvalidToken(index) | Self::UnexpectedToken(index) => format!("tiles[{index}].token"),
+      Self::InvalidPoolToken(index) => format!("token_pool[{index}]"),
+      Self::TerrainPoolMismatch => "terrain_pool".to_string(),
+      Self::TokenPoolMismatch => "token_pool".to_string(),
+      Self::HarborNotOnCoast(index) | Self::DuplicateHarbor(index) => format!("harbors[{index}]"),
+      Self::HarborPoolMismatch => "harbor_pool".to_string(),
+      Self::RobberNotOnLand => "robber".to_string()
+    }
+  }
+}
+
+impl Display for MapError {
+  fn fmt(&self, f: &mut Formatter) -> FmtResult {
+    match self {
+      Self::NoLand => write!(f, "map has no land tiles"),
+      Self::DuplicateHex(_) => write!(f, "hex is already used by other tile"),
+      Self::InvalidToken(_) | Self::InvalidPoolToken(_) => {
+        write!(f, "token must be from 2 to 12, except 7")
+      },
+      Self::UnexpectedToken(_) => write!(f, "token is allowed only on fixed producing terrain"),
+      Self::TerrainPoolMismatch => write!(f, "pool size does not match random terrain tiles"),
+      Self::TokenPoolMismatch => write!(f, "pool size does not match tiles without token"),
+      Self::HarborNotOnCoast(_) => write!(f, "harbor edge must separate land from sea"),
+      Self::DuplicateHarbor(_) => write!(f, "harbor edge is already used by other harbor"),
+      Self::HarborPoolMismatch => write!(f, "pool size does not match random harbors"),
+      Self::RobberNotOnLand => write!(f, "robber must start on land tile")
+    }
+  }
+}
+
+const fn is_valid_token(token: u8) -> bool {
+  matches!(token, 2..=6 | 8..=12)
+}
+
+impl Map {
+  // Tokens of random terrain tiles depend on drawn terrain, so pool counts producing
+  // terrains of terrain pool
+  pub fn validate(&self) -> Result<(), MapError> {
+    let mut hexes = BTreeSet::new();
+    let mut random_terrains = 0;
+    let mut missing_tokens = 0;
+
+    for (index, tile) in self.tiles.iter().enumerate() {
+      if !hexes.insert(tile.hex) {
+        return Err(MapError::DuplicateHex(index))
+      }
+
+      match (tile.terrain, tile.token) {
+        (Some(terrain), Some(token)) => {
+          if !terrain.has_token() {
+            return Err(MapError::UnexpectedToken(index))
+          }
+          if !is_valid_token(token) {
+            return Err(MapError::InvalidToken(index))
+          }
+        },
+        (Some(terrain), None) => {
+          if terrain.has_token() {
+            missing_tokens += 1;
+          }
+        },
+        (None, Some(_)) => return Err(MapError::UnexpectedToken(index)),
+        (None, None) => random_terrains += 1
+      }
+    }
+
+    let fixed_land = |hex| self.tiles.iter().any(|tile| {
+      tile.hex == hex && tile.terrain.is_some_and(|terrain| terrain != Terrain::Sea)
+    });
+    let fixed_sea = |hex| self.tiles.iter().any(|tile| {
+      tile.hex == hex && tile.terrain == Some(Terrain::Sea)
+    });
+
+    let has_land = self.tiles.iter().any(|tile| fixed_land(tile.hex))
+      || self.terrain_pool.iter().any(|terrain| *terrain != Terrain::Sea);
+    if !has_land {
+      return Err(MapError::NoLand)
+    }
+
+    if self.terrain_pool.len() != random_terrains {
+      return Err(MapError::TerrainPoolMismatch)
+    }
+
+    missing_tokens += self.terrain_pool.iter().filter(|terrain| terrain.has_token()).count();
+    if self.token_pool.len() != missing_tokens {
+      return Err(MapError::TokenPoolMismatch)
+    }
+
+    if let Some(index) = self.token_pool.iter().position(|token| !is_valid_token(*token)) {
+      return Err(MapError::InvalidPoolToken(index))
+    }
+
+    // Random tile may be drawn as sea, so robber starts only on fixed land
+    if self.robber.is_some_and(|robber| !fixed_land(robber)) {
+      return Err(MapError::RobberNotOnLand)
+    }
+
+    let mut edges = BTreeSet::new();
+    for (index, harbor) in self.harbors.iter().enumerate() {
+      // Harbor borders exactly one tile, which may be land, random tiles are counted too
+      let land_sides = harbor.edge.hexes().iter()
+        .filter(|hex| hexes.contains(*hex) && !fixed_sea(**hex))
+        .count();
+      if land_sides != 1 {
+        return Err(MapError::HarborNotOnCoast(index))
+      }
+
+      if !edges.insert(harbor.edge) {
+        return Err(MapError::DuplicateHarbor(index))
+      }
+    }
+
+    let random_harbors = self.harbors.iter().filter(|harbor| harbor.kind.is_none()).count();
+    if self.harbor_pool.len() != random_harbors {
+      return Err(MapError::HarborPoolMismatch)
+    }
+
+    Ok(())
+  }
+
+  // Map must be validated, pools are shuffled with seed, so board is reproducible
+  pub fn board(&self, seed: u64) -> Board {
+    let rng = Rng::with_seed(seed);
+
+    let mut terrain_pool = self.terrain_pool.clone();
+    rng.shuffle(&mut terrain_pool);
+    let mut token_pool = self.token_pool.clone();
+    rng.shuffle(&mut token_pool);
+    let mut harbor_pool = self.harbor_pool.clone();
+    rng.shuffle(&mut harbor_pool);
+
+    let mut terrains = terrain_pool.into_iter();
+    let mut tokens = token_pool.into_iter();
+    let tiles = self.tiles.iter()
+      .map(|tile| {
+        let terrain = tile.terrain.or_else(|| terrains.next()).unwrap_or(Terrain::Desert);
+        let token = if terrain.has_token() { tile.token.or_else(|| tokens.next()) } else { None };
+        (tile.hex, Tile { terrain, token })
+      })
+      .collect::<BTreeMap<Hex, Tile>>();
+
+    let mut kinds = harbor_pool.into_iter();
+    let harbors = self.harbors.iter()
+      .filter_map(|harbor| {
+        let kind = harbor.kind.or_else(|| kinds.next())?;
+        Some(Harbor { edge: harbor.edge, kind })
+      })
+      .collect();
+
+    let robber = self.robber.unwrap_or_else(|| {
+      tiles.iter()
+        .find(|(_, tile)| tile.terrain != Terrain::Sea && tile.token.is_none())
+        .or_else(|| tiles.iter().find(|(_, tile)| tile.terrain != Terrain::Sea))
+        .map_or(Hex::new(0, 0), |(hex, _)| *hex)
+    });
+
+    Board::new(tiles, harbors, robber)
+  }
+}
\ No newline at end of file
@@ -0,0 +1,23 @@
+mod action;
+pub mod board;
+pub mod cards;
+pub mod dice;
+mod engine;
+mod event;
+pub mod fairness;
+pub mod generator;
+pub mod map;
+mod player;
+pub mod projection;
+pub mod scenario;
+pub mod resources;
+pub mod rules;
+
+pub use self::{
+  action::{ Action, Rejection, Target },
+  engine::{
+    EXTENSION_PLAYERS, MAX_PLAYERS, MIN_PLAYERS, BuildingKind, Difficulty, Game, Observation,
+    Phase, Score, Setup
+  },
+  event::Event
+};
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::collections::BTreeSet;
+use super::{
+  board::Hex, cards::{ DevelopmentCard, ProgressCard }, resources::Resources
+};
+
+// Pieces count in supply of every player at game start
+const ROADS: u8 = 15;
+const SETTLEMENTS: u8 = 5;
+const CITIES: u8 = 4;
+const SHIPS: u8 = 15;
+const CITY_WALLS: u8 = 3;
+// Knights of every level: basic, strong and mighty
+const KNIGHTS: u8 = 2;
+
+#[derive(Clone, Debug)]
+pub struct Player {
+  pub resources: Resources,
+  // Pieces left in supply
+  pub roads: u8,
+  pub settlements: u8,
+  pub cities: u8,
+  pub ships: u8,
+  pub cards: Vec<DevelopmentCard>,
+  // Cards bought in current turn, they can not be played until next turn
+  pub new_cards: Vec<DevelopmentCard>,
+  // Played knights
+  pub knights: u32,
+  // Islands with own settlements, identified as in board
+  pub islands: BTreeSet<Hex>,
+  pub island_bonuses: u32,
+  pub city_walls: u8,
+  // Knights left in supply, indexed by level from basic
+  pub knight_pieces: [u8; 3],
+  // Level of every city improvement track, indexed as track
+  pub improvements: [u8; 3],
+  pub progress_cards: Vec<ProgressCard>,
+  // Revealed victory point progress cards
+  pub progress_points: u32,
+  pub defender_points: u32
+}
+
+impl Player {
+  pub const fn new() -> Self {
+    Self {
+      resources: Resources::new(0, 0, 0, 0, 0),
+      roads: ROADS,
+      settlements: SETTLEMENTS,
+      cities: CITIES,
+      ships: SHIPS,
+      cards: Vec::new(),
+      new_cards: Vec::new(),
+      knights: 0,
+      islands: BTreeSet::new(),
+      island_bonuses: 0,
+      city_walls: CITY_WALLS,
+      knight_pieces: [KNIGHTS; 3],
+      improvements: [0; 3],
+      progress_cards: Vec::new(),
+      progress_points: 0,
+      defender_points: 0
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use super::event::Event;
+
+// Projection turns authoritative game information into view of single seat. Opponents hands are
+// counts only (see `Game::observe`), stolen, bought, drawn, given and shown cards are known only
+// to their owners, decks order and random state are never projected. Every game message is
+// projected for its receiver before sending
+
+impl Event {
+  // Event as player of seat sees it
+  pub fn project(&self, seat: usize) -> Self {
+    match *self {
+      Self::CardStolen { thief, victim, .. } if seat != thief && seat != victim => {
+        Self::CardStolen { thief, victim, resource: None }
+      },
+      Self::ProgressCardStolen { thief, victim, .. } if seat != thief && seat != victim => {
+        Self::ProgressCardStolen { thief, victim, card: None }
+      },
+      Self::DevelopmentCardBought { seat: buyer, .. } if seat != buyer => {
+        Self::DevelopmentCardBought { seat: buyer, card: None }
+      },
+      Self::ProgressCardDrawn { seat: player, track, .. } if seat != player => {
+        Self::ProgressCardDrawn { seat: player, track, card: None }
+      },
+      Self::CardsGiven { seat: from, receiver: to, count, .. } if seat != from && seat != to => {
+        Self::CardsGiven { seat: from, receiver: to, count, resources: None }
+      },
+      Self::HandShown { seat: owner, viewer, .. } if seat != owner && seat != viewer => {
+        Self::HandShown { seat: owner, viewer, resources: None }
+      },
+      _ => self.clone()
+    }
+  }
+}
+
+// Seats grouped by their views of event, so seats with same view share one message
+pub fn views(event: &Event, seats: usize) -> Vec<(Event, Vec<usize>)> {
+  let mut views: Vec<(Event, Vec<usize>)> = Vec::new();
+
+  for seat in 0..seats {
+    let view = event.project(seat);
+    match views.iter_mut().find(|(other, _)| *other == view) {
+      Some((_, group)) => group.push(seat),
+      None => views.push((view, vec![seat]))
+    }
+  }
+
+  views
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::game::{ cards::DevelopmentCard, resources::{ Resource, Resources } };
+  use super::*;
+
+  const HAND: Resources = Resources::new(1, 0, 2, 0, 0);
+
+  #[test]
+  fn stolen_card_is_known_to_thief_and_victim_only() {
+    let stolen = Event::CardStolen { thief: 0, victim: 1, resource: Some(Resource::Ore) };
+    let hidden = Event::CardStolen { thief: 0, victim: 1, resource: None };
+
+    assert_eq!(views(&stolen, 4), vec![(stolen, vec![0, 1]), (hidden, vec![2, 3])]);
+  }
+
+  #[test]
+  fn bought_card_is_known_to_buyer_only() {
+    let bought = Event::DevelopmentCardBought { seat: 2, card: Some(DevelopmentCard::Knight) };
+    let hidden = Event::DevelopmentCardBought { seat: 2, card: None };
+
+    assert_eq!(views(&bought, 3), vec![(hidden, vec![0, 1]), (bought, vec![2])]);
+  }
+
+  #[test]
+  fn given_and_shown_cards_are_known_to_both_sides_only() {
+    let given = Event::CardsGiven { seat: 1, receiver: 2, count: 3, resources: Some(HAND) };
+    assert_eq!(given.project(1), given);
+    assert_eq!(given.project(2), given);
+    let hidden = Event::CardsGiven { seat: 1, receiver: 2, count: 3, resources: None };
+    assert_eq!(given.project(0), hidden);
+
+    let shown = Event::HandShown { seat: 0, viewer: 2, resources: Some(HAND) };
+    assert_eq!(shown.project(0), shown);
+    assert_eq!(shown.project(2), shown);
+    assert_eq!(shown.project(1), Event::HandShown { seat: 0, viewer: 2, resources: None });
+  }
+
+  #[test]
+  fn public_event_has_single_view() {
+    let traded = Event::MaritimeTraded { seat: 0, give: HAND, receive: HAND };
+    assert_eq!(views(&traded, 3), vec![(traded, vec![0, 1, 2])]);
+  }
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use std::ops::{ AddAssign, SubAssign };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Resource {
+  Brick = 0,
+  Lumber = 1,
+  Ore = 2,
+  Grain = 3,
+  Wool = 4,
+  // Commodities of Cities & Knights, produced only by cities
+  Paper = 5,
+  Cloth = 6,
+  Coin = 7
+}
+
+// Count of cards of every resource and commodity, used for players hands, bank and costs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Resources([u32; 8]);
+
+pub const ROAD_COST: Resources = Resources::new(1, 1, 0, 0, 0);
+pub const SETTLEMENT_COST: Resources = Resources::new(1, 1, 0, 1, 1);
+pub const CITY_COST: Resources = Resources::new(0, 0, 3, 2, 0);
+pub const SHIP_COST: Resources = Resources::new(0, 1, 0, 0, 1);
+pub const DEVELOPMENT_CARD_COST: Resources = Resources::new(0, 0, 1, 1, 1);
+pub const CITY_WALL_COST: Resources = Resources::new(2, 0, 0, 0, 0);
+pub const KNIGHT_COST: Resources = Resources::new(0, 0, 1, 0, 1);
+pub const KNIGHT_ACTIVATION_COST: Resources = Resources::new(0, 0, 0, 1, 0);
+
+impl Resource {
+  // Resources produced by terrains
+  pub const ALL: [Self; 5] = [Self::Brick, Self::Lumber, Self::Ore, Self::Grain, Self::Wool];
+  pub const COMMODITIES: [Self; 3] = [Self::Paper, Self::Cloth, Self::Coin];
+
+  pub const fn is_commodity(self) -> bool {
+    matches!(self, Self::Paper | Self::Cloth | Self::Coin)
+  }
+
+  // Commodity, which city produces instead of second resource card of terrain
+  pub const fn commodity(self) -> Option<Self> {
+    match self {
+      Self::Lumber => Some(Self::Paper),
+      Self::Wool => Some(Self::Cloth),
+      Self::Ore => Some(Self::Coin),
+      _ => None
+    }
+  }
+}
+
+impl Resources {
+  pub const fn new(brick: u32, lumber: u32, ore: u32, grain: u32, wool: u32) -> Self {
+    Self([brick, lumber, ore, grain, wool, 0, 0, 0])
+  }
+
+  pub const fn commodities(paper: u32, cloth: u32, coin: u32) -> Self {
+    Self([0, 0, 0, 0, 0, paper, cloth, coin])
+  }
+
+  pub const fn get(&self, resource: Resource) -> u32 {
+    self.0[resource as usize]
+  }
+
+  pub const fn add(&mut self, resource: Resource, count: u32) {
+    self.0[resource as usize] += count;
+  }
+
+  // Caller must check that enough cards of resource exist
+  pub const fn remove(&mut self, resource: Resource, count: u32) {
+    self.0[resource as usize] -= count;
+  }
+
+  pub fn total(&self) -> u32 {
+    self.0.iter().sum()
+  }
+
+  pub fn contains(&self, other: &Self) -> bool {
+    self.0.iter().zip(other.0.iter()).all(|(own, other)| own >= other)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.total() == 0
+  }
+
+  pub fn has_commodities(&self) -> bool {
+    Resource::COMMODITIES.iter().any(|commodity| self.get(*commodity) > 0)
+  }
+
+  // Resources and commodities with non-zero count
+  pub fn iter(&self) -> impl Iterator<Item = (Resource, u32)> + '_ {
+    Resource::ALL.into_iter()
+      .chain(Resource::COMMODITIES)
+      .map(|resource| (resource, self.get(resource)))
+      .filter(|(_, count)| *count > 0)
+  }
+}
+
+impl AddAssign for Resources {
+  fn add_assign(&mut self, other: Self) {
+    for (own, other) in self.0.iter_mut().zip(other.0) {
+      *own += other;
+    }
+  }
+}
+
+// Caller must check that subtracted resources are contained
+impl SubAssign for Resources {
+  fn sub_assign(&mut self, other: Self) {
+    for (own, other) in self.0.iter_mut().zip(other.0) {
+      *own -= other;
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use super::{ dice::Dice, resources::Resources, scenario::Scenario };
+
+// Players with more cards than limit discard half of them, when seven is rolled
+pub const DISCARD_LIMIT: u32 = 7;
+// Initial placement rounds, players order is reversed in every next round
+const SETUP_ROUNDS: u8 = 2;
+
+// Limits of house rules, so game stays playable with pieces and bank of scenario
+const MIN_VICTORY_POINTS: u32 = 3;
+const MAX_VICTORY_POINTS: u32 = 30;
+const MAX_DISCARD_LIMIT: u32 = 30;
+const MAX_SETUP_ROUNDS: u8 = 3;
+const MAX_STARTING_BONUS: u32 = 10;
+
+// House rules chosen by host, defaults are standard rules of scenario
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+  pub victory_points: u32,
+  pub discard_limit: u32,
+  // Players with fewer visible points are not robbed, 0 disables friendly robber
+  pub friendly_robber: u32,
+  // Active player may trade before dice roll too
+  pub trade_between_rolls: bool,
+  pub setup_rounds: u8,
+  // Cards every player gets from bank at game start
+  pub starting_bonus: Resources,
+  pub dice: Dice
+}
+
+impl Rules {
+  pub const fn new(scenario: Scenario) -> Self {
+    Self {
+      victory_points: scenario.victory_points(),
+      discard_limit: DISCARD_LIMIT,
+      friendly_robber: 0,
+      trade_between_rolls: false,
+      setup_rounds: SETUP_ROUNDS,
+      starting_bonus: Resources::new(0, 0, 0, 0, 0),
+      dice: Dice::Standard
+    }
+  }
+
+  // Commodities exist only in Cities & Knights
+  pub fn is_valid(&self, scenario: Scenario) -> bool {
+    (MIN_VICTORY_POINTS..=MAX_VICTORY_POINTS).contains(&self.victory_points)
+    && (1..=MAX_DISCARD_LIMIT).contains(&self.discard_limit)
+    && (1..=MAX_SETUP_ROUNDS).contains(&self.setup_rounds)
+    && self.starting_bonus.total() <= MAX_STARTING_BONUS
+    && (scenario.cities_and_knights() || !self.starting_bonus.has_commodities())
+  }
+}
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use super::board::{ Board, Hex, Terrain, Tile };
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scenario {
+  // Base game with generated board, 5-6 player extension for larger games
+  #[default]
+  Base,
+  // Seafarers "Heading for New Shores": main island with small islands around it
+  NewShores,
+  // Cities & Knights on generated board
+  CitiesAndKnights
+}
+
+// Radius of New Shores frame, every hex within it, which is not land, is sea
+const NEW_SHORES_RADIUS: i8 = 5;
+
+// Small islands of New Shores outside of main island, which is base game beginner layout
+const NEW_SHORES_ISLANDS: [(i8, i8, Terrain, u8); 12] = [
+  (0, -4, Terrain::Fields, 10),
+  (1, -4, Terrain::Mountains, 3),
+  (2, -4, Terrain::Gold, 9),
+  (4, -1, Terrain::Hills, 5),
+  (4, 0, Terrain::Gold, 10),
+  (3, 1, Terrain::Pasture, 11),
+  (-2, 4, Terrain::Mountains, 9),
+  (-1, 4, Terrain::Pasture, 3),
+  (0, 4, Terrain::Fields, 8),
+  (-4, 1, Terrain::Pasture, 4),
+  (-4, 2, Terrain::Hills, 11),
+  (-4, 3, Terrain::Forest, 12)
+];
+
+impl Scenario {
+  pub const fn victory_points(self) -> u32 {
+    match self {
+      Self::Base => 10,
+      Self::NewShores => 14,
+      Self::CitiesAndKnights => 13
+    }
+  }
+
+  pub const fn max_players(self) -> usize {
+    match self {
+      Self::Base => 6,
+      Self::NewShores | Self::CitiesAndKnights => 4
+    }
+  }
+
+  // Ships, pirate, gold fields and island bonuses
+  pub const fn seafarers(self) -> bool {
+    matches!(self, Self::NewShores)
+  }
+
+  // Commodities, city improvements, knights, barbarians and progress cards instead of
+  // development cards
+  pub const fn cities_and_knights(self) -> bool {
+    matches!(self, Self::CitiesAndKnights)
+  }
+
+  // Hex of island, where setup settlements must be placed, None if any island is allowed
+  pub const fn setup_island(self) -> Option<Hex> {
+    match self {
+      Self::Base | Self::CitiesAndKnights => None,
+      Self::NewShores => Some(Hex::new(0, 0))
+    }
+  }
+
+  // Fixed scenario map, None if board is generated
+  pub fn board(self) -> Option<Board> {
+    match self {
+      Self::Base | Self::CitiesAndKnights => None,
+      Self::NewShores => Some(new_shores_board())
+    }
+  }
+}
+
+fn new_shores_board() -> Board {
+  let main = Board::standard();
+
+  let mut tiles = main.tiles()
+    .map(|(hex, tile)| (*hex, *tile))
+    .collect::<BTreeMap<Hex, Tile>>();
+
+  for (q, r, terrain, token) in NEW_SHORES_ISLANDS {
+    tiles.insert(Hex::new(q, r), Tile { terrain, token: Some(token) });
+  }
+
+  let center = Hex::new(0, 0);
+  for r in -NEW_SHORES_RADIUS..=NEW_SHORES_RADIUS {
+    for q in -NEW_SHORES_RADIUS..=NEW_SHORES_RADIUS {
+      let hex = Hex::new(q, r);
+      if center.distance(hex) <= NEW_SHORES_RADIUS.unsigned_abs() {
+        tiles.entry(hex).or_insert(Tile { terrain: Terrain::Sea, token: None });
+      }
+    }
+  }
+
+  Board::new(tiles, main.harbors().to_vec(), main.robber())
+}
\ No newline at end of file
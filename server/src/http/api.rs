@@ -2,26 +2,47 @@ use bytes::Bytes;
 use http_body_util::BodyExt;
 use hyper::{ body::Incoming, Request, StatusCode };
 use lazy_static::lazy_static;
-use log::debug;
-use std::collections::HashMap;
-use crate::protos::auth::{
-  CheckTokenParams, CheckTokenResult, CheckTokenTestParams, CheckTokenTestResult
+use log::{ debug, error };
+use sea_orm::DatabaseConnection;
+use std::{ collections::HashMap, future::Future, pin::Pin };
+use crate::{
+  protos::{
+    auth::{ CheckTokenParams, CheckTokenResult, CheckTokenTestParams, CheckTokenTestResult },
+    replay::{ ExportReplayParams, StepReplayParams }
+  },
+  replay::{ export, step }
 };
 use super::helpers::{
   MAX_API_BODY_SIZE, HttpResponse, status_response,
   deserialize_api_params as deserialize, serialize_api_response as serialize
 };
 
-type HandlerWrapper = dyn Fn(&Bytes) -> Result<HttpResponse, HttpResponse> + Sync;
+// Handlers are async, because some of them read database
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<HttpResponse, HttpResponse>> + Send>>;
+type HandlerWrapper = dyn Fn(Bytes, DatabaseConnection) -> HandlerFuture + Sync + Send;
 type RouteHandlers = HashMap<&'static str, Box<HandlerWrapper>>;
 
+// Routes with uploaded files, they use main HTTP body limit
+// TODO: if upload profile picture method name changed, change it too
+const UPLOAD_ROUTES: [&str; 2] = ["upload_picture", "step_replay"];
+
 lazy_static! {
   pub static ref ROUTE_HANDLERS: RouteHandlers = {
     // IMPORTANT: increase capacity when new route will be added
-    let mut routes: RouteHandlers = HashMap::with_capacity(2);
+    let mut routes: RouteHandlers = HashMap::with_capacity(4);
 
-    routes.insert("check_token", Box::new(|body| Ok(check_token(deserialize(body)?))));
-    routes.insert("check_token_test", Box::new(|body| Ok(check_token_test(deserialize(body)?))));
+    routes.insert("check_token", Box::new(|body, _| Box::pin(async move {
+      Ok(check_token(deserialize(&body)?))
+    })));
+    routes.insert("check_token_test", Box::new(|body, _| Box::pin(async move {
+      Ok(check_token_test(deserialize(&body)?))
+    })));
+    routes.insert("export_replay", Box::new(|body, db| Box::pin(async move {
+      Ok(export_replay(&db, deserialize(&body)?).await)
+    })));
+    routes.insert("step_replay", Box::new(|body, _| Box::pin(async move {
+      Ok(step_replay(&deserialize(&body)?))
+    })));
 
     routes
   };
@@ -39,14 +60,31 @@ fn check_token_test(_params: CheckTokenTestParams) -> HttpResponse {
   serialize(CheckTokenTestResult { result: true })
 }
 
-pub async fn api(path: &str, req: Request<Incoming>, body_size: u64) -> HttpResponse {
+// Replay of finished game, game id is sent to players at game end
+async fn export_replay(db: &DatabaseConnection, params: ExportReplayParams) -> HttpResponse {
+  match export(db, params.game_id).await {
+    Ok(Some(replay)) => serialize(replay),
+    Ok(None) => status_response(StatusCode::NOT_FOUND),
+    Err(err) => {
+      error!("Export replay of game {} error: {err}", params.game_id);
+      status_response(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+  }
+}
+
+fn step_replay(params: &StepReplayParams) -> HttpResponse {
+  step(params).map_or_else(|| status_response(StatusCode::BAD_REQUEST), serialize)
+}
+
+pub async fn api(
+  path: &str, req: Request<Incoming>, body_size: u64, db: DatabaseConnection
+) -> HttpResponse {
   if !ROUTE_HANDLERS.contains_key(path) {
     return status_response(StatusCode::NOT_FOUND)
   }
 
   // Check API request maximum body size before read it
-  // TODO: if upload profile picture method name changed, change it too
-  if path != "upload_picture" && body_size > MAX_API_BODY_SIZE {
+  if !UPLOAD_ROUTES.contains(&path) && body_size > MAX_API_BODY_SIZE {
     debug!("API body too large: {body_size} > {MAX_API_BODY_SIZE}");
     return status_response(StatusCode::PAYLOAD_TOO_LARGE)
   }
@@ -66,7 +104,7 @@ pub async fn api(path: &str, req: Request<Incoming>, body_size: u64) -> HttpResp
   // Ok variant contain return value of exactly handler function
   // Err variant contain error API params deserialization
   // Due to use of a shorter syntax `?` in API handlers closure wrappers
-  match route_handler(&body) {
+  match route_handler(body, db).await {
     Ok(response) | Err(response) => response
   }
 }
\ No newline at end of file
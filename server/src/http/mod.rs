@@ -16,6 +16,7 @@ use tokio::{
   io::{ AsyncRead, AsyncWrite }, net::{ TcpListener, TcpStream },
   sync::{ oneshot::Receiver, Mutex }, task::spawn, select
 };
+use sea_orm::DatabaseConnection;
 use crate::{ communicator::Communicator, helpers::exit_with_error, settings::SETTINGS };
 use self::{
   api::{ ROUTE_HANDLERS, api },
@@ -45,7 +46,8 @@ const MAX_HTTP_BODY_USIZE: usize = MAX_HTTP_BODY_SIZE as usize;
 
 #[derive(Clone)]
 struct Service {
-  communicator: Arc<Mutex<Communicator>>
+  communicator: Arc<Mutex<Communicator>>,
+  db: DatabaseConnection
 }
 
 impl HyperService<Request<Incoming>> for Service {
@@ -55,7 +57,8 @@ impl HyperService<Request<Incoming>> for Service {
 
   fn call(&mut self, req: Request<Incoming>) -> Self::Future {
     let communicator = self.communicator.clone();
-    Box::pin(async { Ok(handle_connection(req, communicator).await) })
+    let db = self.db.clone();
+    Box::pin(async { Ok(handle_connection(req, communicator, db).await) })
   }
 }
 
@@ -92,7 +95,7 @@ fn build_section_subpath(path: &str) -> (&str, String) {
 }
 
 async fn handle_connection(
-  req: Request<Incoming>, communicator: Arc<Mutex<Communicator>>
+  req: Request<Incoming>, communicator: Arc<Mutex<Communicator>>, db: DatabaseConnection
 ) -> HttpResponse {
   // Main check payload size for all HTTP requests
   // For API requests (except profile picture upload) separate limit
@@ -124,7 +127,7 @@ async fn handle_connection(
       serve(&subpath, req).await
     },
     "api" => {
-      let mut response = api(&subpath, req, body_size).await;
+      let mut response = api(&subpath, req, body_size, db).await;
       let headers = response.headers_mut();
 
       // Disable caching for API requests for browsers and HTTP 1.0 proxies
@@ -134,7 +137,7 @@ async fn handle_connection(
 
       response
     },
-    "ws" => ws(&subpath, req, communicator).await,
+    "ws" => ws(&subpath, req, communicator, db).await,
     _ => status_response(StatusCode::NOT_FOUND)
   }
 }
@@ -268,7 +271,9 @@ async fn run(listener: TcpListener, service: Service, _: (), mut stop_receiver:
   }
 }
 
-pub async fn start(communicator: Arc<Mutex<Communicator>>, stop_receiver: Receiver<()>) {
+pub async fn start(
+  communicator: Arc<Mutex<Communicator>>, db: DatabaseConnection, stop_receiver: Receiver<()>
+) {
   // For "secure_server" feature create_additional_acceptor return used later value,
   // it used for same `run` function signatures for "secure_server" and if it disabled
   #[allow(clippy::let_unit_value)]
@@ -284,7 +289,7 @@ pub async fn start(communicator: Arc<Mutex<Communicator>>, stop_receiver: Receiv
   #[cfg(any(feature = "client_resources_caching", feature = "client_resources_packing"))]
   initialize(&CLIENT_RESOURCES);
 
-  run(listener, Service { communicator }, additional_acceptor, stop_receiver).await;
+  run(listener, Service { communicator, db }, additional_acceptor, stop_receiver).await;
 
   // TODO: when https://github.com/hyperium/hyper/issues/2730 will be fixed,
   //       implement server graceful shutdown
@@ -4,39 +4,106 @@ use http_body_util::Full;
 use hyper::{
   body::Incoming,
   header::{
-    CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE, HeaderValue
+    AUTHORIZATION, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION,
+    UPGRADE, HeaderMap, HeaderValue
   },
   upgrade::{ Upgraded, on },
   Method, Request, Response, StatusCode, Version
 };
 use log::{ debug, error };
-use std::sync::Arc;
+use sea_orm::{ ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter };
+use std::{ sync::Arc, time::{ Duration, Instant } };
 use tokio::{ sync::Mutex, task::spawn, select };
 use tokio_tungstenite::{
   tungstenite::{ handshake::derive_accept_key, protocol::Role, Error, Message }, WebSocketStream
 };
-use crate::communicator::Communicator;
+use crate::{
+  bots::token_hash,
+  communicator::{ Client, Communicator, Data },
+  db::entities::auth_method::{ self, Method as AuthMethod },
+  messages::{ encode, rejection },
+  protos::game::RejectionReason,
+  settings::SETTINGS
+};
 use super::helpers::{
   WEB_SOCKET_CONFIG, HttpResponse, PreBuiltHeader,
   header_value, get_header_str, header_list_contains, status_response
 };
 
+// Messages count of bot connection in current second
+struct RateLimit {
+  limit: u32,
+  window: Instant,
+  count: u32
+}
+
+impl RateLimit {
+  fn new() -> Self {
+    // Limit is always set by settings defaults
+    Self { limit: SETTINGS.bots.rate_limit.unwrap_or(u32::MAX), window: Instant::now(), count: 0 }
+  }
+
+  fn allow(&mut self) -> bool {
+    let now = Instant::now();
+    if now.duration_since(self.window) >= Duration::from_secs(1) {
+      self.window = now;
+      self.count = 0;
+    }
+
+    self.count += 1;
+    self.count <= self.limit
+  }
+}
+
+// Bot account connects with "Authorization: Bot <token>" header, other peers are players
+// Hashes are compared, so lookup time does not depend on how much of token is guessed
+async fn authenticate(headers: &HeaderMap, db: &DatabaseConnection) -> Result<Client, StatusCode> {
+  let Some(authorization) = get_header_str(headers, &AUTHORIZATION) else {
+    return Ok(Client::Player)
+  };
+  let Some(token) = authorization.strip_prefix("Bot ") else {
+    return Err(StatusCode::UNAUTHORIZED)
+  };
+
+  let method = auth_method::Entity::find()
+    .filter(auth_method::Column::Method.eq(AuthMethod::Bot))
+    .filter(auth_method::Column::Data.eq(token_hash(token)))
+    .one(db).await
+    .map_err(|err| {
+      error!("Find bot account error: {err}");
+      StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+  method.map(|method| Client::Bot(method.user_id)).ok_or(StatusCode::UNAUTHORIZED)
+}
+
 async fn handle_connection(
-  stream: WebSocketStream<Upgraded>, communicator: Arc<Mutex<Communicator>>
+  stream: WebSocketStream<Upgraded>, communicator: Arc<Mutex<Communicator>>, client: Client
 ) {
   let mut communicator_lock = communicator.lock().await;
   let (id, sender, mut receiver) = communicator_lock.add();
   drop(communicator_lock);
 
   let (mut write, mut read) = stream.split();
+  let mut rate_limit = RateLimit::new();
 
   loop {
     select! {
       from = read.next() => {
         match from {
           Some(result) => match result {
-            Ok(message) => if let Message::Text(data) = message {
-              if let Err(err) = sender.send((id, data)) {
+            Ok(message) => if let Message::Binary(data) = message {
+              // Extra bot messages are not passed to game, bot is answered directly
+              if matches!(client, Client::Bot(_)) && !rate_limit.allow() {
+                let limited = encode(rejection(RejectionReason::RATE_LIMITED));
+                if let Err(err) = write.send(Message::Binary(limited)).await {
+                  debug!("Send WS message {id} error: {err}");
+                  break
+                }
+                continue
+              }
+
+              if let Err(err) = sender.send(Data::Message(id, client, data)) {
                 error!("Send from peer {id} error: {err}");
                 break
               }
@@ -51,7 +118,7 @@ async fn handle_connection(
       },
       to = receiver.recv() => {
         if let Some(data) = to {
-          if let Err(err) = write.send(Message::Text(data)).await {
+          if let Err(err) = write.send(Message::Binary(data)).await {
             debug!("Send WS message {id} error: {err}");
             break
           }
@@ -83,7 +150,8 @@ async fn handle_connection(
 }
 
 pub async fn ws(
-  path: &str, mut req: Request<Incoming>, communicator: Arc<Mutex<Communicator>>
+  path: &str, mut req: Request<Incoming>, communicator: Arc<Mutex<Communicator>>,
+  db: DatabaseConnection
 ) -> HttpResponse {
   let version = req.version();
   let headers = req.headers();
@@ -105,11 +173,17 @@ pub async fn ws(
   let key = unsafe { key_option.unwrap_unchecked() };
   let derived = derive_accept_key(key.as_bytes());
 
+  let client = match authenticate(headers, &db).await {
+    Ok(client) => client,
+    Err(code) => return status_response(code)
+  };
+
   spawn(async move {
     match on(&mut req).await {
       Ok(upgraded) => handle_connection(
         WebSocketStream::from_raw_socket(upgraded, Role::Server, Some(*WEB_SOCKET_CONFIG)).await,
-        communicator
+        communicator,
+        client
       ).await,
       Err(err) => debug!("Upgrade HTTP connection error: {err}")
     }
@@ -1,24 +1,42 @@
 use log::debug;
-use std::sync::Arc;
-use tokio::{ sync::{ oneshot::Receiver as OneshotReceiver, Mutex }, select };
-use crate::{ communicator::{ Communicator, Data, Receiver } };
+use std::{ future::pending, sync::Arc, time::Instant };
+use tokio::{ sync::{ oneshot::Receiver as OneshotReceiver, Mutex }, select, time::sleep_until };
+use crate::{
+  communicator::{ Client, Communicator, Data, Receiver },
+  lobby::{ Lobby, Outbox, Timers },
+  messages::{ decode, encode, rejection },
+  protos::game::RejectionReason,
+  recorder::{ Restored, Sender as RecordSender }
+};
 
 pub struct Intermedium {
   communicator: Arc<Mutex<Communicator>>,
-  receiver: Receiver
+  receiver: Receiver,
+  lobby: Lobby
 }
 
 impl Intermedium {
-  pub fn new(communicator: Arc<Mutex<Communicator>>, receiver: Receiver) -> Self {
+  pub fn new(
+    communicator: Arc<Mutex<Communicator>>, receiver: Receiver,
+    recorder: RecordSender, restored: Vec<Restored>, last_game: u32, timers: Timers
+  ) -> Self {
     Self {
       communicator,
-      receiver
+      receiver,
+      lobby: Lobby::new(recorder, restored, last_game, timers)
     }
   }
 
-  async fn send(&self, id: u32, data: String) -> bool {
-    let communicator_lock = self.communicator.lock().await;
-    communicator_lock.send(id, data)
+  // Not a method, because lobby is not Sync, so reference to Intermedium
+  // can not be held across await point
+  async fn send(communicator: &Arc<Mutex<Communicator>>, outbox: Outbox) {
+    let communicator_lock = communicator.lock().await;
+    // Message is encoded once for all its receivers
+    for (ids, message) in outbox {
+      for id in communicator_lock.broadcast(&ids, &encode(message)) {
+        debug!("Send message to peer {id} failed");
+      }
+    }
   }
 
   async fn receive(&mut self) -> Data {
@@ -29,13 +47,44 @@ impl Intermedium {
     unsafe { data_option.unwrap_unchecked() }
   }
 
+  // Never completes without deadline, so select waits for other branches only
+  async fn expiry(deadline: Option<Instant>) {
+    match deadline {
+      Some(deadline) => sleep_until(deadline.into()).await,
+      None => pending().await
+    }
+  }
+
+  fn handle(&mut self, id: u32, client: Client, data: &[u8]) -> Outbox {
+    // Bot account is bound to its last connection, so it is invited there
+    if let Client::Bot(user) = client {
+      self.lobby.connect_bot(id, user);
+    }
+
+    // Client messages are validated and turned into typed actions by lobby,
+    // invalid ones are answered with rejection to sender only
+    decode(data).map_or_else(
+      || vec![(vec![id], rejection(RejectionReason::MALFORMED))],
+      |message| self.lobby.handle(id, message)
+    )
+  }
+
   pub async fn run(&mut self, mut stop_receiver: OneshotReceiver<()>) {
     loop {
+      // Deadline is taken on every iteration, because every handled message may change it
+      let deadline = self.lobby.next_deadline();
+
       select! {
-        (id, data) = self.receive() => {
-          println!("Data received {id}: {data}");
-          let result = self.send(id, data).await;
-          println!("Send result {id}: {result}");
+        data = self.receive() => {
+          let outbox = match data {
+            Data::Message(id, client, data) => self.handle(id, client, &data),
+            Data::Closed(id) => self.lobby.disconnect(id)
+          };
+          Self::send(&self.communicator, outbox).await;
+        },
+        () = Self::expiry(deadline) => {
+          let outbox = self.lobby.expire();
+          Self::send(&self.communicator, outbox).await;
         },
         _ = &mut stop_receiver => {
           debug!("Graceful intermedium shutdown");
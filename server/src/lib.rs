@@ -0,0 +1,16 @@
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::nursery)]
+#![deny(clippy::cargo)]
+
+// Project will not published on crates.io, so no need for fields "keywords" and "categories"
+#![allow(clippy::cargo_common_metadata)]
+// Library is used only by binaries of this package, it is not public API for other crates,
+// so lints of exported API documentation and attributes are not needed
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::must_use_candidate)]
+#![allow(clippy::return_self_not_must_use)]
+
+// Rules engine is built once and shared by server and simulation binaries
+pub mod game;
+pub mod helpers;
\ No newline at end of file
@@ -0,0 +1,1043 @@
+use fastrand::Rng;
+use log::{ error, info };
+use sha2::{ Digest, Sha256 };
+use std::{ collections::HashMap, time::{ Duration, Instant } };
+use crate::{
+  game::{
+    EXTENSION_PLAYERS, MAX_PLAYERS, MIN_PLAYERS, Action, Difficulty, Event, Game, Phase,
+    Setup,
+    board::Board,
+    fairness::{ MAX_ENTROPY, Proof },
+    projection,
+    generator::{ Constraints, Generator, Template },
+    map::Map,
+    rules::Rules,
+    scenario::Scenario
+  },
+  maps::MAPS,
+  messages::{
+    action_from, action_to, difficulty_from, encode_action, encode_owners, encode_setup, event_to,
+    game_started_to, observation_to, rejection, rejection_to, rules_from, scenario_from, seat_to
+  },
+  protos::game::{
+    mod_ClientMessage::OneOfmessage as Incoming, mod_ServerMessage::OneOfmessage as Outgoing,
+    GameInvitation, GameJoined, GameRecorded, PlayerJoined, PlayerLeft, ReconnectToken,
+    RejectionReason, SeedRevealed, TimerChanged
+  },
+  recorder::{ Record, Restored, Sender as RecordSender }
+};
+
+// Messages to send, each with receivers peer ids
+pub type Outbox = Vec<(Vec<u32>, Outgoing)>;
+
+// Bots actions made in a row, limit stops bots, which keep game in loop without players
+const BOT_ACTIONS_LIMIT: usize = 10_000;
+// Random bytes of reconnect token, token is hashed with SHA-256 of same size
+const TOKEN_SIZE: usize = 32;
+
+// Who can take seat of started game again, owners are stored with game, so they survive restart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+  // SHA-256 hash of reconnect token of player
+  Player([u8; TOKEN_SIZE]),
+  // User id of bot account
+  Account(u32),
+  Bot(Difficulty)
+}
+
+// Time limits of players, None disables limit
+#[derive(Clone, Copy, Debug)]
+pub struct Timers {
+  // Trade and build part of turn
+  pub turn: Option<Duration>,
+  // Setup placements, dice roll, discard, robber, free roads and gold choice
+  pub decision: Option<Duration>
+}
+
+// Timer runs while game waits for same decision, so it is keyed by phase, player and turn
+struct Timer {
+  key: (Phase, usize, u32),
+  deadline: Option<Instant>
+}
+
+struct Room {
+  // Restored games have no host, because they are already started
+  host: Option<u32>,
+  scenario: Scenario,
+  rules: Rules,
+  // Custom map replaces scenario board
+  map: Option<&'static Map>,
+  // Peer ids, index is player seat, seats of restored games are empty until owners rejoin
+  peers: Vec<Option<u32>>,
+  // Difficulty of bot of every seat
+  bots: Vec<Option<Difficulty>>,
+  // User id of bot account of every seat, account reclaims its seat from any connection
+  users: Vec<Option<u32>>,
+  // Reconnect token hash of every player seat of started game, player reclaims seat with token
+  tokens: Vec<Option<[u8; TOKEN_SIZE]>>,
+  // User ids of bot accounts, which host invited
+  invited: Vec<u32>,
+  // Committed secret of verifiable game before start, then it is kept in game setup
+  proof: Option<Proof>,
+  game: Option<Game>,
+  // Database id of started game, players export its replay by it, room id is reused later
+  record: u32,
+  timer: Option<Timer>
+}
+
+pub struct Lobby {
+  rng: Rng,
+  rooms: HashMap<u32, Room>,
+  // Room id of every peer, which created or joined game
+  peer_rooms: HashMap<u32, u32>,
+  // Last connection peer id of every bot account and account of every bot peer
+  bot_peers: HashMap<u32, u32>,
+  bot_users: HashMap<u32, u32>,
+  recorder: RecordSender,
+  // Database id of last started game
+  last_game: u32,
+  timers: Timers
+}
+
+impl Room {
+  // Send message to everyone in game
+  fn broadcast(&self, outbox: &mut Outbox, message: Outgoing) {
+    outbox.push((self.peers.iter().flatten().copied().collect(), message));
+  }
+
+  // Send game event projected for every seat, hidden cards reach only their owners
+  fn notify(&self, outbox: &mut Outbox, event: &Event) {
+    for (view, seats) in projection::views(event, self.peers.len()) {
+      let peers = seats.into_iter().filter_map(|seat| self.peers[seat]).collect::<Vec<_>>();
+      if !peers.is_empty() {
+        outbox.push((peers, event_to(&view)));
+      }
+    }
+  }
+
+  // Timer restarts, when game waits for other decision, change is sent to everyone
+  // Rooms without connected peers and finished games have no timer
+  fn update_timer(&mut self, outbox: &mut Outbox, timers: Timers, now: Instant) {
+    let Some(game) = self.game.as_ref() else { return };
+
+    let key = (game.phase(), game.active(), game.turn());
+    if self.timer.as_ref().is_some_and(|timer| timer.key == key) {
+      return
+    }
+
+    let connected = self.peers.iter().any(Option::is_some);
+    let duration = match game.phase() {
+      _ if !connected => None,
+      Phase::Main | Phase::SpecialBuilding { .. } => timers.turn,
+      Phase::Finished { .. } => None,
+      _ => timers.decision
+    };
+
+    let was_running = self.timer.as_ref().is_some_and(|timer| timer.deadline.is_some());
+    self.timer = Some(Timer { key, deadline: duration.map(|duration| now + duration) });
+
+    if was_running || duration.is_some() {
+      let milliseconds = duration.map_or(0, milliseconds);
+      self.broadcast(outbox, Outgoing::timer_changed(TimerChanged { milliseconds }));
+    }
+  }
+
+  // Seat left by player before start
+  fn vacant_seat(&self) -> Option<usize> {
+    (0..self.peers.len())
+      .position(|seat| self.peers[seat].is_none() && self.bots[seat].is_none()
+        && self.users[seat].is_none())
+  }
+
+  // Vacant seat or new one, None if room is full
+  fn take_seat(&mut self) -> Option<usize> {
+    if let Some(seat) = self.vacant_seat() {
+      return Some(seat)
+    }
+
+    if self.peers.len() >= MAX_PLAYERS.min(self.scenario.max_players()) {
+      return None
+    }
+
+    self.peers.push(None);
+    self.bots.push(None);
+    self.users.push(None);
+    self.tokens.push(None);
+    Some(self.peers.len() - 1)
+  }
+
+  // Seat of started game, which player has given reconnect token
+  fn token_seat(&self, token: &[u8]) -> Option<usize> {
+    let hash = token_hash(token);
+    self.tokens.iter().position(|seat_hash| *seat_hash == Some(hash))
+  }
+
+  // Every seat of started game has bot, account or player with token
+  fn owners(&self) -> Vec<Owner> {
+    (0..self.peers.len())
+      .filter_map(|seat| self.bots[seat].map(Owner::Bot)
+        .or_else(|| self.users[seat].map(Owner::Account))
+        .or_else(|| self.tokens[seat].map(Owner::Player)))
+      .collect()
+  }
+
+  fn deadline(&self) -> Option<Instant> {
+    self.timer.as_ref().and_then(|timer| timer.deadline)
+  }
+
+  // Action made by server for seat is logged and sent, as action of its player
+  fn perform(
+    &self, outbox: &mut Outbox, recorder: &RecordSender,
+    (seat, action, events): (usize, Action, Vec<Event>)
+  ) {
+    // Seats count never exceeds maximum players count, so it fits u8
+    let seat_number = u8::try_from(seat).unwrap_or(u8::MAX);
+    record(recorder, Record::Applied {
+      id: self.record, seat: seat_number, action: encode_action(action_to(action))
+    });
+
+    for event in &events {
+      self.notify(outbox, event);
+    }
+  }
+
+  // Approved undo rolls back game, it is sent from start, as to rejoined player
+  fn finish_undo(&self, outbox: &mut Outbox, recorder: &RecordSender, undone: bool) {
+    if !undone {
+      return
+    }
+
+    record(recorder, Record::Undone { id: self.record });
+
+    if let Some(game) = self.game.as_ref() {
+      let history = game.history();
+      for (seat, peer) in self.peers.iter().enumerate() {
+        if let Some(peer) = peer {
+          send_game(outbox, *peer, seat, game, &history);
+        }
+      }
+    }
+  }
+
+  // Bot accounts get their observation every time game waits for their decision
+  fn observe(&self, outbox: &mut Outbox, bot_users: &HashMap<u32, u32>) {
+    let Some(game) = self.game.as_ref() else { return };
+
+    for (seat, peer) in self.peers.iter().enumerate() {
+      let Some(peer) = peer.filter(|peer| bot_users.contains_key(peer)) else { continue };
+      if game.awaits(seat) {
+        outbox.push((vec![peer], observation_to(&game.observe(seat))));
+      }
+    }
+  }
+
+  // Finished game is recorded, its id and secret of verifiable game are sent to everyone
+  fn finish(&self, outbox: &mut Outbox, recorder: &RecordSender) {
+    record(recorder, Record::Finished { id: self.record });
+    self.broadcast(outbox, Outgoing::game_recorded(GameRecorded { game_id: self.record }));
+
+    let proof = self.game.as_ref().and_then(|game| game.setup().proof.as_ref());
+    if let Some(proof) = proof {
+      self.broadcast(outbox, Outgoing::seed_revealed(SeedRevealed {
+        secret: proof.secret.to_vec(),
+        contributions: proof.contributions.clone()
+      }));
+    }
+  }
+
+  // Bots act through same actions as players, until game waits for players
+  // Every bot approves undo, first accepted action of first bot with decision is made
+  fn play_bots(&mut self, outbox: &mut Outbox, recorder: &RecordSender, id: u32, rng: &Rng) {
+    let bots = self.bots.iter().enumerate()
+      .filter_map(|(seat, bot)| bot.map(|difficulty| (seat, difficulty)))
+      .collect::<Vec<_>>();
+    if bots.is_empty() {
+      return
+    }
+
+    for _ in 0..BOT_ACTIONS_LIMIT {
+      let Some(game) = self.game.as_mut() else { return };
+
+      let voter = bots.iter().map(|(seat, _)| *seat).find(|seat| game.undo_vote_pending(*seat));
+      if let Some(seat) = voter {
+        let Ok((events, undone)) = game.vote_undo(seat, true) else { return };
+        for event in &events {
+          self.notify(outbox, event);
+        }
+        self.finish_undo(outbox, recorder, undone);
+        continue
+      }
+
+      let applied = bots.iter().find_map(|(seat, difficulty)| {
+        game.bot_actions(*seat, *difficulty, rng).into_iter()
+          .find_map(|action| Some((*seat, action, game.apply(*seat, action).ok()?)))
+      });
+      let Some(applied) = applied else { return };
+      let finished = game.is_finished();
+
+      self.perform(outbox, recorder, applied);
+      if finished {
+        self.finish(outbox, recorder);
+      }
+    }
+
+    error!("Game {id} bots exceeded actions limit");
+  }
+}
+
+impl Lobby {
+  pub fn new(
+    recorder: RecordSender, restored: Vec<Restored>, last_game: u32, timers: Timers
+  ) -> Self {
+    let rooms = restored.into_iter()
+      .map(|Restored { id: record, room: id, game, owners, .. }| {
+        let Setup { scenario, rules, players, .. } = *game.setup();
+        let room = Room {
+          host: None,
+          scenario,
+          rules,
+          map: None,
+          peers: vec![None; players],
+          bots: owners.iter()
+            .map(|owner| if let Owner::Bot(difficulty) = owner { Some(*difficulty) } else { None })
+            .collect(),
+          users: owners.iter()
+            .map(|owner| if let Owner::Account(user) = owner { Some(*user) } else { None })
+            .collect(),
+          tokens: owners.iter()
+            .map(|owner| if let Owner::Player(hash) = owner { Some(*hash) } else { None })
+            .collect(),
+          invited: Vec::new(),
+          proof: None,
+          game: Some(game),
+          record,
+          timer: None
+        };
+        (id, room)
+      })
+      .collect();
+
+    Self {
+      rng: Rng::new(),
+      rooms,
+      peer_rooms: HashMap::new(),
+      bot_peers: HashMap::new(),
+      bot_users: HashMap::new(),
+      recorder,
+      last_game,
+      timers
+    }
+  }
+
+  pub fn handle(&mut self, peer: u32, message: Incoming) -> Outbox {
+    let result = match message {
+      Incoming::create_game(create) => {
+        let scenario = scenario_from(create.scenario);
+        rules_from(scenario, create.rules.as_ref())
+          .filter(|rules| rules.is_valid(scenario))
+          .ok_or(RejectionReason::INVALID_RULES)
+          .and_then(|rules| self.create(peer, scenario, rules, &create.map, create.verifiable))
+      },
+      Incoming::join_game(join) => self.join(peer, join.game_id, &join.token),
+      Incoming::start_game(_) => self.start(peer),
+      Incoming::add_bot(bot) => self.add_bot(peer, difficulty_from(bot.difficulty)),
+      Incoming::invite_bot(invite) => self.invite_bot(peer, invite.bot_id),
+      Incoming::contribute_entropy(contribution) => self.contribute(peer, contribution.entropy),
+      Incoming::request_undo(_) => self.undo(peer, None),
+      Incoming::vote_undo(vote) => self.undo(peer, Some(vote.approve)),
+      message => self.act(peer, &message)
+    };
+
+    let mut outbox = match result {
+      Ok(outbox) => outbox,
+      Err(reason) => return vec![(vec![peer], rejection(reason))]
+    };
+
+    if let Some(&id) = self.peer_rooms.get(&peer) {
+      // SAFETY: room removed from lobby only with all its peers
+      let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+      room.play_bots(&mut outbox, &self.recorder, id, &self.rng);
+      room.update_timer(&mut outbox, self.timers, Instant::now());
+      room.observe(&mut outbox, &self.bot_users);
+    }
+
+    outbox
+  }
+
+  // Bot account connection replaces its previous one
+  pub fn connect_bot(&mut self, peer: u32, user: u32) {
+    if self.bot_users.contains_key(&peer) {
+      return
+    }
+
+    if let Some(previous) = self.bot_peers.insert(user, peer) {
+      self.bot_users.remove(&previous);
+    }
+    self.bot_users.insert(peer, user);
+  }
+
+  // Closed connection leaves its seat, room is removed, when nobody is connected to it,
+  // unless it has unfinished game to rejoin
+  pub fn disconnect(&mut self, peer: u32) -> Outbox {
+    // Newer connection of same bot account is kept
+    if let Some(user) = self.bot_users.remove(&peer) {
+      if self.bot_peers.get(&user) == Some(&peer) {
+        self.bot_peers.remove(&user);
+      }
+    }
+
+    let Some(id) = self.peer_rooms.remove(&peer) else { return Vec::new() };
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    // SAFETY: peer room id is set only after peer added to room
+    let index = unsafe {
+      room.peers.iter().position(|p| *p == Some(peer)).unwrap_unchecked()
+    };
+    room.peers[index] = None;
+
+    // Seat of not started game is freed for anyone, with its entropy and host rights
+    if room.game.is_none() {
+      room.users[index] = None;
+      if let Some(proof) = room.proof.as_mut() {
+        proof.contribute(index, Vec::new());
+      }
+      if room.host == Some(peer) {
+        room.host = room.peers.iter().flatten().next().copied();
+      }
+    }
+
+    if room.peers.iter().all(Option::is_none) {
+      if room.game.as_ref().is_none_or(Game::is_finished) {
+        info!("Game {id} is left by everyone, it is removed");
+        self.rooms.remove(&id);
+        return Vec::new()
+      }
+      // Timer starts again, when player rejoins
+      room.timer = None;
+    }
+
+    let mut outbox = Vec::with_capacity(1);
+    let seat = seat_to(index);
+    room.broadcast(&mut outbox, Outgoing::player_left(PlayerLeft { seat }));
+    outbox
+  }
+
+  // Nearest deadline of all rooms, None if no timer is running
+  pub fn next_deadline(&self) -> Option<Instant> {
+    self.rooms.values().filter_map(Room::deadline).min()
+  }
+
+  // Server acts for players of rooms with passed deadlines, then their timers restart
+  pub fn expire(&mut self) -> Outbox {
+    let now = Instant::now();
+    let mut outbox = Vec::new();
+
+    for (id, room) in &mut self.rooms {
+      if room.deadline().is_none_or(|deadline| deadline > now) {
+        continue
+      }
+
+      let Some(game) = room.game.as_mut() else { continue };
+      let applied = game.time_out(&self.rng);
+      let finished = game.is_finished();
+
+      for applied in applied {
+        info!("Game {id} seat {} timed out, server made {:?}", applied.0, applied.1);
+        room.perform(&mut outbox, &self.recorder, applied);
+      }
+      if finished {
+        room.finish(&mut outbox, &self.recorder);
+      }
+
+      room.play_bots(&mut outbox, &self.recorder, *id, &self.rng);
+      room.observe(&mut outbox, &self.bot_users);
+
+      // Timer restarts even for same decision, so rejected defaults do not repeat at once
+      room.timer = None;
+      room.update_timer(&mut outbox, self.timers, now);
+    }
+
+    outbox
+  }
+
+  fn create(
+    &mut self, peer: u32, scenario: Scenario, rules: Rules, map: &str, verifiable: bool
+  ) -> Result<Outbox, RejectionReason> {
+    if self.peer_rooms.contains_key(&peer) {
+      return Err(RejectionReason::ALREADY_IN_GAME)
+    }
+
+    let map = if map.is_empty() {
+      None
+    } else {
+      Some(MAPS.get(map).ok_or(RejectionReason::MAP_NOT_FOUND)?)
+    };
+
+    let proof = if verifiable {
+      let proof = Proof::new().map_err(|err| {
+        error!("Generate verifiable game secret error: {err}");
+        RejectionReason::NOT_VERIFIABLE
+      })?;
+      Some(proof)
+    } else {
+      None
+    };
+    let commitment = proof.as_ref().map(Proof::commitment).unwrap_or_default();
+
+    let id = self.generate_id();
+
+    self.rooms.insert(id, Room {
+      host: Some(peer),
+      scenario,
+      rules,
+      map,
+      peers: vec![Some(peer)],
+      bots: vec![None],
+      users: vec![self.bot_users.get(&peer).copied()],
+      tokens: vec![None],
+      invited: Vec::new(),
+      proof,
+      game: None,
+      record: 0,
+      timer: None
+    });
+    self.peer_rooms.insert(peer, id);
+
+    let joined = GameJoined { game_id: id, seat: 0, commitment };
+    Ok(vec![(vec![peer], Outgoing::game_joined(joined))])
+  }
+
+  fn join(&mut self, peer: u32, id: u32, token: &[u8]) -> Result<Outbox, RejectionReason> {
+    if self.peer_rooms.contains_key(&peer) {
+      return Err(RejectionReason::ALREADY_IN_GAME)
+    }
+
+    let room = self.rooms.get_mut(&id).ok_or(RejectionReason::GAME_NOT_FOUND)?;
+
+    // Account takes its seat back, player takes seat of started game back with its token,
+    // previous connection of seat may be not closed yet
+    let user = self.bot_users.get(&peer).copied();
+    let reclaimed = user.and_then(|user| room.users.iter().position(|u| *u == Some(user)))
+      .or_else(|| room.game.as_ref().and_then(|_| room.token_seat(token)));
+
+    // Bot accounts join only games, which they are invited to
+    if reclaimed.is_none() && user.is_some_and(|user| !room.invited.contains(&user)) {
+      return Err(RejectionReason::NOT_INVITED)
+    }
+
+    if let Some(previous) = reclaimed.and_then(|index| room.peers[index].take()) {
+      self.peer_rooms.remove(&previous);
+    }
+
+    // Seats of started game are not taken by anyone else, they keep hidden cards of owners
+    if room.game.is_some() {
+      let index = reclaimed.ok_or(RejectionReason::GAME_STARTED)?;
+      return Self::rejoin(&mut self.peer_rooms, peer, id, room, index)
+    }
+
+    let index = reclaimed.or_else(|| room.take_seat()).ok_or(RejectionReason::GAME_FULL)?;
+    let seat = seat_to(index);
+    let commitment = room.proof.as_ref().map(Proof::commitment).unwrap_or_default();
+
+    let mut outbox = Vec::with_capacity(2);
+    room.broadcast(&mut outbox, Outgoing::player_joined(PlayerJoined { seat, bot: false }));
+    outbox.push((vec![peer], Outgoing::game_joined(GameJoined { game_id: id, seat, commitment })));
+
+    room.peers[index] = Some(peer);
+    room.users[index] = user;
+    self.peer_rooms.insert(peer, id);
+
+    Ok(outbox)
+  }
+
+  // Invitation is sent to last connection of bot account, bot joins game itself
+  fn invite_bot(&mut self, peer: u32, user: u32) -> Result<Outbox, RejectionReason> {
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    if room.host != Some(peer) {
+      return Err(RejectionReason::NOT_HOST)
+    }
+
+    if room.game.is_some() {
+      return Err(RejectionReason::GAME_STARTED)
+    }
+
+    let bot = *self.bot_peers.get(&user).ok_or(RejectionReason::BOT_NOT_CONNECTED)?;
+    if !room.invited.contains(&user) {
+      room.invited.push(user);
+    }
+
+    Ok(vec![(vec![bot], Outgoing::game_invitation(GameInvitation { game_id: id }))])
+  }
+
+  // Bot takes vacant or next seat as joined player, it has no peer
+  fn add_bot(&mut self, peer: u32, difficulty: Difficulty) -> Result<Outbox, RejectionReason> {
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    if room.host != Some(peer) {
+      return Err(RejectionReason::NOT_HOST)
+    }
+
+    if room.game.is_some() {
+      return Err(RejectionReason::GAME_STARTED)
+    }
+
+    let index = room.take_seat().ok_or(RejectionReason::GAME_FULL)?;
+    let seat = seat_to(index);
+
+    let mut outbox = Vec::with_capacity(1);
+    room.broadcast(&mut outbox, Outgoing::player_joined(PlayerJoined { seat, bot: true }));
+
+    room.bots[index] = Some(difficulty);
+
+    Ok(outbox)
+  }
+
+  // Entropy is kept secret until game end, so players can not adapt theirs to others
+  fn contribute(&mut self, peer: u32, entropy: Vec<u8>) -> Result<Outbox, RejectionReason> {
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    if room.game.is_some() {
+      return Err(RejectionReason::GAME_STARTED)
+    }
+
+    let proof = room.proof.as_mut().ok_or(RejectionReason::NOT_VERIFIABLE)?;
+    if entropy.len() > MAX_ENTROPY {
+      return Err(RejectionReason::INVALID_ENTROPY)
+    }
+
+    // SAFETY: peer room id is set only after peer added to room
+    let seat = unsafe {
+      room.peers.iter().position(|p| *p == Some(peer)).unwrap_unchecked()
+    };
+    proof.contribute(seat, entropy);
+
+    Ok(Vec::new())
+  }
+
+  // Started game is joined into seat of its owner, peer gets whole game history
+  // from its seat point of view
+  fn rejoin(
+    peer_rooms: &mut HashMap<u32, u32>, peer: u32, id: u32, room: &mut Room, index: usize
+  ) -> Result<Outbox, RejectionReason> {
+    let game = room.game.as_ref().ok_or(RejectionReason::GAME_NOT_STARTED)?;
+    let seat = seat_to(index);
+    let commitment = game.setup().proof.as_ref().map(Proof::commitment).unwrap_or_default();
+
+    let mut outbox = Vec::new();
+    room.broadcast(&mut outbox, Outgoing::player_joined(PlayerJoined { seat, bot: false }));
+    outbox.push((vec![peer], Outgoing::game_joined(GameJoined { game_id: id, seat, commitment })));
+    send_game(&mut outbox, peer, index, game, &game.history());
+    // Running timer is not changed by rejoin, so only joined peer gets its time left
+    if let Some(deadline) = room.deadline() {
+      let milliseconds = milliseconds(deadline.saturating_duration_since(Instant::now()));
+      outbox.push((vec![peer], Outgoing::timer_changed(TimerChanged { milliseconds })));
+    }
+
+    room.peers[index] = Some(peer);
+    peer_rooms.insert(peer, id);
+
+    Ok(outbox)
+  }
+
+  fn start(&mut self, peer: u32) -> Result<Outbox, RejectionReason> {
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    let mut board_seed = self.rng.u64(..);
+    let mut game_seed = self.rng.u64(..);
+
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    if room.host != Some(peer) {
+      return Err(RejectionReason::NOT_HOST)
+    }
+
+    if room.game.is_some() {
+      return Err(RejectionReason::GAME_STARTED)
+    }
+
+    // Seats left by players must be taken again, before game starts
+    if room.peers.len() < MIN_PLAYERS || room.vacant_seat().is_some() {
+      return Err(RejectionReason::NOT_ENOUGH_PLAYERS)
+    }
+
+    // Every player gets secret token of its seat, so nobody else can take seat after disconnect
+    let mut tokens = Vec::new();
+    for seat in 0..room.peers.len() {
+      if room.bots[seat].is_some() || room.users[seat].is_some() {
+        continue
+      }
+
+      let mut token = [0; TOKEN_SIZE];
+      getrandom::getrandom(&mut token).map_err(|err| {
+        error!("Generate reconnect token error: {err}");
+        RejectionReason::SERVER_ERROR
+      })?;
+      tokens.push((seat, token));
+    }
+
+    // Seeds of verifiable game are derived from committed secret and entropy of players
+    let proof = room.proof.take().map(|mut proof| {
+      proof.seal(room.peers.len());
+      (board_seed, game_seed) = proof.seeds();
+      proof
+    });
+
+    // Custom map is used first, then fixed map of scenario, otherwise board is generated
+    let board = room.map.map(|map| map.board(board_seed))
+      .or_else(|| room.scenario.board())
+      .unwrap_or_else(|| generate_board(id, room.peers.len(), board_seed));
+
+    let game = Game::new(Setup {
+      scenario: room.scenario,
+      rules: room.rules,
+      board,
+      players: room.peers.len(),
+      seed: game_seed,
+      proof
+    });
+
+    let mut outbox = Vec::new();
+    room.broadcast(&mut outbox, Outgoing::game_started(game_started_to(game.setup())));
+    for event in game.start_events() {
+      room.notify(&mut outbox, &event);
+    }
+
+    for (seat, token) in tokens {
+      room.tokens[seat] = Some(token_hash(&token));
+      if let Some(peer) = room.peers[seat] {
+        let token = ReconnectToken { token: token.to_vec() };
+        outbox.push((vec![peer], Outgoing::reconnect_token(token)));
+      }
+    }
+
+    let (setup, seats) = (encode_setup(game.setup()), encode_owners(&room.owners()));
+    self.last_game += 1;
+    room.record = self.last_game;
+    record(&self.recorder, Record::Started { id: room.record, room: id, setup, seats });
+
+    room.game = Some(game);
+
+    Ok(outbox)
+  }
+
+  fn act(&mut self, peer: u32, message: &Incoming) -> Result<Outbox, RejectionReason> {
+    let action = action_from(message).ok_or(RejectionReason::MALFORMED)?;
+
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    let game = room.game.as_mut().ok_or(RejectionReason::GAME_NOT_STARTED)?;
+
+    // SAFETY: peer room id is set only after peer added to room
+    let seat = unsafe {
+      room.peers.iter().position(|p| *p == Some(peer)).unwrap_unchecked()
+    };
+
+    let events = game.apply(seat, action).map_err(rejection_to)?;
+
+    // Seats count never exceeds maximum players count, so it fits u8
+    let seat_number = u8::try_from(seat).unwrap_or(u8::MAX);
+    record(&self.recorder, Record::Applied {
+      id: room.record, seat: seat_number, action: encode_action(message.clone())
+    });
+    let finished = game.is_finished();
+
+    let mut outbox = Vec::with_capacity(events.len());
+    for event in &events {
+      room.notify(&mut outbox, event);
+    }
+
+    if finished {
+      room.finish(&mut outbox, &self.recorder);
+    }
+
+    Ok(outbox)
+  }
+
+  // Undo is requested without vote, other players approve or reject it with votes
+  fn undo(&mut self, peer: u32, vote: Option<bool>) -> Result<Outbox, RejectionReason> {
+    let id = *self.peer_rooms.get(&peer).ok_or(RejectionReason::NOT_IN_GAME)?;
+    // SAFETY: room removed from lobby only with all its peers
+    let room = unsafe { self.rooms.get_mut(&id).unwrap_unchecked() };
+
+    let game = room.game.as_mut().ok_or(RejectionReason::GAME_NOT_STARTED)?;
+
+    // SAFETY: peer room id is set only after peer added to room
+    let seat = unsafe {
+      room.peers.iter().position(|p| *p == Some(peer)).unwrap_unchecked()
+    };
+
+    let (events, undone) = match vote {
+      None => (game.request_undo(seat).map_err(rejection_to)?, false),
+      Some(approve) => game.vote_undo(seat, approve).map_err(rejection_to)?
+    };
+
+    let mut outbox = Vec::new();
+    for event in &events {
+      room.notify(&mut outbox, event);
+    }
+
+    room.finish_undo(&mut outbox, &self.recorder, undone);
+
+    Ok(outbox)
+  }
+
+  fn generate_id(&self) -> u32 {
+    loop {
+      let random = self.rng.u32(..);
+      if self.rooms.contains_key(&random) {
+        continue
+      }
+      return random
+    }
+  }
+}
+
+// Game from start for player, client rebuilds its state from game started message and events
+fn send_game(outbox: &mut Outbox, peer: u32, seat: usize, game: &Game, history: &[Event]) {
+  outbox.push((vec![peer], Outgoing::game_started(game_started_to(game.setup()))));
+  for event in history {
+    outbox.push((vec![peer], event_to(&event.project(seat))));
+  }
+}
+
+fn token_hash(token: &[u8]) -> [u8; TOKEN_SIZE] {
+  Sha256::digest(token).into()
+}
+
+fn milliseconds(duration: Duration) -> u32 {
+  u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
+
+// Games log is written in background, lost recorder only stops persistence
+fn record(recorder: &RecordSender, record: Record) {
+  if recorder.send(record).is_err() {
+    error!("Send game record error: recorder stopped");
+  }
+}
+
+fn generate_board(id: u32, players: usize, seed: u64) -> Board {
+  let extension = players >= EXTENSION_PLAYERS;
+  let template = if extension { Template::extension() } else { Template::standard() };
+  let constraints = Constraints { separate_red_tokens: true, ..Constraints::default() };
+
+  match Generator::new(template.clone(), constraints).generate(seed) {
+    Ok((board, report)) => {
+      info!("Game {id} board generated: {report:?}");
+      board
+    },
+    Err(err) => {
+      error!("Game {id} board generation error: {err}");
+      // Unconstrained generation fails only on template mismatch
+      Generator::new(template, Constraints::default()).generate(seed)
+        .map_or_else(|_| Board::standard(), |(board, _)| board)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tokio::sync::mpsc::unbounded_channel;
+  use crate::protos::game::{ AddBot, CreateGame, InviteBot, JoinGame, StartGame };
+  use super::*;
+
+  fn restored(owners: Vec<Owner>) -> Restored {
+    let players = owners.len();
+    let game = Game::new(Setup {
+      scenario: Scenario::Base,
+      rules: Rules::new(Scenario::Base),
+      board: Board::standard(),
+      players,
+      seed: 0,
+      proof: None
+    });
+    Restored { id: 1, room: 5, actions: 0, game, owners }
+  }
+
+  fn lobby() -> Lobby {
+    let (recorder, _) = unbounded_channel();
+    Lobby::new(recorder, Vec::new(), 0, Timers { turn: None, decision: None })
+  }
+
+  fn create(lobby: &mut Lobby, peer: u32) -> u32 {
+    let outbox = lobby.handle(peer, Incoming::create_game(CreateGame::default()));
+    joined(&outbox).map(|(id, _)| id).unwrap()
+  }
+
+  fn join(lobby: &mut Lobby, peer: u32, game_id: u32) -> Outbox {
+    rejoin(lobby, peer, game_id, Vec::new())
+  }
+
+  fn rejoin(lobby: &mut Lobby, peer: u32, game_id: u32, token: Vec<u8>) -> Outbox {
+    lobby.handle(peer, Incoming::join_game(JoinGame { game_id, token }))
+  }
+
+  fn add_bot(lobby: &mut Lobby, peer: u32) -> Outbox {
+    lobby.handle(peer, Incoming::add_bot(AddBot::default()))
+  }
+
+  fn start(lobby: &mut Lobby, peer: u32) -> Outbox {
+    lobby.handle(peer, Incoming::start_game(StartGame { }))
+  }
+
+  // Game id and seat of joined peer
+  fn joined(outbox: &Outbox) -> Option<(u32, u32)> {
+    outbox.iter().find_map(|(_, message)| match message {
+      Outgoing::game_joined(joined) => Some((joined.game_id, joined.seat)),
+      _ => None
+    })
+  }
+
+  // Reconnect token sent to peer
+  fn token(outbox: &Outbox, peer: u32) -> Option<Vec<u8>> {
+    outbox.iter().find_map(|(peers, message)| match message {
+      Outgoing::reconnect_token(token) if *peers == [peer] => Some(token.token.clone()),
+      _ => None
+    })
+  }
+
+  fn rejected(outbox: &Outbox, reason: RejectionReason) -> bool {
+    outbox.iter().any(|(_, message)| *message == rejection(reason))
+  }
+
+  #[test]
+  fn room_left_by_everyone_is_removed() {
+    let mut lobby = lobby();
+    create(&mut lobby, 1);
+
+    assert!(lobby.disconnect(1).is_empty());
+    assert!(lobby.rooms.is_empty());
+    assert!(lobby.peer_rooms.is_empty());
+  }
+
+  #[test]
+  fn left_seat_is_taken_by_next_player() {
+    let mut lobby = lobby();
+    let id = create(&mut lobby, 1);
+    join(&mut lobby, 2, id);
+    join(&mut lobby, 3, id);
+
+    let outbox = lobby.disconnect(2);
+    let left = Outgoing::player_left(PlayerLeft { seat: 1 });
+    assert_eq!(outbox, vec![(vec![1, 3], left)]);
+    assert!(rejected(&start(&mut lobby, 1), RejectionReason::NOT_ENOUGH_PLAYERS));
+
+    // Peer id of closed connection may be given to other client, which is new player
+    assert_eq!(joined(&join(&mut lobby, 2, id)), Some((id, 1)));
+    assert!(!rejected(&start(&mut lobby, 1), RejectionReason::NOT_ENOUGH_PLAYERS));
+  }
+
+  #[test]
+  fn host_passes_to_first_connected_player() {
+    let mut lobby = lobby();
+    let id = create(&mut lobby, 1);
+    join(&mut lobby, 2, id);
+    join(&mut lobby, 3, id);
+
+    lobby.disconnect(1);
+    assert!(rejected(&add_bot(&mut lobby, 3), RejectionReason::NOT_HOST));
+    assert!(!rejected(&add_bot(&mut lobby, 2), RejectionReason::NOT_HOST));
+  }
+
+  #[test]
+  fn started_game_is_kept_for_rejoin() {
+    let mut lobby = lobby();
+    let id = create(&mut lobby, 1);
+    add_bot(&mut lobby, 1);
+    add_bot(&mut lobby, 1);
+    let token = token(&start(&mut lobby, 1), 1).unwrap();
+
+    lobby.disconnect(1);
+    assert!(lobby.rooms.contains_key(&id));
+    assert_eq!(joined(&rejoin(&mut lobby, 4, id, token)), Some((id, 0)));
+    assert!(rejected(&join(&mut lobby, 5, id), RejectionReason::GAME_STARTED));
+  }
+
+  #[test]
+  fn player_seat_is_taken_only_with_its_token() {
+    let mut lobby = lobby();
+    let id = create(&mut lobby, 1);
+    join(&mut lobby, 2, id);
+    add_bot(&mut lobby, 1);
+    let outbox = start(&mut lobby, 1);
+    let (first, second) = (token(&outbox, 1).unwrap(), token(&outbox, 2).unwrap());
+    assert_ne!(first, second);
+
+    lobby.disconnect(1);
+    assert!(rejected(&join(&mut lobby, 4, id), RejectionReason::GAME_STARTED));
+    let wrong = vec![0; TOKEN_SIZE];
+    assert!(rejected(&rejoin(&mut lobby, 4, id, wrong), RejectionReason::GAME_STARTED));
+
+    // Token moves seat to new connection, even if previous one is not closed yet
+    assert_eq!(joined(&rejoin(&mut lobby, 5, id, second)), Some((id, 1)));
+    assert!(!lobby.peer_rooms.contains_key(&2));
+    assert_eq!(joined(&rejoin(&mut lobby, 4, id, first)), Some((id, 0)));
+  }
+
+  #[test]
+  fn started_games_are_numbered_after_last_recorded_one() {
+    let (recorder, mut records) = unbounded_channel();
+    let mut lobby = Lobby::new(recorder, Vec::new(), 7, Timers { turn: None, decision: None });
+    for host in [1, 2] {
+      create(&mut lobby, host);
+      add_bot(&mut lobby, host);
+      add_bot(&mut lobby, host);
+      start(&mut lobby, host);
+    }
+
+    let ids = std::iter::from_fn(|| records.try_recv().ok())
+      .filter_map(|record| if let Record::Started { id, .. } = record { Some(id) } else { None })
+      .collect::<Vec<_>>();
+    assert_eq!(ids, vec![8, 9]);
+  }
+
+  #[test]
+  fn restored_seats_are_kept_for_owners() {
+    let hash = token_hash(b"token");
+    let owners = vec![Owner::Player(hash), Owner::Account(7), Owner::Bot(Difficulty::Easy)];
+    let (recorder, _) = unbounded_channel();
+    let timers = Timers { turn: None, decision: None };
+    let mut lobby = Lobby::new(recorder, vec![restored(owners)], 1, timers);
+
+    assert!(rejected(&join(&mut lobby, 1, 5), RejectionReason::GAME_STARTED));
+    assert_eq!(joined(&rejoin(&mut lobby, 1, 5, b"token".to_vec())), Some((5, 0)));
+
+    // Account rejoins without invitation, which is not restored
+    lobby.connect_bot(2, 7);
+    assert_eq!(joined(&join(&mut lobby, 2, 5)), Some((5, 1)));
+    assert_eq!(lobby.rooms[&5].bots, vec![None, None, Some(Difficulty::Easy)]);
+  }
+
+  #[test]
+  fn bot_account_reclaims_its_seat() {
+    let mut lobby = lobby();
+    let id = create(&mut lobby, 1);
+    lobby.connect_bot(10, 7);
+    lobby.handle(1, Incoming::invite_bot(InviteBot { bot_id: 7 }));
+    join(&mut lobby, 10, id);
+    add_bot(&mut lobby, 1);
+    start(&mut lobby, 1);
+
+    // Seat of disconnected account is kept for it, not for other players
+    lobby.disconnect(10);
+    assert!(rejected(&join(&mut lobby, 4, id), RejectionReason::GAME_STARTED));
+
+    lobby.connect_bot(11, 7);
+    assert_eq!(joined(&join(&mut lobby, 11, id)), Some((id, 1)));
+
+    // New connection replaces previous one, even if it is not closed yet
+    lobby.connect_bot(12, 7);
+    assert_eq!(joined(&join(&mut lobby, 12, id)), Some((id, 1)));
+    assert!(!lobby.peer_rooms.contains_key(&11));
+    assert!(lobby.disconnect(11).is_empty());
+  }
+}
\ No newline at end of file
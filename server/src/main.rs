@@ -20,13 +20,18 @@ compile_error!("Features `client_resources_...` cannot be enabled at the same ti
 )))]
 compile_error!("Using one of `db_...` features is required");
 
+mod bots;
 mod communicator;
 mod db;
-mod helpers;
 mod http;
 mod intermedium;
+mod lobby;
+mod maps;
+mod messages;
 mod protos {
   // Disable lints for automatically generated files
+  #![allow(non_camel_case_types)]
+  #![allow(non_snake_case)]
   #![allow(unused_imports)]
   #![allow(clippy::bool_comparison)]
   #![allow(clippy::cast_lossless)]
@@ -37,6 +42,8 @@ mod protos {
   #![allow(clippy::wildcard_imports)]
   include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/protos/mod.rs"));
 }
+mod recorder;
+mod replay;
 mod settings;
 
 use dotenv::dotenv;
@@ -49,9 +56,12 @@ use std::{ io::Write, time::Duration };
 use tokio::{
   runtime::Builder as RuntimeBuilder, signal::ctrl_c, sync::oneshot::channel, join, spawn
 };
+use settlers_server::{ game, helpers };
 use crate::{
-  communicator::Communicator, db::Migrator, helpers::exit_with_error,
-  http::start, intermedium::Intermedium, settings::SETTINGS
+  bots::create as create_bot, communicator::Communicator, db::Migrator, helpers::exit_with_error,
+  http::start, intermedium::Intermedium, lobby::Timers, maps::MAPS,
+  recorder::{ Recorder, last_game_id, restore },
+  settings::SETTINGS
 };
 
 fn main() {
@@ -85,6 +95,9 @@ fn main() {
 
   env_logger_builder.init();
 
+  // Custom maps are validated before server start, so broken map is found at once
+  initialize(&MAPS);
+
   let runtime = RuntimeBuilder::new_multi_thread().enable_io().enable_time().build()
     .unwrap_or_else(|err| {
       exit_with_error(&format!("Create tokio runtime error: {err}"))
@@ -112,17 +125,53 @@ fn main() {
       exit_with_error(&format!("Database migration error: {err}"))
     }
 
+    // Command "create-bot <name>" creates bot account and prints its token instead of server start
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("create-bot") {
+      let name = args.next().unwrap_or_default();
+      match create_bot(&db, &name).await {
+        Ok(token) => println!("{token}"),
+        Err(err) => exit_with_error(&format!("Create bot account error: {err}"))
+      }
+      return
+    }
+
+    // Games unfinished before server stop are rebuilt from their logs
+    let restored = restore(&db).await.unwrap_or_else(|err| {
+      exit_with_error(&format!("Restore games error: {err}"))
+    });
+    let last_game = last_game_id(&db).await.unwrap_or_else(|err| {
+      exit_with_error(&format!("Find last game error: {err}"))
+    });
+    let (mut recorder, record_sender) = Recorder::new(db.clone(), &restored);
+
     let (intermedium_stop_sender, intermedium_stop_receiver) = channel::<()>();
     let (http_stop_sender, http_stop_receiver) = channel::<()>();
 
     let (communicator, receiver) = Communicator::new();
-    let mut intermedium = Intermedium::new(communicator.clone(), receiver);
+    // Zero seconds disables timer
+    let timer = |seconds: Option<u64>| {
+      seconds.filter(|seconds| *seconds > 0).map(Duration::from_secs)
+    };
+    let timers = Timers {
+      turn: timer(SETTINGS.timers.turn),
+      decision: timer(SETTINGS.timers.decision)
+    };
+
+    let mut intermedium = Intermedium::new(
+      communicator.clone(), receiver, record_sender, restored, last_game, timers
+    );
+
+    // Recorder stops after intermedium is dropped with its lobby, so it writes all records
+    let recorder_handle = spawn(async move {
+      recorder.run().await;
+    });
 
     let intermedium_handle = spawn(async move {
       intermedium.run(intermedium_stop_receiver).await;
     });
 
-    let http_handle = spawn(start(communicator, http_stop_receiver));
+    let http_handle = spawn(start(communicator, db, http_stop_receiver));
 
     let stop_handle = spawn(async move {
       if let Err(err) = ctrl_c().await {
@@ -140,13 +189,16 @@ fn main() {
       }
     });
 
-    let (intermedium_join_result, http_join_result, stop_join_result) = join!(
-      intermedium_handle, http_handle, stop_handle
+    let (intermedium_join_result, recorder_join_result, http_join_result, stop_join_result) = join!(
+      intermedium_handle, recorder_handle, http_handle, stop_handle
     );
 
     if let Err(err) = intermedium_join_result {
       error!("Join intermedium task error: {err}");
     }
+    if let Err(err) = recorder_join_result {
+      error!("Join recorder task error: {err}");
+    }
     if let Err(err) = http_join_result {
       error!("Join http task error: {err}");
     }
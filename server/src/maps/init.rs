@@ -0,0 +1,158 @@
+use log::{ debug, info };
+use serde_path_to_error::{ deserialize, Error as PathError };
+use std::{ collections::BTreeMap, fs::{ read_dir, read_to_string }, path::Path };
+use crate::{
+  game::{
+    board::{ Direction, Edge, HarborKind, Hex, Terrain },
+    map::{ Map, MapHarbor, MapTile },
+    resources::Resource
+  },
+  helpers::exit_with_error,
+  settings::SETTINGS
+};
+use super::structs::{ DirectionName, HarborName, HexCoordinates, MapFile, TerrainName };
+
+const fn hex_from([q, r]: HexCoordinates) -> Hex {
+  Hex::new(q, r)
+}
+
+// None for random terrain
+const fn terrain_from(terrain: TerrainName) -> Option<Terrain> {
+  Some(match terrain {
+    TerrainName::Hills => Terrain::Hills,
+    TerrainName::Forest => Terrain::Forest,
+    TerrainName::Mountains => Terrain::Mountains,
+    TerrainName::Fields => Terrain::Fields,
+    TerrainName::Pasture => Terrain::Pasture,
+    TerrainName::Desert => Terrain::Desert,
+    TerrainName::Sea => Terrain::Sea,
+    TerrainName::Gold => Terrain::Gold,
+    TerrainName::Random => return None
+  })
+}
+
+const fn direction_from(direction: DirectionName) -> Direction {
+  match direction {
+    DirectionName::NorthEast => Direction::NorthEast,
+    DirectionName::East => Direction::East,
+    DirectionName::SouthEast => Direction::SouthEast,
+    DirectionName::SouthWest => Direction::SouthWest,
+    DirectionName::West => Direction::West,
+    DirectionName::NorthWest => Direction::NorthWest
+  }
+}
+
+// None for random harbor
+const fn harbor_from(kind: HarborName) -> Option<HarborKind> {
+  Some(match kind {
+    HarborName::Generic => HarborKind::Generic,
+    HarborName::Brick => HarborKind::Specific(Resource::Brick),
+    HarborName::Lumber => HarborKind::Specific(Resource::Lumber),
+    HarborName::Ore => HarborKind::Specific(Resource::Ore),
+    HarborName::Grain => HarborKind::Specific(Resource::Grain),
+    HarborName::Wool => HarborKind::Specific(Resource::Wool),
+    HarborName::Random => return None
+  })
+}
+
+// Pools hold values to draw, so random value in pool is error with its path
+fn map_from(file: MapFile) -> Result<Map, (String, String)> {
+  let random_error = |pool: &str, index: usize| {
+    (format!("{pool}[{index}]"), "random value is not allowed in pool".to_string())
+  };
+
+  let terrain_pool = file.terrain_pool.into_iter().enumerate()
+    .map(|(index, terrain)| {
+      terrain_from(terrain).ok_or_else(|| random_error("terrain_pool", index))
+    })
+    .collect::<Result<Vec<Terrain>, _>>()?;
+
+  let harbor_pool = file.harbor_pool.into_iter().enumerate()
+    .map(|(index, kind)| harbor_from(kind).ok_or_else(|| random_error("harbor_pool", index)))
+    .collect::<Result<Vec<HarborKind>, _>>()?;
+
+  let map = Map {
+    tiles: file.tiles.into_iter()
+      .map(|tile| MapTile {
+        hex: hex_from(tile.hex),
+        terrain: terrain_from(tile.terrain),
+        token: tile.token
+      })
+      .collect(),
+    terrain_pool,
+    token_pool: file.token_pool,
+    harbors: file.harbors.into_iter()
+      .map(|harbor| MapHarbor {
+        edge: Edge::new(hex_from(harbor.hex), direction_from(harbor.direction)),
+        kind: harbor_from(harbor.kind)
+      })
+      .collect(),
+    harbor_pool,
+    robber: file.robber.map(hex_from)
+  };
+
+  map.validate().map_err(|err| (err.path(), err.to_string()))?;
+
+  Ok(map)
+}
+
+fn path_error<E: ToString>(err: &PathError<E>) -> (String, String) {
+  (err.path().to_string(), err.inner().to_string())
+}
+
+fn parse(path: &Path, extension: &str) -> Result<Map, (String, String)> {
+  let content = read_to_string(path).map_err(|err| (".".to_string(), err.to_string()))?;
+
+  let file: MapFile = if extension == "json" {
+    let mut deserializer = serde_json::Deserializer::from_str(&content);
+    deserialize(&mut deserializer).map_err(|err| path_error(&err))?
+  } else {
+    let mut deserializer = toml::Deserializer::new(&content);
+    deserialize(&mut deserializer).map_err(|err| path_error(&err))?
+  };
+
+  map_from(file)
+}
+
+pub fn init() -> BTreeMap<String, Map> {
+  let mut maps = BTreeMap::new();
+
+  let Some(directory) = SETTINGS.maps_path.as_ref() else {
+    debug!("Maps directory not set, custom maps disabled");
+    return maps
+  };
+
+  let entries = read_dir(directory).unwrap_or_else(|err| {
+    exit_with_error(&format!("Read maps directory \"{directory}\" error: {err}"))
+  });
+
+  for entry in entries {
+    let path = entry.unwrap_or_else(|err| {
+      exit_with_error(&format!("Read maps directory \"{directory}\" entry error: {err}"))
+    }).path();
+
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+    if !path.is_file() || !matches!(extension, "toml" | "json") {
+      debug!("Maps directory entry \"{}\" skipped", path.display());
+      continue
+    }
+
+    let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).map(String::from) else {
+      exit_with_error(&format!("Convert map path \"{}\" to str error", path.display()))
+    };
+
+    let map = parse(&path, extension).unwrap_or_else(|(key, err)| {
+      let key_part = if key == "." { String::new() } else { format!(" key \"{key}\"") };
+      exit_with_error(&format!("Map \"{}\"{key_part} error: {err}", path.display()))
+    });
+
+    if maps.contains_key(&id) {
+      exit_with_error(&format!("Map \"{}\" error: map \"{id}\" already loaded", path.display()))
+    }
+
+    info!("Map \"{id}\" loaded from \"{}\"", path.display());
+    maps.insert(id, map);
+  }
+
+  maps
+}
\ No newline at end of file
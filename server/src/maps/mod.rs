@@ -0,0 +1,12 @@
+mod init;
+mod structs;
+
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use crate::game::map::Map;
+use self::init::init;
+
+lazy_static! {
+  // Custom maps from maps directory, file name without extension is map id
+  pub static ref MAPS: BTreeMap<String, Map> = init();
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+// Hex in axial coordinates as [q, r]
+pub type HexCoordinates = [i8; 2];
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerrainName {
+  Hills,
+  Forest,
+  Mountains,
+  Fields,
+  Pasture,
+  Desert,
+  Sea,
+  Gold,
+  // Terrain is drawn from terrain pool
+  Random
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectionName {
+  NorthEast,
+  East,
+  SouthEast,
+  SouthWest,
+  West,
+  NorthWest
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HarborName {
+  Generic,
+  Brick,
+  Lumber,
+  Ore,
+  Grain,
+  Wool,
+  // Harbor kind is drawn from harbor pool
+  Random
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapTile {
+  pub hex: HexCoordinates,
+  pub terrain: TerrainName,
+  // Producing tile without token gets it from token pool
+  pub token: Option<u8>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapHarbor {
+  // Land hex and its side facing the sea
+  pub hex: HexCoordinates,
+  pub direction: DirectionName,
+  pub kind: HarborName
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapFile {
+  pub tiles: Vec<MapTile>,
+  #[serde(default)]
+  pub terrain_pool: Vec<TerrainName>,
+  #[serde(default)]
+  pub token_pool: Vec<u8>,
+  #[serde(default)]
+  pub harbors: Vec<MapHarbor>,
+  #[serde(default)]
+  pub harbor_pool: Vec<HarborName>,
+  pub robber: Option<HexCoordinates>
+}
\ No newline at end of file
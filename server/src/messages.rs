@@ -0,0 +1,1206 @@
+use log::debug;
+use quick_protobuf::{ BytesReader, MessageRead, MessageWrite, Writer };
+use crate::{
+  game::{
+    MAX_PLAYERS,
+    Action, BuildingKind, Difficulty, Event, Observation, Phase, Rejection, Score, Setup, Target,
+    board::{ Board, Corner, Edge, Harbor, HarborKind, Hex, Side, Terrain, Tile, Vertex },
+    cards::{ DevelopmentCard, ProgressCard, Track },
+    dice::Dice,
+    fairness::Proof,
+    scenario::Scenario,
+    resources::{ Resource, Resources },
+    rules::Rules
+  },
+  lobby::Owner,
+  protos::game::{
+    self as proto,
+    mod_ClientMessage::OneOfmessage as Incoming, mod_PlayProgressCard::OneOftarget,
+    mod_SeatOwner::OneOfowner, mod_ServerMessage::OneOfmessage as Outgoing,
+    ClientMessage, GameSeats, GameSetup, GameStarted, RejectionReason, SeatOwner, ServerMessage
+  }
+};
+
+pub fn decode(data: &[u8]) -> Option<Incoming> {
+  let mut reader = BytesReader::from_bytes(data);
+  match ClientMessage::from_reader(&mut reader, data) {
+    Ok(message) => Some(message.message),
+    Err(err) => {
+      debug!("Read client message error: {err}");
+      None
+    }
+  }
+}
+
+fn write<M: MessageWrite>(message: &M) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(message.get_size());
+
+  let write_result = message.write_message(&mut Writer::new(&mut bytes));
+  // SAFETY: Vec writer backend grows on demand and never returns errors
+  unsafe { write_result.unwrap_unchecked(); };
+
+  bytes
+}
+
+pub fn encode(message: Outgoing) -> Vec<u8> {
+  write(&ServerMessage { message })
+}
+
+// Actions are stored as client messages, so they are decoded by same code as received ones
+pub fn encode_action(message: Incoming) -> Vec<u8> {
+  write(&ClientMessage { message })
+}
+
+pub fn encode_setup(setup: &Setup) -> Vec<u8> {
+  write(&setup_to(setup))
+}
+
+pub fn decode_setup(data: &[u8]) -> Option<Setup> {
+  let mut reader = BytesReader::from_bytes(data);
+  match GameSetup::from_reader(&mut reader, data) {
+    Ok(setup) => setup_from(&setup),
+    Err(err) => {
+      debug!("Read game setup error: {err}");
+      None
+    }
+  }
+}
+
+pub fn encode_owners(owners: &[Owner]) -> Vec<u8> {
+  write(&GameSeats {
+    seats: owners.iter().map(|owner| SeatOwner { owner: owner_to(*owner) }).collect()
+  })
+}
+
+pub fn decode_owners(data: &[u8]) -> Option<Vec<Owner>> {
+  let mut reader = BytesReader::from_bytes(data);
+  match GameSeats::from_reader(&mut reader, data) {
+    Ok(seats) => seats.seats.iter().map(|seat| owner_from(&seat.owner)).collect(),
+    Err(err) => {
+      debug!("Read game seats error: {err}");
+      None
+    }
+  }
+}
+
+fn owner_to(owner: Owner) -> OneOfowner {
+  match owner {
+    Owner::Player(hash) => OneOfowner::token_hash(hash.to_vec()),
+    Owner::Account(user) => OneOfowner::user(user),
+    Owner::Bot(difficulty) => OneOfowner::bot(difficulty_to(difficulty))
+  }
+}
+
+fn owner_from(owner: &OneOfowner) -> Option<Owner> {
+  match owner {
+    OneOfowner::token_hash(hash) => Some(Owner::Player(hash.as_slice().try_into().ok()?)),
+    OneOfowner::user(user) => Some(Owner::Account(*user)),
+    OneOfowner::bot(difficulty) => Some(Owner::Bot(difficulty_from(*difficulty))),
+    OneOfowner::None => None
+  }
+}
+
+pub fn setup_to(setup: &Setup) -> GameSetup {
+  GameSetup {
+    scenario: scenario_to(setup.scenario),
+    rules: Some(rules_to(&setup.rules)),
+    board: Some(board_to(&setup.board)),
+    players: seat_to(setup.players),
+    seed: setup.seed,
+    secret: setup.proof.as_ref().map(|proof| proof.secret.to_vec()).unwrap_or_default(),
+    contributions: setup.proof.as_ref()
+      .map(|proof| proof.contributions.clone())
+      .unwrap_or_default()
+  }
+}
+
+pub fn setup_from(setup: &GameSetup) -> Option<Setup> {
+  let scenario = scenario_from(setup.scenario);
+  Some(Setup {
+    scenario,
+    rules: rules_from(scenario, setup.rules.as_ref())?,
+    board: board_from(setup.board.as_ref()?)?,
+    players: usize::try_from(setup.players).ok()?,
+    seed: setup.seed,
+    proof: if setup.secret.is_empty() {
+      None
+    } else {
+      Some(Proof {
+        secret: setup.secret.as_slice().try_into().ok()?,
+        contributions: setup.contributions.clone()
+      })
+    }
+  })
+}
+
+// Game start data, which players get before start events
+pub fn game_started_to(setup: &Setup) -> GameStarted {
+  GameStarted {
+    board: Some(board_to(&setup.board)),
+    players: seat_to(setup.players),
+    scenario: scenario_to(setup.scenario),
+    rules: Some(rules_to(&setup.rules))
+  }
+}
+
+// Seats count never exceeds maximum players count, so truncation is impossible
+#[allow(clippy::cast_possible_truncation)]
+pub const fn seat_to(seat: usize) -> u32 {
+  seat as u32
+}
+
+fn hex_from(hex: Option<&proto::Hex>) -> Option<Hex> {
+  let hex = hex?;
+  Some(Hex::new(i8::try_from(hex.q).ok()?, i8::try_from(hex.r).ok()?))
+}
+
+fn vertex_from(vertex: Option<&proto::Vertex>) -> Option<Vertex> {
+  let vertex = vertex?;
+  let corner = match vertex.corner {
+    proto::Corner::NORTH => Corner::North,
+    proto::Corner::SOUTH => Corner::South
+  };
+  Some(Vertex::new(hex_from(vertex.hex.as_ref())?, corner))
+}
+
+fn edge_from(edge: Option<&proto::Edge>) -> Option<Edge> {
+  let edge = edge?;
+  let side = match edge.side {
+    proto::Side::NORTH_EAST => Side::NorthEast,
+    proto::Side::EAST => Side::East,
+    proto::Side::SOUTH_EAST => Side::SouthEast
+  };
+  Some(Edge { hex: hex_from(edge.hex.as_ref())?, side })
+}
+
+fn hex_to(hex: Hex) -> proto::Hex {
+  proto::Hex { q: i32::from(hex.q), r: i32::from(hex.r) }
+}
+
+fn vertex_to(vertex: Vertex) -> proto::Vertex {
+  let corner = match vertex.corner {
+    Corner::North => proto::Corner::NORTH,
+    Corner::South => proto::Corner::SOUTH
+  };
+  proto::Vertex { hex: Some(hex_to(vertex.hex)), corner }
+}
+
+fn edge_to(edge: Edge) -> proto::Edge {
+  let side = match edge.side {
+    Side::NorthEast => proto::Side::NORTH_EAST,
+    Side::East => proto::Side::EAST,
+    Side::SouthEast => proto::Side::SOUTH_EAST
+  };
+  proto::Edge { hex: Some(hex_to(edge.hex)), side }
+}
+
+const fn resource_to(resource: Resource) -> proto::Resource {
+  match resource {
+    Resource::Brick => proto::Resource::BRICK,
+    Resource::Lumber => proto::Resource::LUMBER,
+    Resource::Ore => proto::Resource::ORE,
+    Resource::Grain => proto::Resource::GRAIN,
+    Resource::Wool => proto::Resource::WOOL,
+    Resource::Paper => proto::Resource::PAPER,
+    Resource::Cloth => proto::Resource::CLOTH,
+    Resource::Coin => proto::Resource::COIN
+  }
+}
+
+const fn resource_from(resource: proto::Resource) -> Resource {
+  match resource {
+    proto::Resource::BRICK => Resource::Brick,
+    proto::Resource::LUMBER => Resource::Lumber,
+    proto::Resource::ORE => Resource::Ore,
+    proto::Resource::GRAIN => Resource::Grain,
+    proto::Resource::WOOL => Resource::Wool,
+    proto::Resource::PAPER => Resource::Paper,
+    proto::Resource::CLOTH => Resource::Cloth,
+    proto::Resource::COIN => Resource::Coin
+  }
+}
+
+fn resources_from(resources: Option<&proto::Resources>) -> Option<Resources> {
+  let resources = resources?;
+  let mut result = Resources::new(
+    resources.brick, resources.lumber, resources.ore, resources.grain, resources.wool
+  );
+  result += Resources::commodities(resources.paper, resources.cloth, resources.coin);
+  Some(result)
+}
+
+const fn resources_to(resources: &Resources) -> proto::Resources {
+  proto::Resources {
+    brick: resources.get(Resource::Brick),
+    lumber: resources.get(Resource::Lumber),
+    ore: resources.get(Resource::Ore),
+    grain: resources.get(Resource::Grain),
+    wool: resources.get(Resource::Wool),
+    paper: resources.get(Resource::Paper),
+    cloth: resources.get(Resource::Cloth),
+    coin: resources.get(Resource::Coin)
+  }
+}
+
+const fn card_to(card: DevelopmentCard) -> proto::DevelopmentCard {
+  match card {
+    DevelopmentCard::Knight => proto::DevelopmentCard::KNIGHT,
+    DevelopmentCard::RoadBuilding => proto::DevelopmentCard::ROAD_BUILDING_CARD,
+    DevelopmentCard::YearOfPlenty => proto::DevelopmentCard::YEAR_OF_PLENTY,
+    DevelopmentCard::Monopoly => proto::DevelopmentCard::MONOPOLY,
+    DevelopmentCard::VictoryPoint => proto::DevelopmentCard::VICTORY_POINT
+  }
+}
+
+const fn track_to(track: Track) -> proto::Track {
+  match track {
+    Track::Trade => proto::Track::TRADE,
+    Track::Politics => proto::Track::POLITICS,
+    Track::Science => proto::Track::SCIENCE
+  }
+}
+
+const fn track_from(track: proto::Track) -> Track {
+  match track {
+    proto::Track::TRADE => Track::Trade,
+    proto::Track::POLITICS => Track::Politics,
+    proto::Track::SCIENCE => Track::Science
+  }
+}
+
+const fn progress_card_to(card: ProgressCard) -> proto::ProgressCard {
+  match card {
+    ProgressCard::Alchemist => proto::ProgressCard::ALCHEMIST,
+    ProgressCard::Crane => proto::ProgressCard::CRANE,
+    ProgressCard::Engineer => proto::ProgressCard::ENGINEER,
+    ProgressCard::Inventor => proto::ProgressCard::INVENTOR,
+    ProgressCard::Irrigation => proto::ProgressCard::IRRIGATION,
+    ProgressCard::Medicine => proto::ProgressCard::MEDICINE,
+    ProgressCard::Mining => proto::ProgressCard::MINING,
+    ProgressCard::Printer => proto::ProgressCard::PRINTER,
+    ProgressCard::RoadBuilding => proto::ProgressCard::ROAD_BUILDING_PROGRESS,
+    ProgressCard::Smith => proto::ProgressCard::SMITH,
+    ProgressCard::CommercialHarbor => proto::ProgressCard::COMMERCIAL_HARBOR,
+    ProgressCard::Merchant => proto::ProgressCard::MERCHANT,
+    ProgressCard::MerchantFleet => proto::ProgressCard::MERCHANT_FLEET,
+    ProgressCard::MasterMerchant => proto::ProgressCard::MASTER_MERCHANT,
+    ProgressCard::ResourceMonopoly => proto::ProgressCard::RESOURCE_MONOPOLY,
+    ProgressCard::TradeMonopoly => proto::ProgressCard::TRADE_MONOPOLY,
+    ProgressCard::Bishop => proto::ProgressCard::BISHOP,
+    ProgressCard::Constitution => proto::ProgressCard::CONSTITUTION,
+    ProgressCard::Intrigue => proto::ProgressCard::INTRIGUE,
+    ProgressCard::Deserter => proto::ProgressCard::DESERTER,
+    ProgressCard::Diplomat => proto::ProgressCard::DIPLOMAT,
+    ProgressCard::Saboteur => proto::ProgressCard::SABOTEUR,
+    ProgressCard::Spy => proto::ProgressCard::SPY,
+    ProgressCard::Warlord => proto::ProgressCard::WARLORD,
+    ProgressCard::Wedding => proto::ProgressCard::WEDDING
+  }
+}
+
+const fn progress_card_from(card: proto::ProgressCard) -> ProgressCard {
+  match card {
+    proto::ProgressCard::ALCHEMIST => ProgressCard::Alchemist,
+    proto::ProgressCard::CRANE => ProgressCard::Crane,
+    proto::ProgressCard::ENGINEER => ProgressCard::Engineer,
+    proto::ProgressCard::INVENTOR => ProgressCard::Inventor,
+    proto::ProgressCard::IRRIGATION => ProgressCard::Irrigation,
+    proto::ProgressCard::MEDICINE => ProgressCard::Medicine,
+    proto::ProgressCard::MINING => ProgressCard::Mining,
+    proto::ProgressCard::PRINTER => ProgressCard::Printer,
+    proto::ProgressCard::ROAD_BUILDING_PROGRESS => ProgressCard::RoadBuilding,
+    proto::ProgressCard::SMITH => ProgressCard::Smith,
+    proto::ProgressCard::COMMERCIAL_HARBOR => ProgressCard::CommercialHarbor,
+    proto::ProgressCard::MERCHANT => ProgressCard::Merchant,
+    proto::ProgressCard::MERCHANT_FLEET => ProgressCard::MerchantFleet,
+    proto::ProgressCard::MASTER_MERCHANT => ProgressCard::MasterMerchant,
+    proto::ProgressCard::RESOURCE_MONOPOLY => ProgressCard::ResourceMonopoly,
+    proto::ProgressCard::TRADE_MONOPOLY => ProgressCard::TradeMonopoly,
+    proto::ProgressCard::BISHOP => ProgressCard::Bishop,
+    proto::ProgressCard::CONSTITUTION => ProgressCard::Constitution,
+    proto::ProgressCard::INTRIGUE => ProgressCard::Intrigue,
+    proto::ProgressCard::DESERTER => ProgressCard::Deserter,
+    proto::ProgressCard::DIPLOMAT => ProgressCard::Diplomat,
+    proto::ProgressCard::SABOTEUR => ProgressCard::Saboteur,
+    proto::ProgressCard::SPY => ProgressCard::Spy,
+    proto::ProgressCard::WARLORD => ProgressCard::Warlord,
+    proto::ProgressCard::WEDDING => ProgressCard::Wedding
+  }
+}
+
+// None for invalid coordinates, dice out of u8 range or offers to seats beyond players limit,
+// engine validates dice values and seats
+fn target_from(target: &OneOftarget) -> Option<Target> {
+  Some(match target {
+    OneOftarget::dice(dice) => {
+      Target::Dice([u8::try_from(dice.first).ok()?, u8::try_from(dice.second).ok()?])
+    },
+    OneOftarget::hex(hex) => Target::Hex(hex_from(Some(hex))?),
+    OneOftarget::hexes(hexes) => {
+      Target::Hexes(hex_from(hexes.first.as_ref())?, hex_from(hexes.second.as_ref())?)
+    },
+    OneOftarget::vertex(vertex) => Target::Vertex(vertex_from(Some(vertex))?),
+    OneOftarget::resource(resource) => Target::Resource(resource_from(*resource)),
+    OneOftarget::seat(seat) => Target::Seat(usize::try_from(*seat).ok()?),
+    OneOftarget::vertices(vertices) => Target::Vertices(
+      vertex_from(vertices.first.as_ref())?,
+      if vertices.has_second { Some(vertex_from(vertices.second.as_ref())?) } else { None }
+    ),
+    OneOftarget::edges(edges) => Target::Edges(
+      edge_from(edges.first.as_ref())?,
+      if edges.has_second { Some(edge_from(edges.second.as_ref())?) } else { None }
+    ),
+    OneOftarget::seat_vertex(target) => Target::SeatVertex(
+      usize::try_from(target.seat).ok()?,
+      if target.has_vertex { Some(vertex_from(target.vertex.as_ref())?) } else { None }
+    ),
+    OneOftarget::offers(harbor) => {
+      let mut offers = [None; MAX_PLAYERS];
+      for offer in &harbor.offers {
+        let seat = usize::try_from(offer.seat).ok().filter(|seat| *seat < MAX_PLAYERS)?;
+        offers[seat] = Some(resource_from(offer.resource));
+      }
+      Target::Offers(offers)
+    },
+    OneOftarget::None => Target::None
+  })
+}
+
+const fn terrain_to(terrain: Terrain) -> proto::Terrain {
+  match terrain {
+    Terrain::Hills => proto::Terrain::HILLS,
+    Terrain::Forest => proto::Terrain::FOREST,
+    Terrain::Mountains => proto::Terrain::MOUNTAINS,
+    Terrain::Fields => proto::Terrain::FIELDS,
+    Terrain::Pasture => proto::Terrain::PASTURE,
+    Terrain::Desert => proto::Terrain::DESERT,
+    Terrain::Sea => proto::Terrain::SEA,
+    Terrain::Gold => proto::Terrain::GOLD
+  }
+}
+
+const fn terrain_from(terrain: proto::Terrain) -> Terrain {
+  match terrain {
+    proto::Terrain::HILLS => Terrain::Hills,
+    proto::Terrain::FOREST => Terrain::Forest,
+    proto::Terrain::MOUNTAINS => Terrain::Mountains,
+    proto::Terrain::FIELDS => Terrain::Fields,
+    proto::Terrain::PASTURE => Terrain::Pasture,
+    proto::Terrain::DESERT => Terrain::Desert,
+    proto::Terrain::SEA => Terrain::Sea,
+    proto::Terrain::GOLD => Terrain::Gold
+  }
+}
+
+const fn phase_to(phase: Phase) -> proto::Phase {
+  match phase {
+    Phase::Setup { settlement: None, .. } => proto::Phase::SETUP_SETTLEMENT,
+    Phase::Setup { settlement: Some(_), .. } => proto::Phase::SETUP_ROAD,
+    Phase::Roll => proto::Phase::ROLL,
+    Phase::Main => proto::Phase::MAIN,
+    Phase::Discard { .. } => proto::Phase::DISCARD,
+    Phase::Robber => proto::Phase::ROBBER,
+    Phase::RoadBuilding { .. } => proto::Phase::ROAD_BUILDING,
+    Phase::Finished { .. } => proto::Phase::FINISHED,
+    Phase::SpecialBuilding { .. } => proto::Phase::SPECIAL_BUILDING,
+    Phase::Gold => proto::Phase::GOLD_CHOICE,
+    Phase::Gift => proto::Phase::GIFT,
+    Phase::Desertion { .. } => proto::Phase::DESERTION,
+    Phase::Taking { .. } => proto::Phase::TAKING
+  }
+}
+
+const fn score_to(score: &Score) -> proto::Score {
+  proto::Score {
+    settlements: score.settlements,
+    cities: score.cities,
+    longest_road: score.longest_road,
+    largest_army: score.largest_army,
+    victory_cards: score.victory_cards,
+    total: score.total(),
+    island_bonuses: score.island_bonuses,
+    metropolises: score.metropolises,
+    defender_points: score.defender_points,
+    merchant: score.merchant,
+    progress_points: score.progress_points
+  }
+}
+
+pub const fn scenario_from(scenario: proto::Scenario) -> Scenario {
+  match scenario {
+    proto::Scenario::BASE => Scenario::Base,
+    proto::Scenario::NEW_SHORES => Scenario::NewShores,
+    proto::Scenario::CITIES_AND_KNIGHTS => Scenario::CitiesAndKnights
+  }
+}
+
+pub const fn scenario_to(scenario: Scenario) -> proto::Scenario {
+  match scenario {
+    Scenario::Base => proto::Scenario::BASE,
+    Scenario::NewShores => proto::Scenario::NEW_SHORES,
+    Scenario::CitiesAndKnights => proto::Scenario::CITIES_AND_KNIGHTS
+  }
+}
+
+pub const fn difficulty_from(difficulty: proto::BotDifficulty) -> Difficulty {
+  match difficulty {
+    proto::BotDifficulty::EASY => Difficulty::Easy,
+    proto::BotDifficulty::MEDIUM => Difficulty::Medium,
+    proto::BotDifficulty::HARD => Difficulty::Hard
+  }
+}
+
+const fn difficulty_to(difficulty: Difficulty) -> proto::BotDifficulty {
+  match difficulty {
+    Difficulty::Easy => proto::BotDifficulty::EASY,
+    Difficulty::Medium => proto::BotDifficulty::MEDIUM,
+    Difficulty::Hard => proto::BotDifficulty::HARD
+  }
+}
+
+const fn dice_from(dice: proto::DiceMode) -> Dice {
+  match dice {
+    proto::DiceMode::STANDARD => Dice::Standard,
+    proto::DiceMode::BALANCED => Dice::Balanced,
+    proto::DiceMode::KARMA => Dice::Karma
+  }
+}
+
+const fn dice_to(dice: Dice) -> proto::DiceMode {
+  match dice {
+    Dice::Standard => proto::DiceMode::STANDARD,
+    Dice::Balanced => proto::DiceMode::BALANCED,
+    Dice::Karma => proto::DiceMode::KARMA
+  }
+}
+
+// Missing rules are standard rules of scenario, so are unset numeric limits, where 0 has no
+// meaning of its own, None for setup rounds out of range
+pub fn rules_from(scenario: Scenario, rules: Option<&proto::Rules>) -> Option<Rules> {
+  let standard = Rules::new(scenario);
+  let Some(rules) = rules else { return Some(standard) };
+  let or_standard = |value, standard| if value == 0 { standard } else { value };
+
+  Some(Rules {
+    victory_points: or_standard(rules.victory_points, standard.victory_points),
+    discard_limit: or_standard(rules.discard_limit, standard.discard_limit),
+    friendly_robber: rules.friendly_robber,
+    trade_between_rolls: rules.trade_between_rolls,
+    setup_rounds: u8::try_from(or_standard(rules.setup_rounds, u32::from(standard.setup_rounds)))
+      .ok()?,
+    starting_bonus: resources_from(rules.starting_bonus.as_ref()).unwrap_or_default(),
+    dice: dice_from(rules.dice)
+  })
+}
+
+pub fn rules_to(rules: &Rules) -> proto::Rules {
+  proto::Rules {
+    victory_points: rules.victory_points,
+    discard_limit: rules.discard_limit,
+    friendly_robber: rules.friendly_robber,
+    trade_between_rolls: rules.trade_between_rolls,
+    setup_rounds: u32::from(rules.setup_rounds),
+    starting_bonus: Some(resources_to(&rules.starting_bonus)),
+    dice: dice_to(rules.dice)
+  }
+}
+
+pub fn board_to(board: &Board) -> proto::Board {
+  proto::Board {
+    tiles: board.tiles().map(|(hex, tile)| proto::Tile {
+      hex: Some(hex_to(*hex)),
+      terrain: terrain_to(tile.terrain),
+      token: tile.token.map_or(0, u32::from)
+    }).collect(),
+    harbors: board.harbors().iter().map(|harbor| {
+      let (generic, resource) = match harbor.kind {
+        HarborKind::Generic => (true, proto::Resource::default()),
+        HarborKind::Specific(resource) => (false, resource_to(resource))
+      };
+      proto::Harbor { edge: Some(edge_to(harbor.edge)), generic, resource }
+    }).collect(),
+    robber: Some(hex_to(board.robber())),
+    has_pirate: board.pirate().is_some(),
+    pirate: board.pirate().map(hex_to)
+  }
+}
+
+// Bot observation is sent as is, it is already limited to what its seat knows
+pub fn observation_to(observation: &Observation) -> Outgoing {
+  let vertex_pieces = |kind: BuildingKind| {
+    observation.buildings.iter()
+      .filter(|(_, building)| building.kind == kind)
+      .map(|(vertex, building)| proto::VertexPiece {
+        vertex: Some(vertex_to(*vertex)),
+        seat: seat_to(building.owner)
+      })
+      .collect()
+  };
+  let edge_pieces = |pieces: &[(Edge, usize)]| {
+    pieces.iter()
+      .map(|(edge, owner)| proto::EdgePiece { edge: Some(edge_to(*edge)), seat: seat_to(*owner) })
+      .collect()
+  };
+
+  Outgoing::observation(proto::Observation {
+    seat: seat_to(observation.seat),
+    phase: phase_to(observation.phase),
+    active: seat_to(observation.active),
+    turn: observation.turn,
+    resources: Some(resources_to(&observation.resources)),
+    development_cards: observation.development_cards.iter().map(|card| card_to(*card)).collect(),
+    progress_cards: observation.progress_cards.iter()
+      .map(|card| progress_card_to(*card))
+      .collect(),
+    discard: observation.discard,
+    gold: observation.gold,
+    gift: observation.gift,
+    ratios: Some(resources_to(&observation.ratios)),
+    players: observation.players.iter().map(|player| proto::PlayerView {
+      resources: player.resources,
+      development_cards: player.development_cards,
+      progress_cards: player.progress_cards,
+      knights: player.knights,
+      score: Some(score_to(&player.score)),
+      roads: u32::from(player.roads),
+      settlements: u32::from(player.settlements),
+      cities: u32::from(player.cities),
+      ships: u32::from(player.ships),
+      improvements: player.improvements.iter().copied().map(u32::from).collect()
+    }).collect(),
+    settlements: vertex_pieces(BuildingKind::Settlement),
+    cities: vertex_pieces(BuildingKind::City),
+    roads: edge_pieces(&observation.roads),
+    ships: edge_pieces(&observation.ships),
+    knights: observation.knights.iter().map(|knight| proto::KnightPiece {
+      vertex: Some(vertex_to(knight.vertex)),
+      seat: seat_to(knight.owner),
+      level: u32::from(knight.level),
+      active: knight.active
+    }).collect(),
+    city_walls: observation.city_walls.iter().map(|vertex| vertex_to(*vertex)).collect(),
+    metropolises: Track::ALL.iter().zip(observation.metropolises)
+      .filter_map(|(track, vertex)| {
+        Some(proto::Metropolis { track: track_to(*track), vertex: Some(vertex_to(vertex?)) })
+      })
+      .collect(),
+    robber: Some(hex_to(observation.robber)),
+    has_pirate: observation.pirate.is_some(),
+    pirate: observation.pirate.map(hex_to),
+    bank: Some(resources_to(&observation.bank)),
+    has_offer: observation.offer.is_some(),
+    offer_give: observation.offer.map(|(give, _)| resources_to(&give)),
+    offer_receive: observation.offer.map(|(_, receive)| resources_to(&receive)),
+    barbarians: u32::from(observation.barbarians)
+  })
+}
+
+fn board_from(board: &proto::Board) -> Option<Board> {
+  let tiles = board.tiles.iter()
+    .map(|tile| {
+      let token = if tile.token == 0 { None } else { Some(u8::try_from(tile.token).ok()?) };
+      Some((hex_from(tile.hex.as_ref())?, Tile { terrain: terrain_from(tile.terrain), token }))
+    })
+    .collect::<Option<_>>()?;
+
+  let harbors = board.harbors.iter()
+    .map(|harbor| {
+      let kind = if harbor.generic {
+        HarborKind::Generic
+      } else {
+        HarborKind::Specific(resource_from(harbor.resource))
+      };
+      Some(Harbor { edge: edge_from(harbor.edge.as_ref())?, kind })
+    })
+    .collect::<Option<_>>()?;
+
+  let mut result = Board::new(tiles, harbors, hex_from(board.robber.as_ref())?);
+  if board.has_pirate {
+    result.set_pirate(hex_from(board.pirate.as_ref())?);
+  }
+
+  Some(result)
+}
+
+pub const fn rejection_to(rejection: Rejection) -> RejectionReason {
+  match rejection {
+    Rejection::NotYourTurn => RejectionReason::NOT_YOUR_TURN,
+    Rejection::WrongPhase => RejectionReason::WRONG_PHASE,
+    Rejection::InvalidLocation => RejectionReason::INVALID_LOCATION,
+    Rejection::Occupied => RejectionReason::OCCUPIED,
+    Rejection::TooClose => RejectionReason::TOO_CLOSE,
+    Rejection::NotConnected => RejectionReason::NOT_CONNECTED,
+    Rejection::NotOwned => RejectionReason::NOT_OWNED,
+    Rejection::NotEnoughResources => RejectionReason::NOT_ENOUGH_RESOURCES,
+    Rejection::NoPiecesLeft => RejectionReason::NO_PIECES_LEFT,
+    Rejection::NothingToDiscard => RejectionReason::NOTHING_TO_DISCARD,
+    Rejection::WrongDiscardCount => RejectionReason::WRONG_DISCARD_COUNT,
+    Rejection::InvalidVictim => RejectionReason::INVALID_VICTIM,
+    Rejection::DeckEmpty => RejectionReason::DECK_EMPTY,
+    Rejection::NoCard => RejectionReason::NO_CARD,
+    Rejection::CardAlreadyPlayed => RejectionReason::CARD_ALREADY_PLAYED,
+    Rejection::NoLegalRoads => RejectionReason::NO_LEGAL_ROADS,
+    Rejection::NotEnoughInBank => RejectionReason::NOT_ENOUGH_IN_BANK,
+    Rejection::InvalidTrade => RejectionReason::INVALID_TRADE,
+    Rejection::NoOffer => RejectionReason::NO_OFFER,
+    Rejection::InvalidPartner => RejectionReason::INVALID_PARTNER,
+    Rejection::NotAccepted => RejectionReason::NOT_ACCEPTED,
+    Rejection::PirateBlocks => RejectionReason::PIRATE_BLOCKS,
+    Rejection::ShipAlreadyMoved => RejectionReason::SHIP_ALREADY_MOVED,
+    Rejection::ShipNotMovable => RejectionReason::SHIP_NOT_MOVABLE,
+    Rejection::NothingToChoose => RejectionReason::NOTHING_TO_CHOOSE,
+    Rejection::WrongGoldCount => RejectionReason::WRONG_GOLD_COUNT,
+    Rejection::NothingToGive => RejectionReason::NOTHING_TO_GIVE,
+    Rejection::WrongGiftCount => RejectionReason::WRONG_GIFT_COUNT,
+    Rejection::CommodityNotAllowed => RejectionReason::COMMODITY_NOT_ALLOWED,
+    Rejection::CommodityRequired => RejectionReason::COMMODITY_REQUIRED,
+    Rejection::NoCity => RejectionReason::NO_CITY,
+    Rejection::MaxLevel => RejectionReason::MAX_LEVEL,
+    Rejection::KnightInactive => RejectionReason::KNIGHT_INACTIVE,
+    Rejection::KnightActive => RejectionReason::KNIGHT_ACTIVE,
+    Rejection::KnightBusy => RejectionReason::KNIGHT_BUSY,
+    Rejection::KnightTooWeak => RejectionReason::KNIGHT_TOO_WEAK,
+    Rejection::RobberInactive => RejectionReason::ROBBER_INACTIVE,
+    Rejection::InvalidTarget => RejectionReason::INVALID_TARGET,
+    Rejection::RoadNotOpen => RejectionReason::ROAD_NOT_OPEN,
+    Rejection::NotUndoable => RejectionReason::NOT_UNDOABLE,
+    Rejection::UndoPending => RejectionReason::UNDO_PENDING,
+    Rejection::NoUndoRequest => RejectionReason::NO_UNDO_REQUEST,
+    Rejection::AlreadyVoted => RejectionReason::ALREADY_VOTED
+  }
+}
+
+pub const fn rejection(reason: RejectionReason) -> Outgoing {
+  Outgoing::rejection(proto::Rejection { reason })
+}
+
+// Return None for messages, which are not game actions, or contain invalid coordinates
+pub fn action_from(message: &Incoming) -> Option<Action> {
+  Some(match message {
+    Incoming::roll_dice(_) => Action::RollDice,
+    Incoming::build_road(build) => Action::BuildRoad(edge_from(build.edge.as_ref())?),
+    Incoming::build_settlement(build) => {
+      Action::BuildSettlement(vertex_from(build.vertex.as_ref())?)
+    },
+    Incoming::build_city(build) => Action::BuildCity(vertex_from(build.vertex.as_ref())?),
+    Incoming::end_turn(_) => Action::EndTurn,
+    Incoming::discard(discard) => Action::Discard(resources_from(discard.resources.as_ref())?),
+    Incoming::move_robber(move_robber) => Action::MoveRobber {
+      hex: hex_from(move_robber.hex.as_ref())?,
+      victim: if move_robber.steal { Some(usize::try_from(move_robber.victim).ok()?) } else { None }
+    },
+    Incoming::buy_development_card(_) => Action::BuyDevelopmentCard,
+    Incoming::play_knight(_) => Action::PlayKnight,
+    Incoming::play_road_building(_) => Action::PlayRoadBuilding,
+    Incoming::play_year_of_plenty(play) => {
+      Action::PlayYearOfPlenty([resource_from(play.first), resource_from(play.second)])
+    },
+    Incoming::play_monopoly(play) => Action::PlayMonopoly(resource_from(play.resource)),
+    Incoming::offer_trade(offer) => Action::OfferTrade {
+      give: resources_from(offer.give.as_ref())?,
+      receive: resources_from(offer.receive.as_ref())?
+    },
+    Incoming::accept_trade(_) => Action::AcceptTrade,
+    Incoming::reject_trade(_) => Action::RejectTrade,
+    Incoming::counter_trade(counter) => Action::CounterTrade {
+      give: resources_from(counter.give.as_ref())?,
+      receive: resources_from(counter.receive.as_ref())?
+    },
+    Incoming::confirm_trade(confirm) => Action::ConfirmTrade {
+      partner: usize::try_from(confirm.partner).ok()?
+    },
+    Incoming::cancel_trade(_) => Action::CancelTrade,
+    Incoming::maritime_trade(trade) => Action::MaritimeTrade {
+      give: resource_from(trade.give),
+      receive: resource_from(trade.receive)
+    },
+    Incoming::build_ship(build) => Action::BuildShip(edge_from(build.edge.as_ref())?),
+    Incoming::move_ship(move_ship) => Action::MoveShip {
+      from: edge_from(move_ship.from.as_ref())?,
+      to: edge_from(move_ship.to.as_ref())?
+    },
+    Incoming::choose_gold(choose) => {
+      Action::ChooseGold(resources_from(choose.resources.as_ref())?)
+    },
+    Incoming::build_city_wall(build) => {
+      Action::BuildCityWall(vertex_from(build.vertex.as_ref())?)
+    },
+    Incoming::improve_city(improve) => Action::ImproveCity(track_from(improve.track)),
+    Incoming::build_knight(build) => Action::BuildKnight(vertex_from(build.vertex.as_ref())?),
+    Incoming::promote_knight(promote) => {
+      Action::PromoteKnight(vertex_from(promote.vertex.as_ref())?)
+    },
+    Incoming::activate_knight(activate) => {
+      Action::ActivateKnight(vertex_from(activate.vertex.as_ref())?)
+    },
+    Incoming::move_knight(move_knight) => Action::MoveKnight {
+      from: vertex_from(move_knight.from.as_ref())?,
+      to: vertex_from(move_knight.to.as_ref())?
+    },
+    Incoming::chase_robber(chase) => Action::ChaseRobber(vertex_from(chase.vertex.as_ref())?),
+    Incoming::play_progress_card(play) => Action::PlayProgressCard {
+      card: progress_card_from(play.card),
+      target: target_from(&play.target)?
+    },
+    Incoming::give_cards(give) => Action::GiveCards(resources_from(give.resources.as_ref())?),
+    Incoming::remove_knight(remove) => {
+      Action::RemoveKnight(vertex_from(remove.vertex.as_ref())?)
+    },
+    Incoming::take_cards(take) => Action::TakeCards(resources_from(take.resources.as_ref())?),
+    _ => return None
+  })
+}
+
+fn target_to(target: Target) -> OneOftarget {
+  match target {
+    Target::None => OneOftarget::None,
+    Target::Dice([first, second]) => OneOftarget::dice(proto::Dice {
+      first: u32::from(first),
+      second: u32::from(second)
+    }),
+    Target::Hex(hex) => OneOftarget::hex(hex_to(hex)),
+    Target::Hexes(first, second) => OneOftarget::hexes(proto::HexPair {
+      first: Some(hex_to(first)),
+      second: Some(hex_to(second))
+    }),
+    Target::Vertex(vertex) => OneOftarget::vertex(vertex_to(vertex)),
+    Target::Resource(resource) => OneOftarget::resource(resource_to(resource)),
+    Target::Seat(seat) => OneOftarget::seat(seat_to(seat)),
+    Target::Vertices(first, second) => OneOftarget::vertices(proto::VertexPair {
+      first: Some(vertex_to(first)),
+      has_second: second.is_some(),
+      second: second.map(vertex_to)
+    }),
+    Target::Edges(first, second) => OneOftarget::edges(proto::EdgePair {
+      first: Some(edge_to(first)),
+      has_second: second.is_some(),
+      second: second.map(edge_to)
+    }),
+    Target::SeatVertex(seat, vertex) => OneOftarget::seat_vertex(proto::SeatVertex {
+      seat: seat_to(seat),
+      has_vertex: vertex.is_some(),
+      vertex: vertex.map(vertex_to)
+    }),
+    Target::Offers(offers) => OneOftarget::offers(proto::HarborOffers {
+      offers: offers.iter().enumerate()
+        .filter_map(|(seat, offer)| {
+          Some(proto::HarborOffer { seat: seat_to(seat), resource: resource_to((*offer)?) })
+        })
+        .collect()
+    })
+  }
+}
+
+// Actions made by server for players are sent and stored as client messages
+// Single arm for every action, so length grows with actions count
+#[allow(clippy::too_many_lines)]
+pub fn action_to(action: Action) -> Incoming {
+  match action {
+    Action::RollDice => Incoming::roll_dice(proto::RollDice {}),
+    Action::BuildRoad(edge) => Incoming::build_road(proto::BuildRoad { edge: Some(edge_to(edge)) }),
+    Action::BuildSettlement(vertex) => {
+      Incoming::build_settlement(proto::BuildSettlement { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::BuildCity(vertex) => {
+      Incoming::build_city(proto::BuildCity { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::EndTurn => Incoming::end_turn(proto::EndTurn {}),
+    Action::Discard(resources) => {
+      Incoming::discard(proto::Discard { resources: Some(resources_to(&resources)) })
+    },
+    Action::MoveRobber { hex, victim } => Incoming::move_robber(proto::MoveRobber {
+      hex: Some(hex_to(hex)),
+      steal: victim.is_some(),
+      victim: victim.map_or(0, seat_to)
+    }),
+    Action::BuyDevelopmentCard => Incoming::buy_development_card(proto::BuyDevelopmentCard {}),
+    Action::PlayKnight => Incoming::play_knight(proto::PlayKnight {}),
+    Action::PlayRoadBuilding => Incoming::play_road_building(proto::PlayRoadBuilding {}),
+    Action::PlayYearOfPlenty([first, second]) => {
+      Incoming::play_year_of_plenty(proto::PlayYearOfPlenty {
+        first: resource_to(first),
+        second: resource_to(second)
+      })
+    },
+    Action::PlayMonopoly(resource) => {
+      Incoming::play_monopoly(proto::PlayMonopoly { resource: resource_to(resource) })
+    },
+    Action::OfferTrade { give, receive } => Incoming::offer_trade(proto::OfferTrade {
+      give: Some(resources_to(&give)),
+      receive: Some(resources_to(&receive))
+    }),
+    Action::AcceptTrade => Incoming::accept_trade(proto::AcceptTrade {}),
+    Action::RejectTrade => Incoming::reject_trade(proto::RejectTrade {}),
+    Action::CounterTrade { give, receive } => Incoming::counter_trade(proto::CounterTrade {
+      give: Some(resources_to(&give)),
+      receive: Some(resources_to(&receive))
+    }),
+    Action::ConfirmTrade { partner } => {
+      Incoming::confirm_trade(proto::ConfirmTrade { partner: seat_to(partner) })
+    },
+    Action::CancelTrade => Incoming::cancel_trade(proto::CancelTrade {}),
+    Action::MaritimeTrade { give, receive } => Incoming::maritime_trade(proto::MaritimeTrade {
+      give: resource_to(give),
+      receive: resource_to(receive)
+    }),
+    Action::BuildShip(edge) => Incoming::build_ship(proto::BuildShip { edge: Some(edge_to(edge)) }),
+    Action::MoveShip { from, to } => Incoming::move_ship(proto::MoveShip {
+      from: Some(edge_to(from)),
+      to: Some(edge_to(to))
+    }),
+    Action::ChooseGold(resources) => {
+      Incoming::choose_gold(proto::ChooseGold { resources: Some(resources_to(&resources)) })
+    },
+    Action::BuildCityWall(vertex) => {
+      Incoming::build_city_wall(proto::BuildCityWall { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::ImproveCity(track) => {
+      Incoming::improve_city(proto::ImproveCity { track: track_to(track) })
+    },
+    Action::BuildKnight(vertex) => {
+      Incoming::build_knight(proto::BuildKnight { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::PromoteKnight(vertex) => {
+      Incoming::promote_knight(proto::PromoteKnight { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::ActivateKnight(vertex) => {
+      Incoming::activate_knight(proto::ActivateKnight { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::MoveKnight { from, to } => Incoming::move_knight(proto::MoveKnight {
+      from: Some(vertex_to(from)),
+      to: Some(vertex_to(to))
+    }),
+    Action::ChaseRobber(vertex) => {
+      Incoming::chase_robber(proto::ChaseRobber { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::PlayProgressCard { card, target } => {
+      Incoming::play_progress_card(proto::PlayProgressCard {
+        card: progress_card_to(card),
+        target: target_to(target)
+      })
+    },
+    Action::GiveCards(resources) => {
+      Incoming::give_cards(proto::GiveCards { resources: Some(resources_to(&resources)) })
+    },
+    Action::RemoveKnight(vertex) => {
+      Incoming::remove_knight(proto::RemoveKnight { vertex: Some(vertex_to(vertex)) })
+    },
+    Action::TakeCards(resources) => {
+      Incoming::take_cards(proto::TakeCards { resources: Some(resources_to(&resources)) })
+    }
+  }
+}
+
+// Single arm for every event, so length grows with events count
+#[allow(clippy::too_many_lines)]
+pub fn event_to(event: &Event) -> Outgoing {
+  match *event {
+    Event::PhaseChanged { seat, phase, turn } => Outgoing::phase_changed(proto::PhaseChanged {
+      seat: seat_to(seat),
+      phase: phase_to(phase),
+      turn
+    }),
+    Event::DiceRolled { seat, dice: [first, second] } => {
+      Outgoing::dice_rolled(proto::DiceRolled {
+        seat: seat_to(seat),
+        first: u32::from(first),
+        second: u32::from(second)
+      })
+    },
+    Event::ResourcesProduced { seat, resources } => {
+      Outgoing::resources_produced(proto::ResourcesProduced {
+        seat: seat_to(seat),
+        resources: Some(resources_to(&resources))
+      })
+    },
+    Event::ProductionBlocked { resource } => {
+      Outgoing::production_blocked(proto::ProductionBlocked { resource: resource_to(resource) })
+    },
+    Event::DiscardRequired { seat, count } => {
+      Outgoing::discard_required(proto::DiscardRequired { seat: seat_to(seat), count })
+    },
+    Event::Discarded { seat, resources } => Outgoing::discarded(proto::Discarded {
+      seat: seat_to(seat),
+      resources: Some(resources_to(&resources))
+    }),
+    Event::RobberMoved { seat, hex } => Outgoing::robber_moved(proto::RobberMoved {
+      seat: seat_to(seat),
+      hex: Some(hex_to(hex))
+    }),
+    Event::CardStolen { thief, victim, resource } => Outgoing::card_stolen(proto::CardStolen {
+      thief: seat_to(thief),
+      victim: seat_to(victim),
+      revealed: resource.is_some(),
+      resource: resource.map_or_else(proto::Resource::default, resource_to)
+    }),
+    Event::RoadBuilt { seat, edge } => Outgoing::road_built(proto::RoadBuilt {
+      seat: seat_to(seat),
+      edge: Some(edge_to(edge))
+    }),
+    Event::SettlementBuilt { seat, vertex } => {
+      Outgoing::settlement_built(proto::SettlementBuilt {
+        seat: seat_to(seat),
+        vertex: Some(vertex_to(vertex))
+      })
+    },
+    Event::CityBuilt { seat, vertex } => Outgoing::city_built(proto::CityBuilt {
+      seat: seat_to(seat),
+      vertex: Some(vertex_to(vertex))
+    }),
+    Event::DevelopmentCardBought { seat, card } => {
+      Outgoing::development_card_bought(proto::DevelopmentCardBought {
+        seat: seat_to(seat),
+        revealed: card.is_some(),
+        card: card.map_or_else(proto::DevelopmentCard::default, card_to)
+      })
+    },
+    Event::DevelopmentCardPlayed { seat, card } => {
+      Outgoing::development_card_played(proto::DevelopmentCardPlayed {
+        seat: seat_to(seat),
+        card: card_to(card)
+      })
+    },
+    Event::YearOfPlentyTaken { seat, resources } => {
+      Outgoing::year_of_plenty_taken(proto::YearOfPlentyTaken {
+        seat: seat_to(seat),
+        resources: Some(resources_to(&resources))
+      })
+    },
+    Event::MonopolyTaken { seat, resource, count } => {
+      Outgoing::monopoly_taken(proto::MonopolyTaken {
+        seat: seat_to(seat),
+        resource: resource_to(resource),
+        count
+      })
+    },
+    Event::LongestRoadChanged { seat, length } => {
+      Outgoing::longest_road_changed(proto::LongestRoadChanged {
+        held: seat.is_some(),
+        seat: seat.map_or(0, seat_to),
+        length
+      })
+    },
+    Event::LargestArmyChanged { seat, knights } => {
+      Outgoing::largest_army_changed(proto::LargestArmyChanged { seat: seat_to(seat), knights })
+    },
+    Event::GameFinished { winner, ref scores } => {
+      Outgoing::game_finished(proto::GameFinished {
+        winner: seat_to(winner),
+        scores: scores.iter().map(score_to).collect()
+      })
+    },
+    Event::TradeOffered { seat, give, receive } => {
+      Outgoing::trade_offered(proto::TradeOffered {
+        seat: seat_to(seat),
+        give: Some(resources_to(&give)),
+        receive: Some(resources_to(&receive))
+      })
+    },
+    Event::TradeAccepted { seat } => {
+      Outgoing::trade_accepted(proto::TradeAccepted { seat: seat_to(seat) })
+    },
+    Event::TradeRejected { seat } => {
+      Outgoing::trade_rejected(proto::TradeRejected { seat: seat_to(seat) })
+    },
+    Event::TradeCountered { seat, give, receive } => {
+      Outgoing::trade_countered(proto::TradeCountered {
+        seat: seat_to(seat),
+        give: Some(resources_to(&give)),
+        receive: Some(resources_to(&receive))
+      })
+    },
+    Event::TradeCompleted { seat, partner, give, receive } => {
+      Outgoing::trade_completed(proto::TradeCompleted {
+        seat: seat_to(seat),
+        partner: seat_to(partner),
+        give: Some(resources_to(&give)),
+        receive: Some(resources_to(&receive))
+      })
+    },
+    Event::TradeCancelled { seat } => {
+      Outgoing::trade_cancelled(proto::TradeCancelled { seat: seat_to(seat) })
+    },
+    Event::MaritimeTraded { seat, give, receive } => {
+      Outgoing::maritime_traded(proto::MaritimeTraded {
+        seat: seat_to(seat),
+        give: Some(resources_to(&give)),
+        receive: Some(resources_to(&receive))
+      })
+    },
+    Event::TradeRatiosChanged { seat, ratios } => {
+      Outgoing::trade_ratios_changed(proto::TradeRatiosChanged {
+        seat: seat_to(seat),
+        ratios: Some(resources_to(&ratios))
+      })
+    },
+    Event::ShipBuilt { seat, edge } => Outgoing::ship_built(proto::ShipBuilt {
+      seat: seat_to(seat),
+      edge: Some(edge_to(edge))
+    }),
+    Event::ShipMoved { seat, from, to } => Outgoing::ship_moved(proto::ShipMoved {
+      seat: seat_to(seat),
+      from: Some(edge_to(from)),
+      to: Some(edge_to(to))
+    }),
+    Event::PirateMoved { seat, hex } => Outgoing::pirate_moved(proto::PirateMoved {
+      seat: seat_to(seat),
+      hex: Some(hex_to(hex))
+    }),
+    Event::GoldRequired { seat, count } => {
+      Outgoing::gold_required(proto::GoldRequired { seat: seat_to(seat), count })
+    },
+    Event::IslandDiscovered { seat } => {
+      Outgoing::island_discovered(proto::IslandDiscovered { seat: seat_to(seat) })
+    },
+    Event::EventDieRolled { seat, gate } => {
+      Outgoing::event_die_rolled(proto::EventDieRolled {
+        seat: seat_to(seat),
+        barbarians: gate.is_none(),
+        gate: gate.map_or_else(proto::Track::default, track_to)
+      })
+    },
+    Event::BarbariansMoved { position } => {
+      Outgoing::barbarians_moved(proto::BarbariansMoved { position: u32::from(position) })
+    },
+    Event::BarbariansAttacked { strength, defense } => {
+      Outgoing::barbarians_attacked(proto::BarbariansAttacked { strength, defense })
+    },
+    Event::CityPillaged { seat, vertex, removed } => {
+      Outgoing::city_pillaged(proto::CityPillaged {
+        seat: seat_to(seat),
+        vertex: Some(vertex_to(vertex)),
+        removed
+      })
+    },
+    Event::DefenderOfCatan { seat } => {
+      Outgoing::defender_of_catan(proto::DefenderOfCatan { seat: seat_to(seat) })
+    },
+    Event::CityWallBuilt { seat, vertex } => Outgoing::city_wall_built(proto::CityWallBuilt {
+      seat: seat_to(seat),
+      vertex: Some(vertex_to(vertex))
+    }),
+    Event::CityImproved { seat, track, level } => {
+      Outgoing::city_improved(proto::CityImproved {
+        seat: seat_to(seat),
+        track: track_to(track),
+        level: u32::from(level)
+      })
+    },
+    Event::MetropolisChanged { seat, track, vertex } => {
+      Outgoing::metropolis_changed(proto::MetropolisChanged {
+        seat: seat_to(seat),
+        track: track_to(track),
+        vertex: Some(vertex_to(vertex))
+      })
+    },
+    Event::ProgressCardDrawn { seat, track, card } => {
+      Outgoing::progress_card_drawn(proto::ProgressCardDrawn {
+        seat: seat_to(seat),
+        track: track_to(track),
+        revealed: card.is_some(),
+        card: card.map_or_else(proto::ProgressCard::default, progress_card_to)
+      })
+    },
+    Event::ProgressCardPlayed { seat, card } => {
+      Outgoing::progress_card_played(proto::ProgressCardPlayed {
+        seat: seat_to(seat),
+        card: progress_card_to(card)
+      })
+    },
+    Event::ProgressCardStolen { thief, victim, card } => {
+      Outgoing::progress_card_stolen(proto::ProgressCardStolen {
+        thief: seat_to(thief),
+        victim: seat_to(victim),
+        revealed: card.is_some(),
+        card: card.map_or_else(proto::ProgressCard::default, progress_card_to)
+      })
+    },
+    Event::KnightBuilt { seat, vertex } => Outgoing::knight_built(proto::KnightBuilt {
+      seat: seat_to(seat),
+      vertex: Some(vertex_to(vertex))
+    }),
+    Event::KnightPromoted { seat, vertex, level } => {
+      Outgoing::knight_promoted(proto::KnightPromoted {
+        seat: seat_to(seat),
+        vertex: Some(vertex_to(vertex)),
+        level: u32::from(level)
+      })
+    },
+    Event::KnightActivated { seat, vertex } => {
+      Outgoing::knight_activated(proto::KnightActivated {
+        seat: seat_to(seat),
+        vertex: Some(vertex_to(vertex))
+      })
+    },
+    Event::KnightMoved { seat, from, to } => Outgoing::knight_moved(proto::KnightMoved {
+      seat: seat_to(seat),
+      from: Some(vertex_to(from)),
+      to: Some(vertex_to(to))
+    }),
+    Event::KnightDisplaced { seat, from, to } => {
+      Outgoing::knight_displaced(proto::KnightDisplaced {
+        seat: seat_to(seat),
+        from: Some(vertex_to(from)),
+        placed: to.is_some(),
+        to: to.map(vertex_to)
+      })
+    },
+    Event::RobberChased { seat, vertex } => Outgoing::robber_chased(proto::RobberChased {
+      seat: seat_to(seat),
+      vertex: Some(vertex_to(vertex))
+    }),
+    Event::MerchantPlaced { seat, hex } => Outgoing::merchant_placed(proto::MerchantPlaced {
+      seat: seat_to(seat),
+      hex: Some(hex_to(hex))
+    }),
+    Event::TokensSwapped { first, second } => {
+      Outgoing::tokens_swapped(proto::TokensSwapped {
+        first: Some(hex_to(first)),
+        second: Some(hex_to(second))
+      })
+    },
+    Event::GiftRequired { seat, count } => {
+      Outgoing::gift_required(proto::GiftRequired { seat: seat_to(seat), count })
+    },
+    Event::CardsGiven { seat, receiver, count, resources } => {
+      Outgoing::cards_given(proto::CardsGiven {
+        seat: seat_to(seat),
+        receiver: seat_to(receiver),
+        count,
+        revealed: resources.is_some(),
+        resources: resources.as_ref().map(resources_to)
+      })
+    },
+    Event::HandShown { seat, viewer, resources } => Outgoing::hand_shown(proto::HandShown {
+      seat: seat_to(seat),
+      viewer: seat_to(viewer),
+      revealed: resources.is_some(),
+      resources: resources.as_ref().map(resources_to)
+    }),
+    Event::RoadRemoved { seat, edge } => Outgoing::road_removed(proto::RoadRemoved {
+      seat: seat_to(seat),
+      edge: Some(edge_to(edge))
+    }),
+    Event::KnightRemoved { seat, vertex } => Outgoing::knight_removed(proto::KnightRemoved {
+      seat: seat_to(seat),
+      vertex: Some(vertex_to(vertex))
+    }),
+    Event::KnightPlaced { seat, vertex, level, active } => {
+      Outgoing::knight_placed(proto::KnightPlaced {
+        seat: seat_to(seat),
+        vertex: Some(vertex_to(vertex)),
+        level: u32::from(level),
+        active
+      })
+    },
+    Event::UndoRequested { seat } => {
+      Outgoing::undo_requested(proto::UndoRequested { seat: seat_to(seat) })
+    },
+    Event::UndoVoted { seat, approve } => {
+      Outgoing::undo_voted(proto::UndoVoted { seat: seat_to(seat), approve })
+    },
+    Event::UndoFinished { seat, approved } => {
+      Outgoing::undo_finished(proto::UndoFinished { seat: seat_to(seat), approved })
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,201 @@
+use log::{ debug, error, info };
+use sea_orm::{
+  ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+  QueryFilter, QueryOrder
+};
+use std::{ collections::HashMap, time::{ SystemTime, UNIX_EPOCH } };
+use tokio::sync::mpsc::{ UnboundedReceiver, UnboundedSender, unbounded_channel };
+use crate::{
+  db::entities::{ game, game_action },
+  game::Game,
+  lobby::Owner,
+  messages::{ action_from, decode, decode_owners, decode_setup }
+};
+
+// Changes of games log in order they happened in lobby, games are identified by database ids,
+// which lobby gives them at start, so it knows id without waiting for database
+pub enum Record {
+  Started { id: u32, room: u32, setup: Vec<u8>, seats: Vec<u8> },
+  Applied { id: u32, seat: u8, action: Vec<u8> },
+  // Last not undone action is rolled back
+  Undone { id: u32 },
+  Finished { id: u32 }
+}
+
+pub type Sender = UnboundedSender<Record>;
+pub type Receiver = UnboundedReceiver<Record>;
+
+// Unfinished game rebuilt from database, it keeps room id, so players find it again
+pub struct Restored {
+  pub id: u32,
+  pub room: u32,
+  // Recorded actions count, including undone ones
+  pub actions: u32,
+  pub game: Game,
+  // Owner of every seat
+  pub owners: Vec<Owner>
+}
+
+// Writes games log to database in background, so lobby never waits for database
+pub struct Recorder {
+  db: DatabaseConnection,
+  receiver: Receiver,
+  // Number of next action of every unfinished game
+  actions: HashMap<u32, u32>
+}
+
+impl Recorder {
+  pub fn new(db: DatabaseConnection, restored: &[Restored]) -> (Self, Sender) {
+    let (sender, receiver) = unbounded_channel();
+
+    let recorder = Self {
+      db,
+      receiver,
+      actions: restored.iter().map(|restored| (restored.id, restored.actions)).collect()
+    };
+
+    (recorder, sender)
+  }
+
+  // Runs until lobby with sender is dropped, so records received before shutdown are written
+  pub async fn run(&mut self) {
+    while let Some(record) = self.receiver.recv().await {
+      if let Err(err) = self.write(record).await {
+        error!("Write game record error: {err}");
+      }
+    }
+
+    debug!("Graceful recorder shutdown");
+  }
+
+  async fn write(&mut self, record: Record) -> Result<(), DbErr> {
+    match record {
+      Record::Started { id, room, setup, seats } => {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH)
+          .map_or(0, |duration| i64::try_from(duration.as_secs()).unwrap_or(i64::MAX));
+
+        game::ActiveModel {
+          id: Set(id),
+          room: Set(room),
+          setup: Set(setup),
+          seats: Set(seats),
+          started: Set(started),
+          finished: Set(false)
+        }.insert(&self.db).await?;
+
+        self.actions.insert(id, 0);
+      },
+      Record::Applied { id, seat, action } => {
+        let next = self.next_action(id)?;
+        let number = *next;
+        // Number is taken, even if insert fails, so next actions keep their order
+        *next += 1;
+
+        game_action::ActiveModel {
+          game_id: Set(id),
+          number: Set(number),
+          seat: Set(seat),
+          action: Set(action),
+          undone: Set(false)
+        }.insert(&self.db).await?;
+      },
+      Record::Undone { id } => {
+        self.next_action(id)?;
+
+        let last = game_action::Entity::find()
+          .filter(game_action::Column::GameId.eq(id))
+          .filter(game_action::Column::Undone.eq(false))
+          .order_by_desc(game_action::Column::Number)
+          .one(&self.db).await?
+          .ok_or_else(|| DbErr::Custom(format!("game {id} has no action to undo")))?;
+
+        let mut last: game_action::ActiveModel = last.into();
+        last.undone = Set(true);
+        last.update(&self.db).await?;
+      },
+      Record::Finished { id } => {
+        self.next_action(id)?;
+
+        game::ActiveModel {
+          id: Set(id),
+          finished: Set(true),
+          ..Default::default()
+        }.update(&self.db).await?;
+
+        self.actions.remove(&id);
+      }
+    }
+
+    Ok(())
+  }
+
+  fn next_action(&mut self, id: u32) -> Result<&mut u32, DbErr> {
+    self.actions.get_mut(&id)
+      .ok_or_else(|| DbErr::Custom(format!("game {id} is not recorded")))
+  }
+}
+
+// Rebuild unfinished games by replaying their logs, broken games are skipped with error
+pub async fn restore(db: &DatabaseConnection) -> Result<Vec<Restored>, DbErr> {
+  let games = game::Entity::find()
+    .filter(game::Column::Finished.eq(false))
+    .all(db).await?;
+
+  let mut restored = Vec::with_capacity(games.len());
+  for model in games {
+    let actions = find_actions(db, model.id).await?;
+
+    let Some(setup) = decode_setup(&model.setup) else {
+      error!("Game {} restore error: malformed setup", model.id);
+      continue
+    };
+
+    let owners = decode_owners(&model.seats).filter(|owners| owners.len() == setup.players);
+    let Some(owners) = owners else {
+      error!("Game {} restore error: malformed seats", model.id);
+      continue
+    };
+
+    let log = actions.iter()
+      .filter(|action| !action.undone)
+      .map(|action| {
+        let message = decode(&action.action)?;
+        Some((usize::from(action.seat), action_from(&message)?))
+      })
+      .collect::<Option<Vec<_>>>();
+    let Some(log) = log else {
+      error!("Game {} restore error: malformed action", model.id);
+      continue
+    };
+
+    match Game::replay(setup, &log) {
+      Ok((game, _)) => {
+        info!("Game {} restored with {} actions", model.id, log.len());
+        let actions = u32::try_from(actions.len()).unwrap_or(u32::MAX);
+        restored.push(Restored { id: model.id, room: model.room, actions, game, owners });
+      },
+      Err(rejection) => error!("Game {} restore error: action rejected {rejection:?}", model.id)
+    }
+  }
+
+  Ok(restored)
+}
+
+// Id of last recorded game, so lobby continues numbering of games after restart
+pub async fn last_game_id(db: &DatabaseConnection) -> Result<u32, DbErr> {
+  let last = game::Entity::find()
+    .order_by_desc(game::Column::Id)
+    .one(db).await?;
+
+  Ok(last.map_or(0, |model| model.id))
+}
+
+// Actions of game in log order
+pub async fn find_actions(
+  db: &DatabaseConnection, id: u32
+) -> Result<Vec<game_action::Model>, DbErr> {
+  game_action::Entity::find()
+    .filter(game_action::Column::GameId.eq(id))
+    .order_by_asc(game_action::Column::Number)
+    .all(db).await
+}
\ No newline at end of file
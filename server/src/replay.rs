@@ -0,0 +1,95 @@
+use sea_orm::{ ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter };
+use crate::{
+  db::entities::game,
+  game::Game,
+  messages::{
+    action_from, decode, decode_setup, event_to, game_started_to, setup_from, setup_to
+  },
+  protos::{
+    game::{ ClientMessage, ServerMessage },
+    replay::{
+      mod_StepReplayParams::OneOfposition, Replay, ReplayAction, ReplayHeader,
+      StepReplayParams, StepReplayResult
+    }
+  },
+  recorder::find_actions
+};
+
+// Increase on incompatible changes of replay format or game rules
+pub const REPLAY_VERSION: u32 = 1;
+
+// Finished game by its database id, None if there is no such finished game
+pub async fn export(db: &DatabaseConnection, id: u32) -> Result<Option<Replay>, DbErr> {
+  let model = game::Entity::find_by_id(id)
+    .filter(game::Column::Finished.eq(true))
+    .one(db).await?;
+  let Some(model) = model else { return Ok(None) };
+
+  let malformed = || DbErr::Custom(format!("game {} record is malformed", model.id));
+
+  let setup = decode_setup(&model.setup).ok_or_else(malformed)?;
+
+  let actions = find_actions(db, model.id).await?.into_iter()
+    .filter(|action| !action.undone)
+    .map(|action| {
+      Some(ReplayAction {
+        seat: u32::from(action.seat),
+        message: Some(ClientMessage { message: decode(&action.action)? })
+      })
+    })
+    .collect::<Option<Vec<_>>>()
+    .ok_or_else(malformed)?;
+
+  let header = ReplayHeader { version: REPLAY_VERSION, game_id: model.id, started: model.started };
+  Ok(Some(Replay {
+    header: Some(header),
+    setup: Some(setup_to(&setup)),
+    actions
+  }))
+}
+
+// Rebuild game of replay up to position, None for replay of other version or broken replay
+pub fn step(params: &StepReplayParams) -> Option<StepReplayResult> {
+  let replay = params.replay.as_ref()?;
+  if replay.header.as_ref()?.version != REPLAY_VERSION {
+    return None
+  }
+
+  let setup = setup_from(replay.setup.as_ref()?)?;
+  let log = replay.actions.iter()
+    .map(|action| {
+      let message = &action.message.as_ref()?.message;
+      Some((usize::try_from(action.seat).ok()?, action_from(message)?))
+    })
+    .collect::<Option<Vec<_>>>()?;
+
+  let mut game = Game::new(setup);
+  let mut events = game.start_events();
+  let mut last_step_events = events.len();
+  let mut step = 0;
+
+  for (seat, action) in log.iter().copied() {
+    let reached = match params.position {
+      OneOfposition::step(position) => step >= position,
+      OneOfposition::turn(turn) => game.turn() >= turn,
+      OneOfposition::None => true
+    };
+    if reached {
+      break
+    }
+
+    let step_events = game.apply(seat, action).ok()?;
+    last_step_events = step_events.len();
+    events.extend(step_events);
+    step += 1;
+  }
+
+  Some(StepReplayResult {
+    game: Some(game_started_to(game.setup())),
+    step,
+    steps: u32::try_from(log.len()).ok()?,
+    turn: game.turn(),
+    events: events.iter().map(|event| ServerMessage { message: event_to(event) }).collect(),
+    last_step_events: u32::try_from(last_step_events).ok()?
+  })
+}
\ No newline at end of file
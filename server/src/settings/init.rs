@@ -68,7 +68,6 @@ fn try_add_file_source(
   builder.add_source(File::with_name(path_str))
 }
 
-#[cfg(not(feature = "client_resources_packing"))]
 fn prepare_check_path(path_string: &String, must_be_file: bool) -> String {
   let mut path = PathBuf::from(&path_string);
 
@@ -103,11 +102,18 @@ fn default(settings: &mut Settings) {
   settings.database.acquire_timeout = settings.database.acquire_timeout.or(Some(10));
   settings.database.idle_timeout = settings.database.idle_timeout.or(Some(10));
   settings.database.max_lifetime = settings.database.max_lifetime.or(Some(10));
+
+  settings.timers.turn = settings.timers.turn.or(Some(120));
+  settings.timers.decision = settings.timers.decision.or(Some(30));
+
+  settings.bots.rate_limit = settings.bots.rate_limit.or(Some(10));
 }
 
-#[cfg(not(feature = "client_resources_packing"))]
 fn check(settings: &mut Settings) {
-  settings.client_resources_path = prepare_check_path(&settings.client_resources_path, false);
+  #[cfg(not(feature = "client_resources_packing"))]
+  (settings.client_resources_path = prepare_check_path(&settings.client_resources_path, false));
+
+  settings.maps_path = settings.maps_path.as_ref().map(|path| prepare_check_path(path, false));
 
   #[cfg(feature = "secure_server")]
   (settings.secure_server.cert_path = prepare_check_path(&settings.secure_server.cert_path, true));
@@ -152,7 +158,6 @@ pub fn init() -> Settings {
 
   default(&mut settings);
 
-  #[cfg(not(feature = "client_resources_packing"))]
   check(&mut settings);
 
   settings
@@ -12,6 +12,21 @@ pub struct Database {
   pub max_lifetime: Option<u64>
 }
 
+// Seconds to act, 0 disables timer
+#[derive(Debug, Default, Deserialize)]
+pub struct Timers {
+  // Trade and build part of turn
+  pub turn: Option<u64>,
+  // Setup placements, dice roll, discard, robber, free roads and gold choice
+  pub decision: Option<u64>
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Bots {
+  // Messages per second of every bot connection, extra messages are rejected
+  pub rate_limit: Option<u32>
+}
+
 #[cfg(feature = "secure_server")]
 #[derive(Debug, Deserialize)]
 pub struct SecureServer {
@@ -25,7 +40,13 @@ pub struct Settings {
   pub bind_addr: SocketAddr,
   #[cfg(not(feature = "client_resources_packing"))]
   pub client_resources_path: String,
+  // Directory with custom map files, custom maps are disabled, when it is not set
+  pub maps_path: Option<String>,
   pub database: Database,
+  #[serde(default)]
+  pub timers: Timers,
+  #[serde(default)]
+  pub bots: Bots,
   #[cfg(feature = "secure_server")]
   pub secure_server: SecureServer
 }
\ No newline at end of file